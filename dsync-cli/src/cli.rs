@@ -1,5 +1,7 @@
 //! Definition of CLI.
 
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use command::Commands;
@@ -12,8 +14,19 @@ pub(crate) struct Cli {
     #[arg(short = 'l', long, help = "Configure logging level of the client")]
     pub(crate) log_level: Option<log::LevelFilter>,
 
-    #[arg(short = 'p', long, help = "Port the dsync-server is running on")]
-    pub(crate) server_port: Option<u16>,
+    #[arg(
+        short = 'p',
+        long,
+        help = "Port the dsync-server control services listen on (loopback only)"
+    )]
+    pub(crate) control_port: Option<u16>,
+
+    #[arg(
+        short = 's',
+        long,
+        help = "Unix socket the dsync-server control services listen on. Defaults to control.sock in the dsync XDG runtime dir"
+    )]
+    pub(crate) control_socket: Option<PathBuf>,
 
     #[command(subcommand)]
     pub(crate) command: Commands,
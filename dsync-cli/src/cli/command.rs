@@ -1,6 +1,8 @@
+mod conflict;
 mod file;
 mod group;
 mod host;
+mod root;
 mod server;
 
 use crate::command;
@@ -18,6 +20,14 @@ pub(crate) enum Commands {
     #[command(subcommand)]
     File(file::FileCommand),
 
+    /// Track whole directories & sync them with peers
+    #[command(subcommand)]
+    Root(root::RootCommand),
+
+    /// Inspect & resolve files modified concurrently on two hosts
+    #[command(subcommand)]
+    Conflict(conflict::ConflictCommand),
+
     /// Manage & display file groups.
     #[command(subcommand)]
     Group(group::GroupCommand),
@@ -33,12 +43,31 @@ impl Commands {
             Self::Host(subcmd) => match subcmd {
                 host::HostCommand::List { discover: _ } => command::host::host_list(cfg).await,
                 host::HostCommand::Discover => command::host::host_discover(cfg).await,
-                host::HostCommand::Add { ipv4_addr } => {
-                    command::host::host_add(cfg, ipv4_addr).await
+                host::HostCommand::Add { host_addr } => {
+                    command::host::host_add(cfg, host_addr).await
                 }
                 host::HostCommand::Remove { host_spec } => {
                     command::host::host_remove(cfg, host_spec).await
                 }
+                host::HostCommand::Update {
+                    host_spec,
+                    name,
+                    notes,
+                    tags,
+                    untags,
+                } => command::host::host_update(cfg, host_spec, name, notes, tags, untags).await,
+                host::HostCommand::Alias {
+                    host_spec,
+                    alias,
+                    remove,
+                } => command::host::host_alias(cfg, host_spec, alias, remove).await,
+                host::HostCommand::Block { rule, remove } => {
+                    command::host::host_block(cfg, rule, remove).await
+                }
+                host::HostCommand::Blocklist => command::host::host_blocklist(cfg).await,
+                host::HostCommand::Pair { host_spec } => {
+                    command::host::host_pair(cfg, host_spec).await
+                }
             },
             Self::File(subcmd) => match subcmd {
                 file::FileCommand::Add { paths, group_id } => {
@@ -55,8 +84,42 @@ impl Commands {
                     source,
                     destination,
                 } => command::file::file_copy(cfg, source, destination).await,
-                file::FileCommand::Sync => command::file::file_sync(cfg).await,
-                file::FileCommand::Unsync => command::file::file_unsync(cfg).await,
+                file::FileCommand::Share {
+                    path,
+                    host_spec,
+                    access,
+                } => command::file::file_share(cfg, &path, host_spec, access).await,
+                file::FileCommand::Sync { local_id, remote } => {
+                    command::file::file_sync(cfg, local_id, remote).await
+                }
+                file::FileCommand::Unsync {
+                    local_id,
+                    host_spec,
+                } => command::file::file_unsync(cfg, local_id, host_spec).await,
+                file::FileCommand::Rescan => command::file::file_rescan(cfg).await,
+                file::FileCommand::CheckIgnore { path } => {
+                    command::file::file_check_ignore(cfg, &path).await
+                }
+            },
+            Self::Root(subcmd) => match subcmd {
+                root::RootCommand::Add { path } => command::root::root_add(cfg, &path).await,
+                root::RootCommand::Remove { root_id } => {
+                    command::root::root_remove(cfg, root_id).await
+                }
+                root::RootCommand::List { host_spec } => {
+                    command::root::root_list(cfg, host_spec).await
+                }
+                root::RootCommand::Share {
+                    root_id,
+                    host_spec,
+                    access,
+                } => command::root::root_share(cfg, root_id, host_spec, access).await,
+                root::RootCommand::Sync { root_id, remote } => {
+                    command::root::root_sync(cfg, root_id, remote).await
+                }
+                root::RootCommand::Unsync { root_id, host_spec } => {
+                    command::root::root_unsync(cfg, root_id, host_spec).await
+                }
             },
             Self::Group(subcmd) => match subcmd {
                 group::GroupCommand::Create { group_id } => {
@@ -68,6 +131,35 @@ impl Commands {
                 group::GroupCommand::List { remote_id } => {
                     command::group::group_list(cfg, remote_id).await
                 }
+                group::GroupCommand::Share {
+                    group_id,
+                    host_spec,
+                    access,
+                } => command::group::group_share(cfg, group_id, host_spec, access).await,
+                group::GroupCommand::Strategy {
+                    group_id,
+                    strategy,
+                    prefer,
+                } => command::group::group_strategy(cfg, group_id, strategy, prefer).await,
+            },
+            Self::Conflict(subcmd) => match subcmd {
+                conflict::ConflictCommand::List => command::conflict::conflict_list(cfg).await,
+                conflict::ConflictCommand::Show {
+                    local_id,
+                    host_spec,
+                } => command::conflict::conflict_show(cfg, local_id, host_spec).await,
+                conflict::ConflictCommand::Diff {
+                    local_id,
+                    host_spec,
+                    tool,
+                } => command::conflict::conflict_diff(cfg, local_id, host_spec, tool).await,
+                conflict::ConflictCommand::Resolve {
+                    local_id,
+                    resolution,
+                    host_spec,
+                } => {
+                    command::conflict::conflict_resolve(cfg, local_id, resolution, host_spec).await
+                }
             },
             Self::Server(subcmd) => match subcmd {
                 server::ServerCommand::Shutdown {} => command::server::server_shutdown(cfg).await,
@@ -0,0 +1,44 @@
+use clap::Subcommand;
+
+use crate::command::model::ConflictResolution;
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConflictCommand {
+    /// List files modified concurrently on this host & a synced host.
+    List,
+
+    /// Show both versions of a conflicted file.
+    Show {
+        /// Id of the local file, as shown by `conflict list`.
+        local_id: i32,
+
+        /// Host the file conflicts with. Required when it conflicts with more than one.
+        host_spec: Option<String>,
+    },
+
+    /// Compare both versions of a conflicted file.
+    Diff {
+        /// Id of the local file, as shown by `conflict list`.
+        local_id: i32,
+
+        /// Host the file conflicts with. Required when it conflicts with more than one.
+        host_spec: Option<String>,
+
+        /// Open both versions with the given program (e.g. `meld`), instead of printing
+        /// a unified diff.
+        #[arg(short = 't', long = "tool")]
+        tool: Option<String>,
+    },
+
+    /// Pick the version of a conflicted file to keep.
+    Resolve {
+        /// Id of the local file, as shown by `conflict list`.
+        local_id: i32,
+
+        #[arg(value_enum)]
+        resolution: ConflictResolution,
+
+        /// Host the file conflicts with. Required when it conflicts with more than one.
+        host_spec: Option<String>,
+    },
+}
@@ -2,7 +2,7 @@ use clap::Subcommand;
 
 use std::path::PathBuf;
 
-use crate::command::model::{GroupId, RemoteId};
+use crate::command::model::{AccessLevel, GroupId, RemoteId};
 
 #[derive(Subcommand, Debug)]
 pub(crate) enum FileCommand {
@@ -46,6 +46,44 @@ pub(crate) enum FileCommand {
         /// the path is treated as local to the origin host.
         destination: String,
     },
-    Sync,
-    Unsync,
+    /// Grant a paired host access to a tracked file, or revoke it.
+    /// Hosts can not read nor write local files, unless shared with them.
+    Share {
+        /// Path to a local file
+        path: PathBuf,
+
+        /// This might be either the local host id or name.
+        host_spec: String,
+
+        #[arg(short = 'a', long = "access", value_enum, default_value_t = AccessLevel::Read)]
+        access: AccessLevel,
+    },
+    /// Keep a tracked file in sync with a file tracked by a remote host. Whichever side
+    /// changes is transferred to the other one; on the first sync the local file wins.
+    /// The remote host has to grant read-write access to its file.
+    Sync {
+        /// Local id of the tracked file, as shown by `file list`.
+        local_id: i32,
+
+        /// File of the remote host: HOST@REMOTE_ID, where REMOTE_ID is the local id
+        /// of the file on that host.
+        remote: String,
+    },
+    /// Stop syncing a tracked file.
+    Unsync {
+        /// Local id of the tracked file, as shown by `file list`.
+        local_id: i32,
+
+        /// Host to stop syncing with. When not specified, the file is unsynced from all hosts.
+        host_spec: Option<String>,
+    },
+    /// Compare all tracked files with the disk right away, instead of waiting
+    /// for the periodic rescan. Changed files are rehashed.
+    Rescan,
+    /// Tell whether a path of a sync root is ignored & which pattern (of a `.dsyncignore`
+    /// file or of the server config) has decided so.
+    CheckIgnore {
+        /// Path to a local file or directory, it does not need to exist.
+        path: PathBuf,
+    },
 }
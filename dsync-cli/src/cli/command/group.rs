@@ -1,6 +1,6 @@
 use clap::Subcommand;
 
-use crate::command::model::{GroupId, RemoteId};
+use crate::command::model::{AccessLevel, ConflictStrategy, GroupId, RemoteId};
 
 #[derive(Subcommand, Debug)]
 pub(crate) enum GroupCommand {
@@ -16,4 +16,29 @@ pub(crate) enum GroupCommand {
         /// Identifier of remote peer.
         remote_id: Option<RemoteId>,
     },
+
+    /// Grant a paired host access to all files of the group, or revoke it.
+    /// Hosts can not read nor write local files, unless shared with them.
+    Share {
+        group_id: GroupId,
+
+        /// This might be either the local host id or name.
+        host_spec: String,
+
+        #[arg(short = 'a', long = "access", value_enum, default_value_t = AccessLevel::Read)]
+        access: AccessLevel,
+    },
+    /// Set how conflicts of files of the group are resolved as they are detected.
+    /// Files of groups which disagree with each other are left for `conflict resolve`.
+    Strategy {
+        group_id: GroupId,
+
+        #[arg(value_enum)]
+        strategy: ConflictStrategy,
+
+        /// Host whose version wins with the host-priority strategy. Might be repeated,
+        /// the first host wins over the next ones.
+        #[arg(short = 'p', long = "prefer")]
+        prefer: Vec<String>,
+    },
 }
@@ -14,9 +14,9 @@ pub(crate) enum HostCommand {
     /// Manually add a host to the known hosts list. The operation
     /// will succeed only in case the host is reachable when calling it.
     Add {
-        /// Address to try the host discovery at. Accepted syntax: IPV4[:PORT].
-        /// If port is not specified - default will be used.
-        ipv4_addr: String,
+        /// Address to try the host discovery at. Accepted syntax: IPV4[:PORT], IPV6 or
+        /// [IPV6][:PORT]. If port is not specified - default will be used.
+        host_addr: String,
     },
 
     /// Manually remove a host from the known hosts list.
@@ -24,4 +24,62 @@ pub(crate) enum HostCommand {
         /// This might be either the local host id or name.
         host_spec: String,
     },
+
+    /// Edit the display name, notes & tags of a known host.
+    Update {
+        /// This might be either the local host id, name or alias.
+        host_spec: String,
+
+        /// New display name, used instead of the hostname.
+        #[arg(short = 'n', long = "name")]
+        name: Option<String>,
+
+        /// Free form notes, replacing the previous ones.
+        #[arg(long = "notes")]
+        notes: Option<String>,
+
+        /// Tag to add, might be repeated.
+        #[arg(short = 't', long = "tag")]
+        tags: Vec<String>,
+
+        /// Tag to remove, might be repeated.
+        #[arg(long = "untag")]
+        untags: Vec<String>,
+    },
+
+    /// Add an alternative name of a known host, usable wherever a host is specified
+    /// (e.g. `ALIAS@/path`). Aliases are unique across hosts.
+    Alias {
+        /// This might be either the local host id, name or alias.
+        host_spec: String,
+
+        alias: String,
+
+        /// Remove the alias instead.
+        #[arg(short = 'r', long = "remove")]
+        remove: bool,
+    },
+
+    /// Never store nor contact a host, or hosts at an address or in a subnet.
+    /// Known hosts matching the rule are removed.
+    Block {
+        /// Uuid of a host, an IP address or a subnet in CIDR notation (e.g. 10.8.0.0/16).
+        rule: String,
+
+        /// Lift the block instead.
+        #[arg(short = 'r', long = "remove")]
+        remove: bool,
+    },
+
+    /// List blocked hosts, addresses & subnets.
+    Blocklist,
+
+    /// Pair with a known host, so that files might be transferred between the two.
+    /// Both hosts show a verification code, which has to be compared by the user.
+    /// Run w/o host spec on the other host to confirm the pairing there.
+    Pair {
+        /// This might be either the local host id or name. When not specified, pairings
+        /// requested by other hosts are shown for confirmation.
+        host_spec: Option<String>,
+    },
 }
@@ -0,0 +1,56 @@
+use clap::Subcommand;
+
+use std::path::PathBuf;
+
+use crate::command::model::AccessLevel;
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum RootCommand {
+    /// Track a directory with its whole subtree. Files showing up in the directory
+    /// are tracked as well, as long as the root exists.
+    Add {
+        /// Path to a local directory
+        path: PathBuf,
+    },
+    /// Stop tracking the root & all of its files.
+    Remove {
+        /// Id of the root, as shown by `root list`.
+        root_id: i32,
+    },
+    /// List local roots, or roots a remote host has shared with this host.
+    List {
+        /// This might be either the local host id or name.
+        host_spec: Option<String>,
+    },
+    /// Grant a paired host access to all files of the root, or revoke it.
+    /// Hosts can not read nor write local files, unless shared with them.
+    Share {
+        /// Id of the root, as shown by `root list`.
+        root_id: i32,
+
+        /// This might be either the local host id or name.
+        host_spec: String,
+
+        #[arg(short = 'a', long = "access", value_enum, default_value_t = AccessLevel::Read)]
+        access: AccessLevel,
+    },
+    /// Keep a local root in sync with a root of a remote host. Files are matched by
+    /// their paths relative to the roots, files missing on either side are copied over.
+    /// The remote host has to grant read-write access to its root.
+    Sync {
+        /// Id of the local root, as shown by `root list`.
+        root_id: i32,
+
+        /// Root of the remote host: HOST@REMOTE_ROOT_ID, where REMOTE_ROOT_ID is the id
+        /// of the root on that host, as shown by `root list HOST`.
+        remote: String,
+    },
+    /// Stop syncing a root.
+    Unsync {
+        /// Id of the local root, as shown by `root list`.
+        root_id: i32,
+
+        /// Host to stop syncing with. When not specified, the root is unsynced from all hosts.
+        host_spec: Option<String>,
+    },
+}
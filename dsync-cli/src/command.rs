@@ -1,6 +1,8 @@
+pub(crate) mod conflict;
 pub(crate) mod file;
 pub(crate) mod group;
 pub(crate) mod host;
 pub(crate) mod model;
+pub(crate) mod root;
 pub(crate) mod server;
 mod utils;
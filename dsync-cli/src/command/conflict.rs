@@ -0,0 +1,124 @@
+use anyhow::bail;
+use dsync_proto::services::user_agent::{
+    ConflictListRequest, ConflictResolveRequest, ConflictShowRequest, ConflictShowResponse,
+};
+use dsync_shared::{conn::ServiceConnFactory, model::parse_file_source_host_spec};
+
+use crate::command::utils;
+use crate::config::Config;
+
+use super::model::ConflictResolution;
+
+pub(crate) async fn conflict_list(cfg: &Config) -> anyhow::Result<()> {
+    let request = tonic::Request::new(ConflictListRequest {});
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.conflict_list(request).await?.into_inner();
+    if response.conflicts.is_empty() {
+        println!("No conflicts");
+    } else {
+        utils::print_conflicts(&response.conflicts);
+    }
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn conflict_show(
+    cfg: &Config,
+    local_id: i32,
+    host_spec: Option<String>,
+) -> anyhow::Result<()> {
+    let response = show(cfg, local_id, host_spec, false).await?;
+    if let Some(conflict) = response.conflict {
+        utils::print_conflict(&conflict);
+    }
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn conflict_diff(
+    cfg: &Config,
+    local_id: i32,
+    host_spec: Option<String>,
+    tool: Option<String>,
+) -> anyhow::Result<()> {
+    let Some(tool) = tool else {
+        let response = show(cfg, local_id, host_spec, true).await?;
+        print!("{}", response.diff.unwrap_or_default());
+        return anyhow::Ok(());
+    };
+
+    let Some(conflict) = show(cfg, local_id, host_spec, false).await?.conflict else {
+        bail!("Server has not described the conflict");
+    };
+    if !conflict.copy_fetched {
+        bail!("The remote version has not been fetched yet");
+    }
+
+    // The server runs on this machine, both versions are right here.
+    let status = std::process::Command::new(&tool)
+        .arg(&conflict.file_path)
+        .arg(&conflict.copy_path)
+        .status()?;
+    log::debug!("{tool} exited with {status}");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn conflict_resolve(
+    cfg: &Config,
+    local_id: i32,
+    resolution: ConflictResolution,
+    host_spec: Option<String>,
+) -> anyhow::Result<()> {
+    let request = tonic::Request::new(ConflictResolveRequest {
+        local_id,
+        host_spec: host_spec
+            .as_deref()
+            .map(parse_file_source_host_spec)
+            .transpose()?,
+        resolution: dsync_proto::services::user_agent::ConflictResolution::from(resolution).into(),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.conflict_resolve(request).await?.into_inner();
+    match response.kept_path {
+        Some(kept_path) => {
+            println!("Conflict of file {local_id} resolved, remote version kept as {kept_path}")
+        }
+        None => println!("Conflict of file {local_id} resolved, {resolution:?} version kept"),
+    }
+
+    anyhow::Ok(())
+}
+
+async fn show(
+    cfg: &Config,
+    local_id: i32,
+    host_spec: Option<String>,
+    diff: bool,
+) -> anyhow::Result<ConflictShowResponse> {
+    let request = tonic::Request::new(ConflictShowRequest {
+        local_id,
+        host_spec: host_spec
+            .as_deref()
+            .map(parse_file_source_host_spec)
+            .transpose()?,
+        diff,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    Ok(client.conflict_show(request).await?.into_inner())
+}
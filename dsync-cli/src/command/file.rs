@@ -4,14 +4,15 @@ use crate::command::utils;
 use crate::config::Config;
 use anyhow::Context;
 use dsync_proto::services::user_agent::{
-    FileAddRequest, FileCopyRequest, FileListRequest, FileRemoveRequest,
+    FileAddRequest, FileCheckIgnoreRequest, FileCopyRequest, FileListRequest, FileRemoveRequest,
+    FileRescanRequest, FileShareRequest, FileSyncRequest, FileUnsyncRequest,
 };
 use dsync_shared::{
     conn::ServiceConnFactory,
-    model::{FileSourceWrapper, parse_file_source_spec},
+    model::{FileSourceWrapper, parse_file_source_host_spec, parse_file_source_spec},
 };
 
-use super::model::{GroupId, RemoteId};
+use super::model::{AccessLevel, GroupId, RemoteId};
 
 pub(crate) async fn file_add(
     cfg: &Config,
@@ -55,7 +56,7 @@ pub(crate) async fn file_add(
         group_id,
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -92,7 +93,7 @@ pub(crate) async fn file_remove(cfg: &Config, file_path: impl AsRef<Path>) -> an
         group_id: None,
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -105,6 +106,40 @@ pub(crate) async fn file_remove(cfg: &Config, file_path: impl AsRef<Path>) -> an
     anyhow::Ok(())
 }
 
+pub(crate) async fn file_share(
+    cfg: &Config,
+    file_path: impl AsRef<Path>,
+    host_spec: String,
+    access: AccessLevel,
+) -> anyhow::Result<()> {
+    let file_path = file_path.as_ref();
+
+    let file_path_abs = file_path
+        .canonicalize()
+        .with_context(|| format!("Failed to turn file_path: {file_path:?} into absolute path"))?;
+
+    let path_as_string = file_path_abs
+        .to_str()
+        .context("Looks like the specified path is not a valid unicode")?
+        .to_string();
+
+    let request = tonic::Request::new(FileShareRequest {
+        file_path: path_as_string,
+        host_spec: Some(parse_file_source_host_spec(&host_spec)?),
+        access: Some(access.into()),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.file_share(request).await?;
+    println!("Access of {host_spec} to {file_path_abs:?} set to {access:?}");
+
+    anyhow::Ok(())
+}
+
 pub(crate) async fn file_list(
     cfg: &Config,
     remote_id: Option<RemoteId>,
@@ -123,7 +158,7 @@ pub(crate) async fn file_list(
         group_id: None,
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -165,7 +200,7 @@ pub(crate) async fn file_copy(
         dst_spec: Some(file_source_dst.into()),
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -178,10 +213,117 @@ pub(crate) async fn file_copy(
     anyhow::Ok(())
 }
 
-pub(crate) async fn file_sync(_cfg: &crate::config::Config) -> Result<(), anyhow::Error> {
-    todo!()
+pub(crate) async fn file_sync(cfg: &Config, local_id: i32, remote: String) -> anyhow::Result<()> {
+    let Some((host_spec, remote_id)) = remote.rsplit_once('@') else {
+        anyhow::bail!("Expected remote file as HOST@REMOTE_ID, got: {remote}");
+    };
+    let remote_id: i32 = remote_id
+        .parse()
+        .with_context(|| format!("Invalid remote file id: {remote_id}"))?;
+
+    let request = tonic::Request::new(FileSyncRequest {
+        local_id,
+        host_spec: Some(parse_file_source_host_spec(host_spec)?),
+        remote_id,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.file_sync(request).await?;
+    println!("File {local_id} is going to be synced with {remote}");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn file_unsync(
+    cfg: &Config,
+    local_id: i32,
+    host_spec: Option<String>,
+) -> anyhow::Result<()> {
+    let request = tonic::Request::new(FileUnsyncRequest {
+        local_id,
+        host_spec: host_spec
+            .as_deref()
+            .map(parse_file_source_host_spec)
+            .transpose()?,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.file_unsync(request).await?.into_inner();
+    println!("File {local_id} unsynced from {} host(s)", response.removed);
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn file_rescan(cfg: &Config) -> anyhow::Result<()> {
+    let request = tonic::Request::new(FileRescanRequest {});
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.file_rescan(request).await?.into_inner();
+    println!(
+        "Scanned {} file(s): {} modified, {} gone, {} added",
+        response.scanned, response.modified, response.gone, response.added
+    );
+
+    anyhow::Ok(())
 }
 
-pub(crate) async fn file_unsync(_cfg: &crate::config::Config) -> Result<(), anyhow::Error> {
-    todo!()
+pub(crate) async fn file_check_ignore(
+    cfg: &Config,
+    file_path: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let file_path = file_path.as_ref();
+
+    // Roots are stored canonical, the path might not exist though.
+    let file_path_abs = file_path
+        .canonicalize()
+        .or_else(|_| std::path::absolute(file_path))
+        .with_context(|| format!("Failed to turn file_path: {file_path:?} into absolute path"))?;
+
+    let request = tonic::Request::new(FileCheckIgnoreRequest {
+        path: file_path_abs
+            .to_str()
+            .context("Looks like the specified path is not a valid unicode")?
+            .to_string(),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.file_check_ignore(request).await?.into_inner();
+
+    let Some(root_id) = response.root_id else {
+        println!("{file_path_abs:?} is not in any sync root");
+        return anyhow::Ok(());
+    };
+    match response.rule {
+        Some(rule) => {
+            let source = rule.source.as_deref().unwrap_or("server config");
+            let verdict = if response.ignored {
+                "ignored"
+            } else {
+                "not ignored"
+            };
+            println!(
+                "{file_path_abs:?} is {verdict} (root {root_id}), pattern `{}` of {source}",
+                rule.pattern
+            );
+        }
+        None => println!("{file_path_abs:?} is not ignored (root {root_id}), no pattern matches"),
+    }
+
+    anyhow::Ok(())
 }
@@ -1,18 +1,21 @@
 use anyhow::bail;
-use dsync_proto::services::user_agent::{GroupCreateRequest, GroupDeleteRequest, GroupListRequest};
-use dsync_shared::conn::ServiceConnFactory;
+use dsync_proto::services::user_agent::{
+    GroupCreateRequest, GroupDeleteRequest, GroupListRequest, GroupShareRequest,
+    GroupStrategyRequest,
+};
+use dsync_shared::{conn::ServiceConnFactory, model::parse_file_source_host_spec};
 
 use crate::command::utils;
 use crate::config::Config;
 
-use super::model::{GroupId, RemoteId};
+use super::model::{AccessLevel, ConflictStrategy, GroupId, RemoteId};
 
 pub(crate) async fn group_create(cfg: &Config, group_id: GroupId) -> anyhow::Result<()> {
     let request = tonic::Request::new(GroupCreateRequest {
         group_id: group_id.clone(),
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -45,7 +48,7 @@ pub(crate) async fn group_delete(cfg: &Config, group_id: GroupId) -> Result<(),
         group_id: group_id.clone(),
     });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::trace!("Sending group_delete request to server: {:?}", &request);
 
@@ -79,7 +82,7 @@ pub(crate) async fn group_delete(cfg: &Config, group_id: GroupId) -> Result<(),
 pub(crate) async fn group_list(cfg: &Config, remote_id: Option<RemoteId>) -> anyhow::Result<()> {
     let request = tonic::Request::new(GroupListRequest { remote_id });
 
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -98,3 +101,53 @@ pub(crate) async fn group_list(cfg: &Config, remote_id: Option<RemoteId>) -> any
         }
     }
 }
+
+pub(crate) async fn group_share(
+    cfg: &Config,
+    group_id: GroupId,
+    host_spec: String,
+    access: AccessLevel,
+) -> anyhow::Result<()> {
+    let request = tonic::Request::new(GroupShareRequest {
+        group_id: group_id.clone(),
+        host_spec: Some(parse_file_source_host_spec(&host_spec)?),
+        access: Some(access.into()),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.group_share(request).await?;
+    println!("Access of {host_spec} to group '{group_id}' set to {access:?}");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn group_strategy(
+    cfg: &Config,
+    group_id: GroupId,
+    strategy: ConflictStrategy,
+    prefer: Vec<String>,
+) -> anyhow::Result<()> {
+    let host_priority = prefer
+        .iter()
+        .map(|host_spec| parse_file_source_host_spec(host_spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let request = tonic::Request::new(GroupStrategyRequest {
+        group_id: group_id.clone(),
+        strategy: dsync_proto::services::user_agent::ConflictStrategy::from(strategy).into(),
+        host_priority,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.group_strategy(request).await?;
+    println!("Conflicts of group '{group_id}' are resolved by {strategy:?} strategy");
+
+    anyhow::Ok(())
+}
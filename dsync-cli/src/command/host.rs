@@ -1,7 +1,13 @@
-use std::{net::Ipv4Addr, str::FromStr};
+use std::{
+    io::Write,
+    net::{IpAddr, Ipv6Addr},
+    str::FromStr,
+};
 
 use dsync_proto::services::user_agent::{
-    HostAddRequest, HostDiscoverRequest, HostListRequest, HostRemoveRequest,
+    HostAddRequest, HostAliasRequest, HostBlockRequest, HostBlocklistRequest, HostDiscoverRequest,
+    HostListRequest, HostPairConfirmRequest, HostPairPendingRequest, HostPairRequest,
+    HostRemoveRequest, HostUpdateRequest,
 };
 use dsync_shared::{conn::ServiceConnFactory, model::parse_file_source_host_spec};
 
@@ -9,7 +15,7 @@ use crate::command::utils;
 use crate::config::Config;
 
 pub(crate) async fn host_list(cfg: &Config) -> anyhow::Result<()> {
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     let request = tonic::Request::new(HostListRequest { discover: false });
 
@@ -22,41 +28,56 @@ pub(crate) async fn host_list(cfg: &Config) -> anyhow::Result<()> {
     log::debug!("{response:?}");
 
     let response_payload = response.into_inner();
-    utils::print_servers_info(&response_payload.host_list);
+    utils::print_servers_status(&response_payload.host_list);
 
     anyhow::Ok(())
 }
 
 pub(crate) async fn host_discover(cfg: &Config) -> anyhow::Result<()> {
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     let request = tonic::Request::new(HostDiscoverRequest {});
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
 
-    let response = client.host_discover(request).await?;
+    let mut stream = client.host_discover_stream(request).await?.into_inner();
+
+    println!("Looking for hosts...");
+
+    let mut discovered_hosts = Vec::new();
+    while let Some(response) = stream.message().await? {
+        log::debug!("{response:?}");
+        let Some(host_info) = response.host_info else {
+            continue;
+        };
+        println!(
+            "Found {} ({}) at {}",
+            host_info.name,
+            host_info.hostname,
+            utils::format_host_addr(&host_info)
+        );
+        discovered_hosts.push(host_info);
+    }
 
-    log::info!("Received response from server");
-    log::debug!("{response:?}");
-    let response_payload = response.into_inner();
-    utils::print_servers_info(&response_payload.host_list);
+    if discovered_hosts.is_empty() {
+        println!("No hosts found");
+    } else {
+        utils::print_servers_info(&discovered_hosts);
+    }
 
     anyhow::Ok(())
 }
 
 pub(crate) async fn host_add(cfg: &Config, host_addr: String) -> Result<(), anyhow::Error> {
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     let host_addr_spec = parse_host_addr_spec(&host_addr)?;
 
-    let ipv4_addr_str: String = host_addr_spec.0.to_string();
+    let address: String = host_addr_spec.0;
     let port: Option<u32> = host_addr_spec.1.map(Into::<u32>::into);
 
-    let request = tonic::Request::new(HostAddRequest {
-        ipv4_addr: ipv4_addr_str,
-        port,
-    });
+    let request = tonic::Request::new(HostAddRequest { address, port });
 
     log::info!("Sending request to server");
     log::debug!("{request:?}");
@@ -75,7 +96,7 @@ pub(crate) async fn host_add(cfg: &Config, host_addr: String) -> Result<(), anyh
 }
 
 pub(crate) async fn host_remove(cfg: &Config, host_spec: String) -> Result<(), anyhow::Error> {
-    let mut client = ServiceConnFactory::local_user_agent_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
 
     let host_spec = parse_file_source_host_spec(&host_spec)?;
 
@@ -92,23 +113,235 @@ pub(crate) async fn host_remove(cfg: &Config, host_spec: String) -> Result<(), a
     Ok(())
 }
 
-fn parse_host_addr_spec(spec: impl AsRef<str>) -> Result<(Ipv4Addr, Option<u16>), anyhow::Error> {
+pub(crate) async fn host_update(
+    cfg: &Config,
+    host_spec: String,
+    name: Option<String>,
+    notes: Option<String>,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+) -> Result<(), anyhow::Error> {
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    let request = tonic::Request::new(HostUpdateRequest {
+        host_spec: Some(parse_file_source_host_spec(&host_spec)?),
+        name,
+        notes,
+        add_tags,
+        remove_tags,
+    });
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.host_update(request).await?;
+    println!("Host updated successfuly");
+
+    Ok(())
+}
+
+pub(crate) async fn host_alias(
+    cfg: &Config,
+    host_spec: String,
+    alias: String,
+    remove: bool,
+) -> Result<(), anyhow::Error> {
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    let request = tonic::Request::new(HostAliasRequest {
+        host_spec: Some(parse_file_source_host_spec(&host_spec)?),
+        alias,
+        remove,
+    });
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.host_alias(request).await?;
+    if remove {
+        println!("Alias removed successfuly");
+    } else {
+        println!("Alias added successfuly");
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn host_block(
+    cfg: &Config,
+    rule: String,
+    remove: bool,
+) -> Result<(), anyhow::Error> {
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    let request = tonic::Request::new(HostBlockRequest { rule, remove });
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.host_block(request).await?.into_inner();
+    if remove {
+        println!("Block lifted successfuly");
+    } else {
+        println!("Blocked successfuly");
+        if response.removed_hosts > 0 {
+            println!("Removed {} known host(s)", response.removed_hosts);
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn host_blocklist(cfg: &Config) -> Result<(), anyhow::Error> {
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    let request = tonic::Request::new(HostBlocklistRequest {});
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.host_blocklist(request).await?.into_inner();
+    utils::print_blocklist(&response.rules, &response.config_rules);
+
+    Ok(())
+}
+
+pub(crate) async fn host_pair(
+    cfg: &Config,
+    host_spec: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    // Pairing started by this host.
+    if let Some(host_spec) = host_spec {
+        let host_spec = parse_file_source_host_spec(&host_spec)?;
+        let request = tonic::Request::new(HostPairRequest {
+            host_spec: Some(host_spec),
+        });
+
+        log::info!("Sending request to server");
+        log::debug!("{request:?}");
+
+        let response = client.host_pair(request).await?.into_inner();
+        let host_info = response.host_info.expect("Payload is required");
+
+        println!("Verification code: {}", response.code);
+        println!(
+            "Run `dsync-cli host pair` on {} & compare the codes.",
+            host_info.name
+        );
+        let accept = confirm(&format!("Does {} show the same code?", host_info.name))?;
+
+        client
+            .host_pair_confirm(tonic::Request::new(HostPairConfirmRequest {
+                uuid: host_info.uuid,
+                accept,
+            }))
+            .await?;
+        println!(
+            "{}",
+            if accept {
+                "Paired successfuly"
+            } else {
+                "Pairing rejected"
+            }
+        );
+
+        return Ok(());
+    }
+
+    // Pairings started by other hosts.
+    let response = client
+        .host_pair_pending(tonic::Request::new(HostPairPendingRequest {}))
+        .await?
+        .into_inner();
+
+    if response.pairings.is_empty() {
+        println!("No pairing requests");
+        return Ok(());
+    }
+
+    for pairing in response.pairings {
+        let host_info = pairing.host_info.expect("Payload is required");
+        println!(
+            "{} ({}) at {} requests pairing, verification code: {}",
+            host_info.name,
+            host_info.hostname,
+            utils::format_host_addr(&host_info),
+            pairing.code
+        );
+        let accept = confirm(&format!("Does {} show the same code?", host_info.name))?;
+
+        client
+            .host_pair_confirm(tonic::Request::new(HostPairConfirmRequest {
+                uuid: host_info.uuid,
+                accept,
+            }))
+            .await?;
+        println!(
+            "{}",
+            if accept {
+                "Paired successfuly"
+            } else {
+                "Pairing rejected"
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Ask a yes / no question, anything but explicit yes is a no.
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Accepted syntax: `IPV4[:PORT]`, `IPV6` or `[IPV6][:PORT]`. IPv6 addresses might carry
+/// a numeric zone id, e.g. `[fe80::1%2]:50051`.
+fn parse_host_addr_spec(spec: impl AsRef<str>) -> Result<(String, Option<u16>), anyhow::Error> {
     let spec = spec.as_ref();
 
-    if !spec.contains(":") {
-        return Ok((Ipv4Addr::from_str(spec)?, None));
+    // Bare IPV4 or IPV6 address, w/o port.
+    if let Some(addr) = parse_ip(spec) {
+        return Ok((addr, None));
     }
 
-    let mut split_str = spec.splitn(2, ":");
-    let addr_part = split_str.next().ok_or(anyhow::anyhow!(
+    let (addr_part, port_part) = if let Some(rest) = spec.strip_prefix('[') {
+        let (addr_part, rest) = rest.split_once(']').ok_or(anyhow::anyhow!(
+            "Invalid addr format - missing closing bracket"
+        ))?;
+        match rest.strip_prefix(':') {
+            Some(port_part) => (addr_part, Some(port_part)),
+            None if rest.is_empty() => (addr_part, None),
+            None => anyhow::bail!("Invalid addr format - failed to parse port"),
+        }
+    } else {
+        let (addr_part, port_part) = spec.rsplit_once(':').ok_or(anyhow::anyhow!(
+            "Invalid addr format - failed to parse addr"
+        ))?;
+        (addr_part, Some(port_part))
+    };
+
+    let addr = parse_ip(addr_part).ok_or(anyhow::anyhow!(
         "Invalid addr format - failed to parse addr"
     ))?;
-    let port_part = split_str.next().ok_or(anyhow::anyhow!(
-        "Invalid addr format - failed to parse port"
-    ))?;
+    let port = port_part.map(u16::from_str).transpose()?;
 
-    let ipv4_addr = Ipv4Addr::from_str(addr_part)?;
-    let port = u16::from_str(port_part)?;
+    Ok((addr, port))
+}
+
+/// Validate the address & return it in a canonical form, keeping the IPv6 zone id.
+fn parse_ip(addr: &str) -> Option<String> {
+    if let Some((ip, scope_id)) = addr.split_once('%') {
+        let ip = Ipv6Addr::from_str(ip).ok()?;
+        let scope_id = u32::from_str(scope_id).ok()?;
+        return Some(format!("{ip}%{scope_id}"));
+    }
 
-    Ok((ipv4_addr, Some(port)))
+    IpAddr::from_str(addr).ok().map(|ip| ip.to_string())
 }
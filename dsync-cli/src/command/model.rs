@@ -1,2 +1,63 @@
 pub(crate) type GroupId = String;
 pub(crate) type RemoteId = String;
+
+/// Access granted to a remote host with the `share` commands.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccessLevel {
+    /// Revoke previously granted access.
+    None,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Version of a conflicted file to keep with `conflict resolve`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConflictResolution {
+    /// Keep the local version, the remote one is overwritten by the next sync.
+    Local,
+    /// Replace the local version with the remote one.
+    Remote,
+    /// Keep the local version & save the remote one next to it, as a new file.
+    KeepBoth,
+}
+
+/// How conflicts of files of a group are resolved as they are detected.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConflictStrategy {
+    /// Leave conflicts to `conflict resolve`.
+    Manual,
+    /// Keep the version modified last.
+    NewestWins,
+    /// Keep the version of the host listed first with `--prefer`.
+    HostPriority,
+}
+
+impl From<ConflictResolution> for dsync_proto::services::user_agent::ConflictResolution {
+    fn from(value: ConflictResolution) -> Self {
+        match value {
+            ConflictResolution::Local => Self::Local,
+            ConflictResolution::Remote => Self::Remote,
+            ConflictResolution::KeepBoth => Self::KeepBoth,
+        }
+    }
+}
+
+impl From<ConflictStrategy> for dsync_proto::services::user_agent::ConflictStrategy {
+    fn from(value: ConflictStrategy) -> Self {
+        match value {
+            ConflictStrategy::Manual => Self::Manual,
+            ConflictStrategy::NewestWins => Self::NewestWins,
+            ConflictStrategy::HostPriority => Self::HostPriority,
+        }
+    }
+}
+
+impl From<AccessLevel> for dsync_proto::services::user_agent::Access {
+    fn from(value: AccessLevel) -> Self {
+        Self {
+            read: matches!(value, AccessLevel::Read | AccessLevel::ReadWrite),
+            write: matches!(value, AccessLevel::Write | AccessLevel::ReadWrite),
+        }
+    }
+}
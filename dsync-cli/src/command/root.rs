@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use crate::command::utils;
+use crate::config::Config;
+use anyhow::Context;
+use dsync_proto::services::user_agent::{
+    RootAddRequest, RootListRequest, RootRemoveRequest, RootShareRequest, RootSyncRequest,
+    RootUnsyncRequest,
+};
+use dsync_shared::{conn::ServiceConnFactory, model::parse_file_source_host_spec};
+
+use super::model::AccessLevel;
+
+pub(crate) async fn root_add(cfg: &Config, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+
+    let path_abs = path
+        .canonicalize()
+        .with_context(|| format!("Failed to turn path: {path:?} into absolute path"))?;
+
+    let request = tonic::Request::new(RootAddRequest {
+        path: path_abs
+            .to_str()
+            .context("Looks like the specified path is not a valid unicode")?
+            .to_string(),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.root_add(request).await?.into_inner();
+    println!(
+        "Root {} added with {} file(s): {path_abs:?}",
+        response.root_id, response.files
+    );
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn root_remove(cfg: &Config, root_id: i32) -> anyhow::Result<()> {
+    let request = tonic::Request::new(RootRemoveRequest { root_id });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.root_remove(request).await?;
+    println!("Root {root_id} removed");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn root_list(cfg: &Config, host_spec: Option<String>) -> anyhow::Result<()> {
+    let request = tonic::Request::new(RootListRequest {
+        host_spec: host_spec
+            .as_deref()
+            .map(parse_file_source_host_spec)
+            .transpose()?,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.root_list(request).await?.into_inner();
+    utils::print_sync_roots(&response.roots);
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn root_share(
+    cfg: &Config,
+    root_id: i32,
+    host_spec: String,
+    access: AccessLevel,
+) -> anyhow::Result<()> {
+    let request = tonic::Request::new(RootShareRequest {
+        root_id,
+        host_spec: Some(parse_file_source_host_spec(&host_spec)?),
+        access: Some(access.into()),
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.root_share(request).await?;
+    println!("Access of {host_spec} to root {root_id} set to {access:?}");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn root_sync(cfg: &Config, root_id: i32, remote: String) -> anyhow::Result<()> {
+    let Some((host_spec, remote_root_id)) = remote.rsplit_once('@') else {
+        anyhow::bail!("Expected remote root as HOST@REMOTE_ROOT_ID, got: {remote}");
+    };
+    let remote_root_id: i32 = remote_root_id
+        .parse()
+        .with_context(|| format!("Invalid remote root id: {remote_root_id}"))?;
+
+    let request = tonic::Request::new(RootSyncRequest {
+        root_id,
+        host_spec: Some(parse_file_source_host_spec(host_spec)?),
+        remote_root_id,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let _ = client.root_sync(request).await?;
+    println!("Root {root_id} is going to be synced with {remote}");
+
+    anyhow::Ok(())
+}
+
+pub(crate) async fn root_unsync(
+    cfg: &Config,
+    root_id: i32,
+    host_spec: Option<String>,
+) -> anyhow::Result<()> {
+    let request = tonic::Request::new(RootUnsyncRequest {
+        root_id,
+        host_spec: host_spec
+            .as_deref()
+            .map(parse_file_source_host_spec)
+            .transpose()?,
+    });
+
+    let mut client = ServiceConnFactory::local_user_agent_service(&cfg.control).await?;
+
+    log::info!("Sending request to server");
+    log::debug!("{request:?}");
+
+    let response = client.root_unsync(request).await?.into_inner();
+    println!("Root {root_id} unsynced from {} host(s)", response.removed);
+
+    anyhow::Ok(())
+}
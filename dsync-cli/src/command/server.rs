@@ -6,8 +6,7 @@ use dsync_shared::conn::ServiceConnFactory;
 pub(crate) async fn server_shutdown(cfg: &Config) -> Result<(), anyhow::Error> {
     let request = tonic::Request::new(ShutdownRequest {});
 
-    let mut client =
-        ServiceConnFactory::local_server_control_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_server_control_service(&cfg.control).await?;
 
     log::trace!("Sending request to server");
     log::debug!("{request:?}");
@@ -28,8 +27,7 @@ pub(crate) async fn server_shutdown(cfg: &Config) -> Result<(), anyhow::Error> {
 pub(crate) async fn server_print_config(cfg: &Config) -> Result<(), anyhow::Error> {
     let request = tonic::Request::new(PrintConfigRequest {});
 
-    let mut client =
-        ServiceConnFactory::local_server_control_service(Some(cfg.server_port)).await?;
+    let mut client = ServiceConnFactory::local_server_control_service(&cfg.control).await?;
 
     log::trace!("Sending request to server");
     log::debug!("{request:?}");
@@ -1,8 +1,11 @@
 use dsync_proto::model::{
-    common::LocalFileDescription,
+    common::{LocalFileDescription, SyncRootDescription},
     server::{GroupInfo, HostInfo},
 };
+use dsync_proto::services::user_agent::FileConflict;
 use prettytable::row;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub(super) fn print_servers_info(server_info_coll: &[HostInfo]) {
     use prettytable as pt;
@@ -10,22 +13,210 @@ pub(super) fn print_servers_info(server_info_coll: &[HostInfo]) {
     let mut table = pt::Table::new();
     table.add_row(row!["LID", "NAME", "HOSTNAME", "ADDR"]);
 
-    server_info_coll.iter().enumerate().for_each(|(i, info)| {
-        table.add_row(row![i, info.name, info.hostname, info.address]);
+    server_info_coll.iter().for_each(|info| {
+        table.add_row(row![
+            info.local_id,
+            info.name,
+            info.hostname,
+            format_host_addr(info)
+        ]);
     });
 
     table.printstd();
 }
 
+/// Like `print_servers_info`, with liveness of each host, as seen by the server.
+pub(super) fn print_servers_status(server_info_coll: &[HostInfo]) {
+    use prettytable as pt;
+
+    let mut table = pt::Table::new();
+    table.add_row(row![
+        "LID",
+        "NAME",
+        "HOSTNAME",
+        "ADDR",
+        "STATUS",
+        "LAST SEEN",
+        "RTT",
+        "VERSION",
+        "PAIRED",
+        "ALIASES",
+        "TAGS",
+        "NOTES"
+    ]);
+
+    server_info_coll.iter().for_each(|info| {
+        let status = if info.online { "online" } else { "offline" };
+        let latency = info
+            .latency_ms
+            .map(|latency| format!("{latency}ms"))
+            .unwrap_or_else(|| "-".to_owned());
+        let version = info
+            .version
+            .as_ref()
+            .map(|version| {
+                format!(
+                    "{} (proto {})",
+                    version.build_version, version.protocol_version
+                )
+            })
+            .unwrap_or_else(|| "-".to_owned());
+        table.add_row(row![
+            info.local_id,
+            info.name,
+            info.hostname,
+            format_host_addr(info),
+            status,
+            format_last_seen(info.last_seen),
+            latency,
+            version,
+            if info.paired { "yes" } else { "no" },
+            info.aliases.join(", "),
+            info.tags.join(", "),
+            info.notes
+        ]);
+    });
+
+    table.printstd();
+}
+
+pub(super) fn print_blocklist(rules: &[String], config_rules: &[String]) {
+    use prettytable as pt;
+
+    let mut table = pt::Table::new();
+    table.add_row(row!["RULE", "SOURCE"]);
+
+    config_rules.iter().for_each(|rule| {
+        table.add_row(row![rule, "config"]);
+    });
+    rules.iter().for_each(|rule| {
+        table.add_row(row![rule, "user"]);
+    });
+
+    table.printstd();
+}
+
+fn format_last_seen(last_seen: i64) -> String {
+    if last_seen == 0 {
+        return "-".to_owned();
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    format!("{}s ago", (now - last_seen).max(0))
+}
+
+/// Render `address:port` of the host, omitting the port when it is unknown.
+pub(super) fn format_host_addr(info: &HostInfo) -> String {
+    if info.port == 0 {
+        info.address.clone()
+    } else if info.address.contains(':') {
+        format!("[{}]:{}", info.address, info.port)
+    } else {
+        format!("{}:{}", info.address, info.port)
+    }
+}
+
 pub(super) fn print_local_files_desc(file_descs: &[LocalFileDescription]) {
     use prettytable as pt;
 
     let mut table = pt::Table::new();
 
-    table.add_row(row!["LID", "PATH", "SHA1[:8]"]);
+    table.add_row(row!["LID", "PATH", "SHA1[:8]", "SYNCED WITH"]);
 
     file_descs.iter().for_each(|desc| {
-        table.add_row(row![desc.local_id, &desc.file_path, desc.hash_sha1[0..8]]);
+        let file_path = if desc.gone {
+            format!("{} (gone)", desc.file_path)
+        } else if desc.conflicted {
+            format!("{} (conflict)", desc.file_path)
+        } else {
+            desc.file_path.clone()
+        };
+        table.add_row(row![
+            desc.local_id,
+            file_path,
+            desc.hash_sha1[0..8],
+            desc.synced_with.join(", ")
+        ]);
+    });
+
+    table.printstd();
+}
+
+pub(super) fn print_conflicts(conflicts: &[FileConflict]) {
+    use prettytable as pt;
+
+    let mut table = pt::Table::new();
+
+    table.add_row(row!["LID", "PATH", "HOST", "REMOTE ID", "DETECTED"]);
+
+    conflicts.iter().for_each(|conflict| {
+        table.add_row(row![
+            conflict.local_id,
+            conflict.file_path,
+            conflict.host_name,
+            conflict.remote_id,
+            format_last_seen(conflict.detected_at)
+        ]);
+    });
+
+    table.printstd();
+}
+
+pub(super) fn print_conflict(conflict: &FileConflict) {
+    use prettytable as pt;
+
+    let mut table = pt::Table::new();
+
+    table.add_row(row!["", "LOCAL", "REMOTE"]);
+    table.add_row(row![
+        "HOST",
+        "(this host)",
+        format!("{} ({})", conflict.host_name, conflict.host_uuid)
+    ]);
+    let remote_path = if conflict.copy_fetched {
+        conflict.copy_path.clone()
+    } else {
+        format!("{} (not fetched yet)", conflict.copy_path)
+    };
+    table.add_row(row!["PATH", conflict.file_path, remote_path]);
+    table.add_row(row!["SHA1", conflict.local_hash, conflict.remote_hash]);
+    table.add_row(row![
+        "VERSION",
+        format_version(&conflict.local_version),
+        format_version(&conflict.remote_version)
+    ]);
+
+    table.printstd();
+    println!(
+        "Detected {}, resolve with `conflict resolve {} <local|remote|keep-both>`",
+        format_last_seen(conflict.detected_at),
+        conflict.local_id
+    );
+}
+
+/// Counters of a version vector, ordered by host uuid.
+fn format_version(version: &HashMap<String, u64>) -> String {
+    let mut counters: Vec<_> = version.iter().collect();
+    counters.sort();
+    counters
+        .into_iter()
+        .map(|(host_uuid, counter)| format!("{host_uuid}: {counter}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(super) fn print_sync_roots(roots: &[SyncRootDescription]) {
+    use prettytable as pt;
+
+    let mut table = pt::Table::new();
+
+    table.add_row(row!["ID", "PATH", "SYNCED WITH"]);
+
+    roots.iter().for_each(|root| {
+        table.add_row(row![root.root_id, root.path, root.synced_with.join(", ")]);
     });
 
     table.printstd();
@@ -36,10 +227,19 @@ pub(super) fn print_local_group_info(group_list: &[GroupInfo]) {
 
     let mut table = pt::Table::new();
 
-    table.add_row(row!["LID", "NAME"]);
+    table.add_row(row!["LID", "NAME", "CONFLICTS"]);
 
     group_list.iter().for_each(|group| {
-        table.add_row(row![group.local_id, group.name]);
+        let strategy = if group.host_priority.is_empty() {
+            group.conflict_strategy.clone()
+        } else {
+            format!(
+                "{} ({})",
+                group.conflict_strategy,
+                group.host_priority.join(" > ")
+            )
+        };
+        table.add_row(row![group.local_id, group.name, strategy]);
     });
 
     table.printstd();
@@ -2,20 +2,28 @@
 // This module provides a `Config` struct that is derived from the parsed CLI options,
 // with sensible defaults applied where needed.
 
-use dsync_shared::DEFAULT_SERVER_PORT;
+use dsync_shared::{DEFAULT_CONTROL_PORT, conn::ControlAddr};
 
 /// Application configuration derived from CLI inputs with defaults.
 #[derive(Debug, Clone)]
 pub(crate) struct Config {
-    /// Port the dsync-server is running on. Defaults to `DEFAULT_SERVER_PORT` when not provided by the user.
-    pub(crate) server_port: u16,
+    /// Where the control services of dsync-server are reachable. The socket in the XDG runtime dir
+    /// is tried first, then `DEFAULT_CONTROL_PORT`; explicitly passed port is used alone.
+    pub(crate) control: ControlAddr,
 }
 
 impl Config {
     /// Construct `Config` from the parsed CLI.
     pub(crate) fn from_cli(cli: &crate::cli::Cli) -> Self {
         Self {
-            server_port: cli.server_port.unwrap_or(DEFAULT_SERVER_PORT),
+            control: match (&cli.control_socket, cli.control_port) {
+                (Some(socket), port) => ControlAddr {
+                    socket: Some(socket.clone()),
+                    port: port.unwrap_or(DEFAULT_CONTROL_PORT),
+                },
+                (None, Some(port)) => ControlAddr { socket: None, port },
+                (None, None) => ControlAddr::default(),
+            },
         }
     }
 }
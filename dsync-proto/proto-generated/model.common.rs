@@ -7,4 +7,56 @@ pub struct LocalFileDescription {
     pub file_path: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub hash_sha1: ::prost::alloc::string::String,
+    /// Remote files this file is synced with, as HOST@REMOTE_ID.
+    #[prost(string, repeated, tag = "4")]
+    pub synced_with: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The file has not been found on disk during the last check.
+    #[prost(bool, tag = "5")]
+    pub gone: bool,
+    /// Version vector of the file: modifications seen so far, counted per host uuid.
+    #[prost(map = "string, uint64", tag = "6")]
+    pub version: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
+    /// The file has been modified concurrently with a synced remote file.
+    #[prost(bool, tag = "7")]
+    pub conflicted: bool,
+    /// Unix timestamp (seconds) of the last modification of the file on disk.
+    #[prost(int64, tag = "8")]
+    pub mtime: i64,
+    /// Set when the file has been deleted; `version` is the version of the deletion then.
+    #[prost(message, optional, tag = "9")]
+    pub tombstone: ::core::option::Option<Tombstone>,
+}
+/// Deletion of a tracked file.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tombstone {
+    /// Host the file has been deleted on first.
+    #[prost(string, tag = "1")]
+    pub host_uuid: ::prost::alloc::string::String,
+    /// Unix timestamp (seconds) of the deletion.
+    #[prost(int64, tag = "2")]
+    pub deleted_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncRootDescription {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+    /// Absolute path of the root directory.
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    /// Remote roots this root is synced with, as HOST@REMOTE_ROOT_ID.
+    #[prost(string, repeated, tag = "3")]
+    pub synced_with: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootFileDescription {
+    /// Id of the file among all files tracked by the host.
+    #[prost(int32, tag = "1")]
+    pub file_id: i32,
+    /// Path relative to the root, with `/` separators.
+    #[prost(string, tag = "2")]
+    pub relative_path: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub hash_sha1: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub gone: bool,
 }
@@ -9,6 +9,49 @@ pub struct HostInfo {
     pub hostname: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub address: ::prost::alloc::string::String,
+    /// Port the daemon listens on. 0 means unknown, default port is assumed then.
+    #[prost(uint32, tag = "5")]
+    pub port: u32,
+    /// Liveness as observed by the heartbeat of the server answering the request.
+    /// Never sent over the handshake.
+    #[prost(bool, tag = "6")]
+    pub online: bool,
+    /// Unix timestamp (seconds) of the last successful heartbeat, 0 if none yet.
+    #[prost(int64, tag = "7")]
+    pub last_seen: i64,
+    /// Round-trip time of the last heartbeat, unset when it failed.
+    #[prost(uint32, optional, tag = "8")]
+    pub latency_ms: ::core::option::Option<u32>,
+    /// Version of the host's daemon. Exchanged during handshake, unset for hosts which
+    /// predate version negotiation.
+    #[prost(message, optional, tag = "9")]
+    pub version: ::core::option::Option<DaemonVersion>,
+    /// Whether the public key of the host has been verified with `dsync-cli host pair`.
+    /// Only paired hosts are allowed to transfer files to this server.
+    #[prost(bool, tag = "10")]
+    pub paired: bool,
+    /// Stable id of the host in the database of the server answering the request,
+    /// 0 for the server itself. Meaningless to other servers.
+    #[prost(int32, tag = "11")]
+    pub local_id: i32,
+    /// Set by the user of the server answering the request, never sent over the handshake.
+    #[prost(string, repeated, tag = "12")]
+    pub aliases: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "13")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, tag = "14")]
+    pub notes: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DaemonVersion {
+    #[prost(uint32, tag = "1")]
+    pub protocol_version: u32,
+    /// Version of the daemon build, informational only.
+    #[prost(string, tag = "2")]
+    pub build_version: ::prost::alloc::string::String,
+    /// Optional features supported by the daemon, e.g. "hash:sha1".
+    #[prost(string, repeated, tag = "3")]
+    pub capabilities: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GroupInfo {
@@ -16,4 +59,10 @@ pub struct GroupInfo {
     pub local_id: i32,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
+    /// How conflicts of files of the group are resolved: manual, newest-wins or host-priority.
+    #[prost(string, tag = "3")]
+    pub conflict_strategy: ::prost::alloc::string::String,
+    /// Hosts whose versions win conflicts of the host-priority strategy, the first one first.
+    #[prost(string, repeated, tag = "4")]
+    pub host_priority: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
@@ -38,6 +38,10 @@ pub struct TransferInitRequest {
     pub file_size_bytes: i64,
     #[prost(int32, tag = "5")]
     pub chunk_size: i32,
+    /// Version vector of the source file, empty when the file is not tracked.
+    /// The destination adopts it, so that the transfer is not mistaken for a local modification.
+    #[prost(map = "string, uint64", tag = "6")]
+    pub version: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct TransferInitResponse {
@@ -55,6 +59,55 @@ pub struct TransferChunkRequest {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct TransferChunkResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileDescribeRequest {
+    /// Id of a file tracked by the receiving host.
+    #[prost(int32, tag = "1")]
+    pub file_id: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileDescribeResponse {
+    /// Hash is computed on request, so it reflects the current contents.
+    #[prost(message, optional, tag = "1")]
+    pub file: ::core::option::Option<super::super::model::common::LocalFileDescription>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileDeleteRequest {
+    /// Id of a file tracked by the receiving host.
+    #[prost(int32, tag = "1")]
+    pub file_id: i32,
+    /// Version vector of the deletion.
+    #[prost(map = "string, uint64", tag = "2")]
+    pub version: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
+    #[prost(message, optional, tag = "3")]
+    pub tombstone: ::core::option::Option<super::super::model::common::Tombstone>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileDeleteResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootListRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootListResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub roots: ::prost::alloc::vec::Vec<
+        super::super::model::common::SyncRootDescription,
+    >,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootDescribeRequest {
+    /// Id of a sync root of the receiving host.
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootDescribeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub root: ::core::option::Option<super::super::model::common::SyncRootDescription>,
+    #[prost(message, repeated, tag = "2")]
+    pub files: ::prost::alloc::vec::Vec<
+        super::super::model::common::RootFileDescription,
+    >,
+}
 /// Generated client implementations.
 pub mod file_transfer_service_client {
     #![allow(
@@ -246,6 +299,134 @@ pub mod file_transfer_service_client {
                 );
             self.inner.client_streaming(req, path, codec).await
         }
+        /// *
+        /// Describe a file tracked by the receiving host, so that the caller can sync
+        /// with it. Requires read access to the file.
+        pub async fn file_describe(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FileDescribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileDescribeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.file_transfer.FileTransferService/FileDescribe",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.file_transfer.FileTransferService",
+                        "FileDescribe",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// *
+        /// Delete a file tracked by the receiving host, which has been deleted by
+        /// the caller. Refused unless the deletion has seen all modifications of the
+        /// file. Requires write access to the file.
+        pub async fn file_delete(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FileDeleteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileDeleteResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.file_transfer.FileTransferService/FileDelete",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.file_transfer.FileTransferService",
+                        "FileDelete",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// *
+        /// List sync roots of the receiving host the caller has read access to.
+        pub async fn root_list(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootListResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.file_transfer.FileTransferService/RootList",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.file_transfer.FileTransferService",
+                        "RootList",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// *
+        /// List files of a sync root of the receiving host, with paths relative to
+        /// the root. Requires read access to the root.
+        pub async fn root_describe(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootDescribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootDescribeResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.file_transfer.FileTransferService/RootDescribe",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.file_transfer.FileTransferService",
+                        "RootDescribe",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -293,6 +474,46 @@ pub mod file_transfer_service_server {
             tonic::Response<super::TransferChunkResponse>,
             tonic::Status,
         >;
+        /// *
+        /// Describe a file tracked by the receiving host, so that the caller can sync
+        /// with it. Requires read access to the file.
+        async fn file_describe(
+            &self,
+            request: tonic::Request<super::FileDescribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileDescribeResponse>,
+            tonic::Status,
+        >;
+        /// *
+        /// Delete a file tracked by the receiving host, which has been deleted by
+        /// the caller. Refused unless the deletion has seen all modifications of the
+        /// file. Requires write access to the file.
+        async fn file_delete(
+            &self,
+            request: tonic::Request<super::FileDeleteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileDeleteResponse>,
+            tonic::Status,
+        >;
+        /// *
+        /// List sync roots of the receiving host the caller has read access to.
+        async fn root_list(
+            &self,
+            request: tonic::Request<super::RootListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootListResponse>,
+            tonic::Status,
+        >;
+        /// *
+        /// List files of a sync root of the receiving host, with paths relative to
+        /// the root. Requires read access to the root.
+        async fn root_describe(
+            &self,
+            request: tonic::Request<super::RootDescribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootDescribeResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct FileTransferServiceServer<T> {
@@ -510,6 +731,189 @@ pub mod file_transfer_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.file_transfer.FileTransferService/FileDescribe" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileDescribeSvc<T: FileTransferService>(pub Arc<T>);
+                    impl<
+                        T: FileTransferService,
+                    > tonic::server::UnaryService<super::FileDescribeRequest>
+                    for FileDescribeSvc<T> {
+                        type Response = super::FileDescribeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileDescribeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FileTransferService>::file_describe(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileDescribeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.file_transfer.FileTransferService/FileDelete" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileDeleteSvc<T: FileTransferService>(pub Arc<T>);
+                    impl<
+                        T: FileTransferService,
+                    > tonic::server::UnaryService<super::FileDeleteRequest>
+                    for FileDeleteSvc<T> {
+                        type Response = super::FileDeleteResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileDeleteRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FileTransferService>::file_delete(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileDeleteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.file_transfer.FileTransferService/RootList" => {
+                    #[allow(non_camel_case_types)]
+                    struct RootListSvc<T: FileTransferService>(pub Arc<T>);
+                    impl<
+                        T: FileTransferService,
+                    > tonic::server::UnaryService<super::RootListRequest>
+                    for RootListSvc<T> {
+                        type Response = super::RootListResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RootListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FileTransferService>::root_list(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RootListSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.file_transfer.FileTransferService/RootDescribe" => {
+                    #[allow(non_camel_case_types)]
+                    struct RootDescribeSvc<T: FileTransferService>(pub Arc<T>);
+                    impl<
+                        T: FileTransferService,
+                    > tonic::server::UnaryService<super::RootDescribeRequest>
+                    for RootDescribeSvc<T> {
+                        type Response = super::RootDescribeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RootDescribeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FileTransferService>::root_describe(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RootDescribeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(
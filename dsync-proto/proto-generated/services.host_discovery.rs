@@ -9,6 +9,53 @@ pub struct GeneralKenobiResponse {
     #[prost(message, optional, tag = "1")]
     pub host_info: ::core::option::Option<super::super::model::server::HostInfo>,
 }
+/// Heartbeat. Carries uuid of the sender.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PingRequest {
+    #[prost(string, tag = "1")]
+    pub uuid: ::prost::alloc::string::String,
+}
+/// Carries uuid of the responder, so that the sender can tell whether it
+/// reached the host it meant to.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PingResponse {
+    #[prost(string, tag = "1")]
+    pub uuid: ::prost::alloc::string::String,
+}
+/// First step of pairing. The initiator commits to its nonce, so that neither
+/// side can choose its nonce after seeing the other one.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairInitRequest {
+    #[prost(message, optional, tag = "1")]
+    pub host_info: ::core::option::Option<super::super::model::server::HostInfo>,
+    /// Ed25519 public key of the initiator.
+    #[prost(bytes = "vec", tag = "2")]
+    pub public_key: ::prost::alloc::vec::Vec<u8>,
+    /// SHA-256 of the initiator's public key followed by its nonce.
+    #[prost(bytes = "vec", tag = "3")]
+    pub commitment: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairInitResponse {
+    #[prost(string, tag = "1")]
+    pub uuid: ::prost::alloc::string::String,
+    /// Ed25519 public key of the responder.
+    #[prost(bytes = "vec", tag = "2")]
+    pub public_key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+}
+/// Second step of pairing. After it both sides are able to compute the
+/// verification code.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairRevealRequest {
+    #[prost(string, tag = "1")]
+    pub uuid: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PairRevealResponse {}
 /// Generated client implementations.
 pub mod host_discovery_service_client {
     #![allow(
@@ -132,6 +179,93 @@ pub mod host_discovery_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Lightweight liveness check of already known peer.
+        pub async fn ping(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PingRequest>,
+        ) -> std::result::Result<tonic::Response<super::PingResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.host_discovery.HostDiscoveryService/Ping",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.host_discovery.HostDiscoveryService",
+                        "Ping",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Key exchange of the pairing process. Keys become trusted only once
+        /// the user confirms the verification code on both hosts.
+        pub async fn pair_init(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PairInitRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PairInitResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.host_discovery.HostDiscoveryService/PairInit",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.host_discovery.HostDiscoveryService",
+                        "PairInit",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn pair_reveal(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PairRevealRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PairRevealResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.host_discovery.HostDiscoveryService/PairReveal",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.host_discovery.HostDiscoveryService",
+                        "PairReveal",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -157,6 +291,27 @@ pub mod host_discovery_service_server {
             tonic::Response<super::GeneralKenobiResponse>,
             tonic::Status,
         >;
+        /// Lightweight liveness check of already known peer.
+        async fn ping(
+            &self,
+            request: tonic::Request<super::PingRequest>,
+        ) -> std::result::Result<tonic::Response<super::PingResponse>, tonic::Status>;
+        /// Key exchange of the pairing process. Keys become trusted only once
+        /// the user confirms the verification code on both hosts.
+        async fn pair_init(
+            &self,
+            request: tonic::Request<super::PairInitRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PairInitResponse>,
+            tonic::Status,
+        >;
+        async fn pair_reveal(
+            &self,
+            request: tonic::Request<super::PairRevealRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PairRevealResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct HostDiscoveryServiceServer<T> {
@@ -281,6 +436,142 @@ pub mod host_discovery_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.host_discovery.HostDiscoveryService/Ping" => {
+                    #[allow(non_camel_case_types)]
+                    struct PingSvc<T: HostDiscoveryService>(pub Arc<T>);
+                    impl<
+                        T: HostDiscoveryService,
+                    > tonic::server::UnaryService<super::PingRequest> for PingSvc<T> {
+                        type Response = super::PingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HostDiscoveryService>::ping(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.host_discovery.HostDiscoveryService/PairInit" => {
+                    #[allow(non_camel_case_types)]
+                    struct PairInitSvc<T: HostDiscoveryService>(pub Arc<T>);
+                    impl<
+                        T: HostDiscoveryService,
+                    > tonic::server::UnaryService<super::PairInitRequest>
+                    for PairInitSvc<T> {
+                        type Response = super::PairInitResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PairInitRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HostDiscoveryService>::pair_init(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PairInitSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.host_discovery.HostDiscoveryService/PairReveal" => {
+                    #[allow(non_camel_case_types)]
+                    struct PairRevealSvc<T: HostDiscoveryService>(pub Arc<T>);
+                    impl<
+                        T: HostDiscoveryService,
+                    > tonic::server::UnaryService<super::PairRevealRequest>
+                    for PairRevealSvc<T> {
+                        type Response = super::PairRevealResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PairRevealRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HostDiscoveryService>::pair_reveal(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PairRevealSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(
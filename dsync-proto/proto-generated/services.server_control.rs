@@ -104,9 +104,8 @@ pub mod server_control_service_client {
         }
         /// Request shutdown of the server.
         ///
-        /// Ideally this request should be handled only
-        /// when originating from localhost. Shutting down the server from remote
-        /// host / client should not be allowed.
+        /// Handled only when originating from localhost - the service is served
+        /// on the loopback control listener, which rejects remote callers.
         ///
         /// Successful response does not mean that the server will shutdown.
         /// It only means that the server acknowledged the shutdown request,
@@ -186,9 +185,8 @@ pub mod server_control_service_server {
     pub trait ServerControlService: std::marker::Send + std::marker::Sync + 'static {
         /// Request shutdown of the server.
         ///
-        /// Ideally this request should be handled only
-        /// when originating from localhost. Shutting down the server from remote
-        /// host / client should not be allowed.
+        /// Handled only when originating from localhost - the service is served
+        /// on the loopback control listener, which rejects remote callers.
         ///
         /// Successful response does not mean that the server will shutdown.
         /// It only means that the server acknowledged the shutdown request,
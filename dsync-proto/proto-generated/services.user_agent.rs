@@ -10,12 +10,61 @@ pub mod host_spec {
     pub enum Kind {
         #[prost(int32, tag = "1")]
         LocalHost(i32),
+        /// Display name or alias.
         #[prost(string, tag = "2")]
         Name(::prost::alloc::string::String),
         #[prost(int32, tag = "3")]
         LocalId(i32),
     }
 }
+/// Access of a remote host to a local group or file. No access at all revokes it.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Access {
+    #[prost(bool, tag = "1")]
+    pub read: bool,
+    #[prost(bool, tag = "2")]
+    pub write: bool,
+}
+/// Pairing requested by a peer, waiting for the user to compare verification codes.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PendingPairing {
+    #[prost(message, optional, tag = "1")]
+    pub host_info: ::core::option::Option<super::super::model::server::HostInfo>,
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+/// Local file modified concurrently with a synced remote file.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileConflict {
+    #[prost(int32, tag = "1")]
+    pub local_id: i32,
+    #[prost(string, tag = "2")]
+    pub file_path: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub host_uuid: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub host_name: ::prost::alloc::string::String,
+    /// Id of the file tracked by the remote host.
+    #[prost(int32, tag = "5")]
+    pub remote_id: i32,
+    #[prost(string, tag = "6")]
+    pub local_hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub remote_hash: ::prost::alloc::string::String,
+    #[prost(map = "string, uint64", tag = "8")]
+    pub local_version: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
+    #[prost(map = "string, uint64", tag = "9")]
+    pub remote_version: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
+    /// Where the remote version is fetched to.
+    #[prost(string, tag = "10")]
+    pub copy_path: ::prost::alloc::string::String,
+    /// The remote version has arrived at `copy_path`.
+    #[prost(bool, tag = "11")]
+    pub copy_fetched: bool,
+    /// Unix timestamp (seconds) of the detection.
+    #[prost(int64, tag = "12")]
+    pub detected_at: i64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FileSource {
     #[prost(message, optional, tag = "1")]
@@ -33,6 +82,72 @@ pub mod file_source {
         AbsolutePath(::prost::alloc::string::String),
     }
 }
+/// What to do with a conflicted file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ConflictResolution {
+    /// Keep the local version, the remote one is overwritten by the next sync.
+    Local = 0,
+    /// Replace the local version with the fetched remote one.
+    Remote = 1,
+    /// Keep the local version & move the remote one next to it, as a new file.
+    KeepBoth = 2,
+}
+impl ConflictResolution {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Local => "CONFLICT_RESOLUTION_LOCAL",
+            Self::Remote => "CONFLICT_RESOLUTION_REMOTE",
+            Self::KeepBoth => "CONFLICT_RESOLUTION_KEEP_BOTH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CONFLICT_RESOLUTION_LOCAL" => Some(Self::Local),
+            "CONFLICT_RESOLUTION_REMOTE" => Some(Self::Remote),
+            "CONFLICT_RESOLUTION_KEEP_BOTH" => Some(Self::KeepBoth),
+            _ => None,
+        }
+    }
+}
+/// How conflicts of files of a group are resolved as they are detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ConflictStrategy {
+    /// Left for the user to resolve.
+    Manual = 0,
+    /// The version modified last on disk wins.
+    NewestWins = 1,
+    /// The version of the host listed first in the group's host priority wins.
+    HostPriority = 2,
+}
+impl ConflictStrategy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Manual => "CONFLICT_STRATEGY_MANUAL",
+            Self::NewestWins => "CONFLICT_STRATEGY_NEWEST_WINS",
+            Self::HostPriority => "CONFLICT_STRATEGY_HOST_PRIORITY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CONFLICT_STRATEGY_MANUAL" => Some(Self::Manual),
+            "CONFLICT_STRATEGY_NEWEST_WINS" => Some(Self::NewestWins),
+            "CONFLICT_STRATEGY_HOST_PRIORITY" => Some(Self::HostPriority),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FileAddRequest {
     #[prost(string, repeated, tag = "1")]
@@ -93,11 +208,17 @@ pub struct HostDiscoverResponse {
     #[prost(message, repeated, tag = "1")]
     pub host_list: ::prost::alloc::vec::Vec<super::super::model::server::HostInfo>,
 }
+/// Single peer found during discovery.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostDiscoverStreamResponse {
+    #[prost(message, optional, tag = "1")]
+    pub host_info: ::core::option::Option<super::super::model::server::HostInfo>,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HostAddRequest {
-    /// Accepted format: IPV4
+    /// Accepted format: IPV4 or IPV6, the latter optionally with a numeric zone id (fe80::1%2).
     #[prost(string, tag = "1")]
-    pub ipv4_addr: ::prost::alloc::string::String,
+    pub address: ::prost::alloc::string::String,
     #[prost(uint32, optional, tag = "2")]
     pub port: ::core::option::Option<u32>,
 }
@@ -114,6 +235,91 @@ pub struct HostRemoveRequest {
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct HostRemoveResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostUpdateRequest {
+    #[prost(message, optional, tag = "1")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    /// New display name, unchanged when unset.
+    #[prost(string, optional, tag = "2")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Replace the notes, unchanged when unset.
+    #[prost(string, optional, tag = "3")]
+    pub notes: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "4")]
+    pub add_tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "5")]
+    pub remove_tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostUpdateResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostAliasRequest {
+    #[prost(message, optional, tag = "1")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    #[prost(string, tag = "2")]
+    pub alias: ::prost::alloc::string::String,
+    /// Remove the alias instead of adding it.
+    #[prost(bool, tag = "3")]
+    pub remove: bool,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostAliasResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostBlockRequest {
+    /// Uuid of a host, an IP address or a subnet in CIDR notation.
+    #[prost(string, tag = "1")]
+    pub rule: ::prost::alloc::string::String,
+    /// Lift the block instead of adding it.
+    #[prost(bool, tag = "2")]
+    pub remove: bool,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostBlockResponse {
+    /// Number of known hosts removed, because the rule matches them.
+    #[prost(uint32, tag = "1")]
+    pub removed_hosts: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostBlocklistRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostBlocklistResponse {
+    /// Rules added with HostBlock.
+    #[prost(string, repeated, tag = "1")]
+    pub rules: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Rules from the server configuration, these can not be removed at runtime.
+    #[prost(string, repeated, tag = "2")]
+    pub config_rules: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostPairRequest {
+    #[prost(message, optional, tag = "1")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostPairResponse {
+    #[prost(message, optional, tag = "1")]
+    pub host_info: ::core::option::Option<super::super::model::server::HostInfo>,
+    /// Verification code, must match the one shown on the peer.
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostPairPendingRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostPairPendingResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub pairings: ::prost::alloc::vec::Vec<PendingPairing>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HostPairConfirmRequest {
+    #[prost(string, tag = "1")]
+    pub uuid: ::prost::alloc::string::String,
+    /// False rejects the pairing.
+    #[prost(bool, tag = "2")]
+    pub accept: bool,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HostPairConfirmResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GroupCreateRequest {
     #[prost(string, tag = "1")]
     pub group_id: ::prost::alloc::string::String,
@@ -137,6 +343,224 @@ pub struct GroupListResponse {
     #[prost(message, repeated, tag = "1")]
     pub group_list: ::prost::alloc::vec::Vec<super::super::model::server::GroupInfo>,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupShareRequest {
+    #[prost(string, tag = "1")]
+    pub group_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    #[prost(message, optional, tag = "3")]
+    pub access: ::core::option::Option<Access>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GroupShareResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupStrategyRequest {
+    #[prost(string, tag = "1")]
+    pub group_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "ConflictStrategy", tag = "2")]
+    pub strategy: i32,
+    /// Required by the host-priority strategy, the first host wins over the others.
+    #[prost(message, repeated, tag = "3")]
+    pub host_priority: ::prost::alloc::vec::Vec<HostSpec>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GroupStrategyResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileShareRequest {
+    #[prost(string, tag = "1")]
+    pub file_path: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    #[prost(message, optional, tag = "3")]
+    pub access: ::core::option::Option<Access>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileShareResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileSyncRequest {
+    #[prost(int32, tag = "1")]
+    pub local_id: i32,
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    /// Id of the file tracked by the remote host.
+    #[prost(int32, tag = "3")]
+    pub remote_id: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileSyncResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileUnsyncRequest {
+    #[prost(int32, tag = "1")]
+    pub local_id: i32,
+    /// Unsync from all hosts when unset.
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileUnsyncResponse {
+    /// Number of removed pairings.
+    #[prost(uint32, tag = "1")]
+    pub removed: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileRescanRequest {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct FileRescanResponse {
+    #[prost(uint32, tag = "1")]
+    pub scanned: u32,
+    /// Files whose contents have changed.
+    #[prost(uint32, tag = "2")]
+    pub modified: u32,
+    /// Files not found on disk.
+    #[prost(uint32, tag = "3")]
+    pub gone: u32,
+    /// Files new to sync roots.
+    #[prost(uint32, tag = "4")]
+    pub added: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileCheckIgnoreRequest {
+    /// Absolute path, it does not need to exist.
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IgnoreRule {
+    /// The pattern as written, including the leading `!` of negated patterns.
+    #[prost(string, tag = "1")]
+    pub pattern: ::prost::alloc::string::String,
+    /// `.dsyncignore` file the pattern comes from, unset for patterns of the server config.
+    #[prost(string, optional, tag = "2")]
+    pub source: ::core::option::Option<::prost::alloc::string::String>,
+    /// The pattern re-includes the path.
+    #[prost(bool, tag = "3")]
+    pub negated: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileCheckIgnoreResponse {
+    /// Sync root the path belongs to, unset when it does not belong to any.
+    #[prost(int32, optional, tag = "1")]
+    pub root_id: ::core::option::Option<i32>,
+    #[prost(bool, tag = "2")]
+    pub ignored: bool,
+    /// Pattern which has decided, unset when no pattern matches.
+    #[prost(message, optional, tag = "3")]
+    pub rule: ::core::option::Option<IgnoreRule>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootAddRequest {
+    /// Absolute path of a directory.
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootAddResponse {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+    /// Number of files found in the root.
+    #[prost(uint32, tag = "2")]
+    pub files: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootRemoveRequest {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootRemoveResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootListRequest {
+    /// List roots of the given host, local roots are listed when unset.
+    #[prost(message, optional, tag = "1")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootListResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub roots: ::prost::alloc::vec::Vec<
+        super::super::model::common::SyncRootDescription,
+    >,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootShareRequest {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    #[prost(message, optional, tag = "3")]
+    pub access: ::core::option::Option<Access>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootShareResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootSyncRequest {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    /// Id of the root of the remote host.
+    #[prost(int32, tag = "3")]
+    pub remote_root_id: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootSyncResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RootUnsyncRequest {
+    #[prost(int32, tag = "1")]
+    pub root_id: i32,
+    /// Unsync from all hosts when unset.
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RootUnsyncResponse {
+    /// Number of removed pairings.
+    #[prost(uint32, tag = "1")]
+    pub removed: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ConflictListRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictListResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub conflicts: ::prost::alloc::vec::Vec<FileConflict>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictShowRequest {
+    #[prost(int32, tag = "1")]
+    pub local_id: i32,
+    /// Required when the file conflicts with more than one host.
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    /// Include a diff of both versions.
+    #[prost(bool, tag = "3")]
+    pub diff: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictShowResponse {
+    #[prost(message, optional, tag = "1")]
+    pub conflict: ::core::option::Option<FileConflict>,
+    /// Unified diff from the local version to the remote one. Unset when not requested.
+    #[prost(string, optional, tag = "2")]
+    pub diff: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictResolveRequest {
+    #[prost(int32, tag = "1")]
+    pub local_id: i32,
+    /// Required when the file conflicts with more than one host.
+    #[prost(message, optional, tag = "2")]
+    pub host_spec: ::core::option::Option<HostSpec>,
+    #[prost(enumeration = "ConflictResolution", tag = "3")]
+    pub resolution: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictResolveResponse {
+    /// Where the remote version has been moved to, set when both versions are kept.
+    #[prost(string, optional, tag = "1")]
+    pub kept_path: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Generated client implementations.
 pub mod user_agent_service_client {
     #![allow(
@@ -334,11 +758,12 @@ pub mod user_agent_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn host_list(
+        /// Grant a host access to a tracked file, or revoke it.
+        pub async fn file_share(
             &mut self,
-            request: impl tonic::IntoRequest<super::HostListRequest>,
+            request: impl tonic::IntoRequest<super::FileShareRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostListResponse>,
+            tonic::Response<super::FileShareResponse>,
             tonic::Status,
         > {
             self.inner
@@ -351,20 +776,21 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/HostList",
+                "/services.user_agent.UserAgentService/FileShare",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("services.user_agent.UserAgentService", "HostList"),
+                    GrpcMethod::new("services.user_agent.UserAgentService", "FileShare"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn host_discover(
+        /// Keep a tracked file in sync with a file tracked by a peer.
+        pub async fn file_sync(
             &mut self,
-            request: impl tonic::IntoRequest<super::HostDiscoverRequest>,
+            request: impl tonic::IntoRequest<super::FileSyncRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostDiscoverResponse>,
+            tonic::Response<super::FileSyncResponse>,
             tonic::Status,
         > {
             self.inner
@@ -377,23 +803,21 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/HostDiscover",
+                "/services.user_agent.UserAgentService/FileSync",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new(
-                        "services.user_agent.UserAgentService",
-                        "HostDiscover",
-                    ),
+                    GrpcMethod::new("services.user_agent.UserAgentService", "FileSync"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn host_add(
+        /// Stop syncing a tracked file with one peer or with all of them.
+        pub async fn file_unsync(
             &mut self,
-            request: impl tonic::IntoRequest<super::HostAddRequest>,
+            request: impl tonic::IntoRequest<super::FileUnsyncRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostAddResponse>,
+            tonic::Response<super::FileUnsyncResponse>,
             tonic::Status,
         > {
             self.inner
@@ -406,20 +830,21 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/HostAdd",
+                "/services.user_agent.UserAgentService/FileUnsync",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("services.user_agent.UserAgentService", "HostAdd"),
+                    GrpcMethod::new("services.user_agent.UserAgentService", "FileUnsync"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn host_remove(
+        /// Compare all tracked files with the disk & rehash the changed ones.
+        pub async fn file_rescan(
             &mut self,
-            request: impl tonic::IntoRequest<super::HostRemoveRequest>,
+            request: impl tonic::IntoRequest<super::FileRescanRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostRemoveResponse>,
+            tonic::Response<super::FileRescanResponse>,
             tonic::Status,
         > {
             self.inner
@@ -432,20 +857,21 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/HostRemove",
+                "/services.user_agent.UserAgentService/FileRescan",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("services.user_agent.UserAgentService", "HostRemove"),
+                    GrpcMethod::new("services.user_agent.UserAgentService", "FileRescan"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn group_create(
+        /// Tell whether a path of a sync root is ignored & by which pattern.
+        pub async fn file_check_ignore(
             &mut self,
-            request: impl tonic::IntoRequest<super::GroupCreateRequest>,
+            request: impl tonic::IntoRequest<super::FileCheckIgnoreRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupCreateResponse>,
+            tonic::Response<super::FileCheckIgnoreResponse>,
             tonic::Status,
         > {
             self.inner
@@ -458,23 +884,23 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/GroupCreate",
+                "/services.user_agent.UserAgentService/FileCheckIgnore",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "services.user_agent.UserAgentService",
-                        "GroupCreate",
+                        "FileCheckIgnore",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn group_delete(
+        pub async fn host_list(
             &mut self,
-            request: impl tonic::IntoRequest<super::GroupDeleteRequest>,
+            request: impl tonic::IntoRequest<super::HostListRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupDeleteResponse>,
+            tonic::Response<super::HostListResponse>,
             tonic::Status,
         > {
             self.inner
@@ -487,23 +913,50 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/GroupDelete",
+                "/services.user_agent.UserAgentService/HostList",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostList"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn host_discover(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostDiscoverRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostDiscoverResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostDiscover",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "services.user_agent.UserAgentService",
-                        "GroupDelete",
+                        "HostDiscover",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn group_list(
+        /// Same as HostDiscover, but peers are streamed back as soon as they respond.
+        pub async fn host_discover_stream(
             &mut self,
-            request: impl tonic::IntoRequest<super::GroupListRequest>,
+            request: impl tonic::IntoRequest<super::HostDiscoverRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupListResponse>,
+            tonic::Response<tonic::codec::Streaming<super::HostDiscoverStreamResponse>>,
             tonic::Status,
         > {
             self.inner
@@ -516,199 +969,1988 @@ pub mod user_agent_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/services.user_agent.UserAgentService/GroupList",
+                "/services.user_agent.UserAgentService/HostDiscoverStream",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("services.user_agent.UserAgentService", "GroupList"),
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "HostDiscoverStream",
+                    ),
                 );
-            self.inner.unary(req, path, codec).await
+            self.inner.server_streaming(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod user_agent_service_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with UserAgentServiceServer.
-    #[async_trait]
-    pub trait UserAgentService: std::marker::Send + std::marker::Sync + 'static {
-        async fn file_add(
-            &self,
-            request: tonic::Request<super::FileAddRequest>,
-        ) -> std::result::Result<tonic::Response<super::FileAddResponse>, tonic::Status>;
-        async fn file_remove(
-            &self,
-            request: tonic::Request<super::FileRemoveRequest>,
+        pub async fn host_add(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostAddRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::FileRemoveResponse>,
+            tonic::Response<super::HostAddResponse>,
             tonic::Status,
-        >;
-        async fn file_list(
-            &self,
-            request: tonic::Request<super::FileListRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostAdd",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostAdd"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn host_remove(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostRemoveRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::FileListResponse>,
+            tonic::Response<super::HostRemoveResponse>,
             tonic::Status,
-        >;
-        async fn file_copy(
-            &self,
-            request: tonic::Request<super::FileCopyRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostRemove",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostRemove"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Edit the display name, notes & tags of a host.
+        pub async fn host_update(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostUpdateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::FileCopyResponse>,
+            tonic::Response<super::HostUpdateResponse>,
             tonic::Status,
-        >;
-        async fn host_list(
-            &self,
-            request: tonic::Request<super::HostListRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostUpdate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostUpdate"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Add or remove an alternative name of a host.
+        pub async fn host_alias(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostAliasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostListResponse>,
+            tonic::Response<super::HostAliasResponse>,
             tonic::Status,
-        >;
-        async fn host_discover(
-            &self,
-            request: tonic::Request<super::HostDiscoverRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostAlias",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostAlias"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Block a host, an address or a subnet, or lift the block. Known hosts matching
+        /// the rule are removed.
+        pub async fn host_block(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostBlockRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostDiscoverResponse>,
+            tonic::Response<super::HostBlockResponse>,
             tonic::Status,
-        >;
-        async fn host_add(
-            &self,
-            request: tonic::Request<super::HostAddRequest>,
-        ) -> std::result::Result<tonic::Response<super::HostAddResponse>, tonic::Status>;
-        async fn host_remove(
-            &self,
-            request: tonic::Request<super::HostRemoveRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostBlock",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostBlock"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn host_blocklist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostBlocklistRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::HostRemoveResponse>,
+            tonic::Response<super::HostBlocklistResponse>,
             tonic::Status,
-        >;
-        async fn group_create(
-            &self,
-            request: tonic::Request<super::GroupCreateRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostBlocklist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "HostBlocklist",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Exchange keys with a known host & return the verification code.
+        pub async fn host_pair(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostPairRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupCreateResponse>,
+            tonic::Response<super::HostPairResponse>,
             tonic::Status,
-        >;
-        async fn group_delete(
-            &self,
-            request: tonic::Request<super::GroupDeleteRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostPair",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "HostPair"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Pairings started by peers, waiting for confirmation.
+        pub async fn host_pair_pending(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostPairPendingRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupDeleteResponse>,
+            tonic::Response<super::HostPairPendingResponse>,
             tonic::Status,
-        >;
-        async fn group_list(
-            &self,
-            request: tonic::Request<super::GroupListRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostPairPending",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "HostPairPending",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Trust (or reject) the key of a host once the user compared verification codes.
+        pub async fn host_pair_confirm(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HostPairConfirmRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GroupListResponse>,
+            tonic::Response<super::HostPairConfirmResponse>,
             tonic::Status,
-        >;
-    }
-    /// Communication between UserAgent (e.g. CLI tool) and local
-    /// server instance.
-    #[derive(Debug)]
-    pub struct UserAgentServiceServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> UserAgentServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/HostPairConfirm",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "HostPairConfirm",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        pub async fn group_create(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupCreateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupCreateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/GroupCreate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "GroupCreate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
+        pub async fn group_delete(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupDeleteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupDeleteResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/GroupDelete",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "GroupDelete",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
+        pub async fn group_list(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupListResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/GroupList",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "GroupList"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.max_decoding_message_size = Some(limit);
-            self
+        /// Grant a host access to all files of a group, or revoke it.
+        pub async fn group_share(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupShareRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupShareResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/GroupShare",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "GroupShare"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.max_encoding_message_size = Some(limit);
-            self
+        /// Set how conflicts of files of a group are resolved.
+        pub async fn group_strategy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GroupStrategyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupStrategyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/GroupStrategy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "GroupStrategy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for UserAgentServiceServer<T>
-    where
-        T: UserAgentService,
-        B: Body + std::marker::Send + 'static,
-        B::Error: Into<StdError> + std::marker::Send + 'static,
-    {
-        type Response = http::Response<tonic::body::Body>;
-        type Error = std::convert::Infallible;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(
+        pub async fn root_add(
             &mut self,
-            _cx: &mut Context<'_>,
-        ) -> Poll<std::result::Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
+            request: impl tonic::IntoRequest<super::RootAddRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootAddResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootAdd",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootAdd"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            match req.uri().path() {
-                "/services.user_agent.UserAgentService/FileAdd" => {
+        pub async fn root_remove(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootRemoveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootRemoveResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootRemove",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootRemove"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// List local roots, or roots of a remote host shared with this host.
+        pub async fn root_list(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootListResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootList",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootList"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Grant a host access to all files of a root, or revoke it.
+        pub async fn root_share(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootShareRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootShareResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootShare",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootShare"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Keep a local root in sync with a root of a remote host.
+        pub async fn root_sync(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootSyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootSyncResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootSync",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootSync"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Stop syncing a root with one peer or with all of them.
+        pub async fn root_unsync(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RootUnsyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootUnsyncResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/RootUnsync",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("services.user_agent.UserAgentService", "RootUnsync"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn conflict_list(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConflictListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictListResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/ConflictList",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "ConflictList",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn conflict_show(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConflictShowRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictShowResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/ConflictShow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "ConflictShow",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn conflict_resolve(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConflictResolveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictResolveResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.user_agent.UserAgentService/ConflictResolve",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "services.user_agent.UserAgentService",
+                        "ConflictResolve",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod user_agent_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with UserAgentServiceServer.
+    #[async_trait]
+    pub trait UserAgentService: std::marker::Send + std::marker::Sync + 'static {
+        async fn file_add(
+            &self,
+            request: tonic::Request<super::FileAddRequest>,
+        ) -> std::result::Result<tonic::Response<super::FileAddResponse>, tonic::Status>;
+        async fn file_remove(
+            &self,
+            request: tonic::Request<super::FileRemoveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileRemoveResponse>,
+            tonic::Status,
+        >;
+        async fn file_list(
+            &self,
+            request: tonic::Request<super::FileListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileListResponse>,
+            tonic::Status,
+        >;
+        async fn file_copy(
+            &self,
+            request: tonic::Request<super::FileCopyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileCopyResponse>,
+            tonic::Status,
+        >;
+        /// Grant a host access to a tracked file, or revoke it.
+        async fn file_share(
+            &self,
+            request: tonic::Request<super::FileShareRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileShareResponse>,
+            tonic::Status,
+        >;
+        /// Keep a tracked file in sync with a file tracked by a peer.
+        async fn file_sync(
+            &self,
+            request: tonic::Request<super::FileSyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileSyncResponse>,
+            tonic::Status,
+        >;
+        /// Stop syncing a tracked file with one peer or with all of them.
+        async fn file_unsync(
+            &self,
+            request: tonic::Request<super::FileUnsyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileUnsyncResponse>,
+            tonic::Status,
+        >;
+        /// Compare all tracked files with the disk & rehash the changed ones.
+        async fn file_rescan(
+            &self,
+            request: tonic::Request<super::FileRescanRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileRescanResponse>,
+            tonic::Status,
+        >;
+        /// Tell whether a path of a sync root is ignored & by which pattern.
+        async fn file_check_ignore(
+            &self,
+            request: tonic::Request<super::FileCheckIgnoreRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileCheckIgnoreResponse>,
+            tonic::Status,
+        >;
+        async fn host_list(
+            &self,
+            request: tonic::Request<super::HostListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostListResponse>,
+            tonic::Status,
+        >;
+        async fn host_discover(
+            &self,
+            request: tonic::Request<super::HostDiscoverRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostDiscoverResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the HostDiscoverStream method.
+        type HostDiscoverStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::HostDiscoverStreamResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Same as HostDiscover, but peers are streamed back as soon as they respond.
+        async fn host_discover_stream(
+            &self,
+            request: tonic::Request<super::HostDiscoverRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::HostDiscoverStreamStream>,
+            tonic::Status,
+        >;
+        async fn host_add(
+            &self,
+            request: tonic::Request<super::HostAddRequest>,
+        ) -> std::result::Result<tonic::Response<super::HostAddResponse>, tonic::Status>;
+        async fn host_remove(
+            &self,
+            request: tonic::Request<super::HostRemoveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostRemoveResponse>,
+            tonic::Status,
+        >;
+        /// Edit the display name, notes & tags of a host.
+        async fn host_update(
+            &self,
+            request: tonic::Request<super::HostUpdateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostUpdateResponse>,
+            tonic::Status,
+        >;
+        /// Add or remove an alternative name of a host.
+        async fn host_alias(
+            &self,
+            request: tonic::Request<super::HostAliasRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostAliasResponse>,
+            tonic::Status,
+        >;
+        /// Block a host, an address or a subnet, or lift the block. Known hosts matching
+        /// the rule are removed.
+        async fn host_block(
+            &self,
+            request: tonic::Request<super::HostBlockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostBlockResponse>,
+            tonic::Status,
+        >;
+        async fn host_blocklist(
+            &self,
+            request: tonic::Request<super::HostBlocklistRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostBlocklistResponse>,
+            tonic::Status,
+        >;
+        /// Exchange keys with a known host & return the verification code.
+        async fn host_pair(
+            &self,
+            request: tonic::Request<super::HostPairRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostPairResponse>,
+            tonic::Status,
+        >;
+        /// Pairings started by peers, waiting for confirmation.
+        async fn host_pair_pending(
+            &self,
+            request: tonic::Request<super::HostPairPendingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostPairPendingResponse>,
+            tonic::Status,
+        >;
+        /// Trust (or reject) the key of a host once the user compared verification codes.
+        async fn host_pair_confirm(
+            &self,
+            request: tonic::Request<super::HostPairConfirmRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::HostPairConfirmResponse>,
+            tonic::Status,
+        >;
+        async fn group_create(
+            &self,
+            request: tonic::Request<super::GroupCreateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupCreateResponse>,
+            tonic::Status,
+        >;
+        async fn group_delete(
+            &self,
+            request: tonic::Request<super::GroupDeleteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupDeleteResponse>,
+            tonic::Status,
+        >;
+        async fn group_list(
+            &self,
+            request: tonic::Request<super::GroupListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupListResponse>,
+            tonic::Status,
+        >;
+        /// Grant a host access to all files of a group, or revoke it.
+        async fn group_share(
+            &self,
+            request: tonic::Request<super::GroupShareRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupShareResponse>,
+            tonic::Status,
+        >;
+        /// Set how conflicts of files of a group are resolved.
+        async fn group_strategy(
+            &self,
+            request: tonic::Request<super::GroupStrategyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GroupStrategyResponse>,
+            tonic::Status,
+        >;
+        async fn root_add(
+            &self,
+            request: tonic::Request<super::RootAddRequest>,
+        ) -> std::result::Result<tonic::Response<super::RootAddResponse>, tonic::Status>;
+        async fn root_remove(
+            &self,
+            request: tonic::Request<super::RootRemoveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootRemoveResponse>,
+            tonic::Status,
+        >;
+        /// List local roots, or roots of a remote host shared with this host.
+        async fn root_list(
+            &self,
+            request: tonic::Request<super::RootListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootListResponse>,
+            tonic::Status,
+        >;
+        /// Grant a host access to all files of a root, or revoke it.
+        async fn root_share(
+            &self,
+            request: tonic::Request<super::RootShareRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootShareResponse>,
+            tonic::Status,
+        >;
+        /// Keep a local root in sync with a root of a remote host.
+        async fn root_sync(
+            &self,
+            request: tonic::Request<super::RootSyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootSyncResponse>,
+            tonic::Status,
+        >;
+        /// Stop syncing a root with one peer or with all of them.
+        async fn root_unsync(
+            &self,
+            request: tonic::Request<super::RootUnsyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RootUnsyncResponse>,
+            tonic::Status,
+        >;
+        async fn conflict_list(
+            &self,
+            request: tonic::Request<super::ConflictListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictListResponse>,
+            tonic::Status,
+        >;
+        async fn conflict_show(
+            &self,
+            request: tonic::Request<super::ConflictShowRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictShowResponse>,
+            tonic::Status,
+        >;
+        async fn conflict_resolve(
+            &self,
+            request: tonic::Request<super::ConflictResolveRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConflictResolveResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Communication between UserAgent (e.g. CLI tool) and local
+    /// server instance.
+    #[derive(Debug)]
+    pub struct UserAgentServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> UserAgentServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for UserAgentServiceServer<T>
+    where
+        T: UserAgentService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/services.user_agent.UserAgentService/FileAdd" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileAddSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileAddRequest>
+                    for FileAddSvc<T> {
+                        type Response = super::FileAddResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileAddRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_add(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileAddSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileRemove" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileRemoveSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileRemoveRequest>
+                    for FileRemoveSvc<T> {
+                        type Response = super::FileRemoveResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileRemoveRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_remove(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileRemoveSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileList" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileListSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileListRequest>
+                    for FileListSvc<T> {
+                        type Response = super::FileListResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_list(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileListSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileCopy" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileCopySvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileCopyRequest>
+                    for FileCopySvc<T> {
+                        type Response = super::FileCopyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileCopyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_copy(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileCopySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileShare" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileShareSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileShareRequest>
+                    for FileShareSvc<T> {
+                        type Response = super::FileShareResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileShareRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_share(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileShareSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileSync" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileSyncSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileSyncRequest>
+                    for FileSyncSvc<T> {
+                        type Response = super::FileSyncResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileSyncRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_sync(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileSyncSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileUnsync" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileUnsyncSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileUnsyncRequest>
+                    for FileUnsyncSvc<T> {
+                        type Response = super::FileUnsyncResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileUnsyncRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_unsync(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileUnsyncSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileRescan" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileRescanSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileRescanRequest>
+                    for FileRescanSvc<T> {
+                        type Response = super::FileRescanResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileRescanRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_rescan(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileRescanSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/FileCheckIgnore" => {
+                    #[allow(non_camel_case_types)]
+                    struct FileCheckIgnoreSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::FileCheckIgnoreRequest>
+                    for FileCheckIgnoreSvc<T> {
+                        type Response = super::FileCheckIgnoreResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FileCheckIgnoreRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::file_check_ignore(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FileCheckIgnoreSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostList" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostListSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostListRequest>
+                    for HostListSvc<T> {
+                        type Response = super::HostListResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_list(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostListSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostDiscover" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostDiscoverSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostDiscoverRequest>
+                    for HostDiscoverSvc<T> {
+                        type Response = super::HostDiscoverResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostDiscoverRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_discover(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostDiscoverSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostDiscoverStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostDiscoverStreamSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::ServerStreamingService<super::HostDiscoverRequest>
+                    for HostDiscoverStreamSvc<T> {
+                        type Response = super::HostDiscoverStreamResponse;
+                        type ResponseStream = T::HostDiscoverStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostDiscoverRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_discover_stream(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostDiscoverStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostAdd" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostAddSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostAddRequest>
+                    for HostAddSvc<T> {
+                        type Response = super::HostAddResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostAddRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_add(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostAddSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostRemove" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostRemoveSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostRemoveRequest>
+                    for HostRemoveSvc<T> {
+                        type Response = super::HostRemoveResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostRemoveRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_remove(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostRemoveSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostUpdate" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostUpdateSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostUpdateRequest>
+                    for HostUpdateSvc<T> {
+                        type Response = super::HostUpdateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostUpdateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_update(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostUpdateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostAlias" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostAliasSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostAliasRequest>
+                    for HostAliasSvc<T> {
+                        type Response = super::HostAliasResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostAliasRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_alias(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostAliasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostBlock" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostBlockSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostBlockRequest>
+                    for HostBlockSvc<T> {
+                        type Response = super::HostBlockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostBlockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_block(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostBlockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostBlocklist" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostBlocklistSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostBlocklistRequest>
+                    for HostBlocklistSvc<T> {
+                        type Response = super::HostBlocklistResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostBlocklistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_blocklist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostBlocklistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostPair" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostPairSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostPairRequest>
+                    for HostPairSvc<T> {
+                        type Response = super::HostPairResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostPairRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_pair(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostPairSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostPairPending" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostPairPendingSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostPairPendingRequest>
+                    for HostPairPendingSvc<T> {
+                        type Response = super::HostPairPendingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostPairPendingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_pair_pending(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostPairPendingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/HostPairConfirm" => {
+                    #[allow(non_camel_case_types)]
+                    struct HostPairConfirmSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::HostPairConfirmRequest>
+                    for HostPairConfirmSvc<T> {
+                        type Response = super::HostPairConfirmResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HostPairConfirmRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::host_pair_confirm(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HostPairConfirmSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/GroupCreate" => {
                     #[allow(non_camel_case_types)]
-                    struct FileAddSvc<T: UserAgentService>(pub Arc<T>);
+                    struct GroupCreateSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::FileAddRequest>
-                    for FileAddSvc<T> {
-                        type Response = super::FileAddResponse;
+                    > tonic::server::UnaryService<super::GroupCreateRequest>
+                    for GroupCreateSvc<T> {
+                        type Response = super::GroupCreateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::FileAddRequest>,
+                            request: tonic::Request<super::GroupCreateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::file_add(&inner, request).await
+                                <T as UserAgentService>::group_create(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -719,7 +2961,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = FileAddSvc(inner);
+                        let method = GroupCreateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -735,25 +2977,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/FileRemove" => {
+                "/services.user_agent.UserAgentService/GroupDelete" => {
                     #[allow(non_camel_case_types)]
-                    struct FileRemoveSvc<T: UserAgentService>(pub Arc<T>);
+                    struct GroupDeleteSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::FileRemoveRequest>
-                    for FileRemoveSvc<T> {
-                        type Response = super::FileRemoveResponse;
+                    > tonic::server::UnaryService<super::GroupDeleteRequest>
+                    for GroupDeleteSvc<T> {
+                        type Response = super::GroupDeleteResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::FileRemoveRequest>,
+                            request: tonic::Request<super::GroupDeleteRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::file_remove(&inner, request).await
+                                <T as UserAgentService>::group_delete(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -764,7 +3006,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = FileRemoveSvc(inner);
+                        let method = GroupDeleteSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -780,25 +3022,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/FileList" => {
+                "/services.user_agent.UserAgentService/GroupList" => {
                     #[allow(non_camel_case_types)]
-                    struct FileListSvc<T: UserAgentService>(pub Arc<T>);
+                    struct GroupListSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::FileListRequest>
-                    for FileListSvc<T> {
-                        type Response = super::FileListResponse;
+                    > tonic::server::UnaryService<super::GroupListRequest>
+                    for GroupListSvc<T> {
+                        type Response = super::GroupListResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::FileListRequest>,
+                            request: tonic::Request<super::GroupListRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::file_list(&inner, request).await
+                                <T as UserAgentService>::group_list(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -809,7 +3051,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = FileListSvc(inner);
+                        let method = GroupListSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -825,25 +3067,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/FileCopy" => {
+                "/services.user_agent.UserAgentService/GroupShare" => {
                     #[allow(non_camel_case_types)]
-                    struct FileCopySvc<T: UserAgentService>(pub Arc<T>);
+                    struct GroupShareSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::FileCopyRequest>
-                    for FileCopySvc<T> {
-                        type Response = super::FileCopyResponse;
+                    > tonic::server::UnaryService<super::GroupShareRequest>
+                    for GroupShareSvc<T> {
+                        type Response = super::GroupShareResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::FileCopyRequest>,
+                            request: tonic::Request<super::GroupShareRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::file_copy(&inner, request).await
+                                <T as UserAgentService>::group_share(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -854,7 +3096,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = FileCopySvc(inner);
+                        let method = GroupShareSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -870,25 +3112,26 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/HostList" => {
+                "/services.user_agent.UserAgentService/GroupStrategy" => {
                     #[allow(non_camel_case_types)]
-                    struct HostListSvc<T: UserAgentService>(pub Arc<T>);
+                    struct GroupStrategySvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::HostListRequest>
-                    for HostListSvc<T> {
-                        type Response = super::HostListResponse;
+                    > tonic::server::UnaryService<super::GroupStrategyRequest>
+                    for GroupStrategySvc<T> {
+                        type Response = super::GroupStrategyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::HostListRequest>,
+                            request: tonic::Request<super::GroupStrategyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::host_list(&inner, request).await
+                                <T as UserAgentService>::group_strategy(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -899,7 +3142,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = HostListSvc(inner);
+                        let method = GroupStrategySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -915,26 +3158,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/HostDiscover" => {
+                "/services.user_agent.UserAgentService/RootAdd" => {
                     #[allow(non_camel_case_types)]
-                    struct HostDiscoverSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootAddSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::HostDiscoverRequest>
-                    for HostDiscoverSvc<T> {
-                        type Response = super::HostDiscoverResponse;
+                    > tonic::server::UnaryService<super::RootAddRequest>
+                    for RootAddSvc<T> {
+                        type Response = super::RootAddResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::HostDiscoverRequest>,
+                            request: tonic::Request<super::RootAddRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::host_discover(&inner, request)
-                                    .await
+                                <T as UserAgentService>::root_add(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -945,7 +3187,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = HostDiscoverSvc(inner);
+                        let method = RootAddSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -961,25 +3203,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/HostAdd" => {
+                "/services.user_agent.UserAgentService/RootRemove" => {
                     #[allow(non_camel_case_types)]
-                    struct HostAddSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootRemoveSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::HostAddRequest>
-                    for HostAddSvc<T> {
-                        type Response = super::HostAddResponse;
+                    > tonic::server::UnaryService<super::RootRemoveRequest>
+                    for RootRemoveSvc<T> {
+                        type Response = super::RootRemoveResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::HostAddRequest>,
+                            request: tonic::Request<super::RootRemoveRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::host_add(&inner, request).await
+                                <T as UserAgentService>::root_remove(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -990,7 +3232,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = HostAddSvc(inner);
+                        let method = RootRemoveSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1006,25 +3248,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/HostRemove" => {
+                "/services.user_agent.UserAgentService/RootList" => {
                     #[allow(non_camel_case_types)]
-                    struct HostRemoveSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootListSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::HostRemoveRequest>
-                    for HostRemoveSvc<T> {
-                        type Response = super::HostRemoveResponse;
+                    > tonic::server::UnaryService<super::RootListRequest>
+                    for RootListSvc<T> {
+                        type Response = super::RootListResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::HostRemoveRequest>,
+                            request: tonic::Request<super::RootListRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::host_remove(&inner, request).await
+                                <T as UserAgentService>::root_list(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1035,7 +3277,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = HostRemoveSvc(inner);
+                        let method = RootListSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1051,25 +3293,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/GroupCreate" => {
+                "/services.user_agent.UserAgentService/RootShare" => {
                     #[allow(non_camel_case_types)]
-                    struct GroupCreateSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootShareSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::GroupCreateRequest>
-                    for GroupCreateSvc<T> {
-                        type Response = super::GroupCreateResponse;
+                    > tonic::server::UnaryService<super::RootShareRequest>
+                    for RootShareSvc<T> {
+                        type Response = super::RootShareResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GroupCreateRequest>,
+                            request: tonic::Request<super::RootShareRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::group_create(&inner, request).await
+                                <T as UserAgentService>::root_share(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1080,7 +3322,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GroupCreateSvc(inner);
+                        let method = RootShareSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1096,25 +3338,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/GroupDelete" => {
+                "/services.user_agent.UserAgentService/RootSync" => {
                     #[allow(non_camel_case_types)]
-                    struct GroupDeleteSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootSyncSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::GroupDeleteRequest>
-                    for GroupDeleteSvc<T> {
-                        type Response = super::GroupDeleteResponse;
+                    > tonic::server::UnaryService<super::RootSyncRequest>
+                    for RootSyncSvc<T> {
+                        type Response = super::RootSyncResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GroupDeleteRequest>,
+                            request: tonic::Request<super::RootSyncRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::group_delete(&inner, request).await
+                                <T as UserAgentService>::root_sync(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1125,7 +3367,7 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GroupDeleteSvc(inner);
+                        let method = RootSyncSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1141,25 +3383,25 @@ pub mod user_agent_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/services.user_agent.UserAgentService/GroupList" => {
+                "/services.user_agent.UserAgentService/RootUnsync" => {
                     #[allow(non_camel_case_types)]
-                    struct GroupListSvc<T: UserAgentService>(pub Arc<T>);
+                    struct RootUnsyncSvc<T: UserAgentService>(pub Arc<T>);
                     impl<
                         T: UserAgentService,
-                    > tonic::server::UnaryService<super::GroupListRequest>
-                    for GroupListSvc<T> {
-                        type Response = super::GroupListResponse;
+                    > tonic::server::UnaryService<super::RootUnsyncRequest>
+                    for RootUnsyncSvc<T> {
+                        type Response = super::RootUnsyncResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GroupListRequest>,
+                            request: tonic::Request<super::RootUnsyncRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserAgentService>::group_list(&inner, request).await
+                                <T as UserAgentService>::root_unsync(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1170,7 +3412,145 @@ pub mod user_agent_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GroupListSvc(inner);
+                        let method = RootUnsyncSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/ConflictList" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConflictListSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::ConflictListRequest>
+                    for ConflictListSvc<T> {
+                        type Response = super::ConflictListResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConflictListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::conflict_list(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConflictListSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/ConflictShow" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConflictShowSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::ConflictShowRequest>
+                    for ConflictShowSvc<T> {
+                        type Response = super::ConflictShowResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConflictShowRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::conflict_show(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConflictShowSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.user_agent.UserAgentService/ConflictResolve" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConflictResolveSvc<T: UserAgentService>(pub Arc<T>);
+                    impl<
+                        T: UserAgentService,
+                    > tonic::server::UnaryService<super::ConflictResolveRequest>
+                    for ConflictResolveSvc<T> {
+                        type Response = super::ConflictResolveResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConflictResolveRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserAgentService>::conflict_resolve(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConflictResolveSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
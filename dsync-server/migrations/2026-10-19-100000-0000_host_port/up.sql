@@ -0,0 +1,4 @@
+-- Port the daemon of given host listens on. Existing rows were all discovered
+-- on the default port.
+
+alter table hosts add column port integer not null default 50051;
@@ -0,0 +1 @@
+alter table hosts rename column address to ipv4_addr;
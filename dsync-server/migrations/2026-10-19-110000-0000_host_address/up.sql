@@ -0,0 +1 @@
+alter table hosts rename column ipv4_addr to address;
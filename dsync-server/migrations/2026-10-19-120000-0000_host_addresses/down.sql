@@ -0,0 +1 @@
+drop table if exists host_addresses;
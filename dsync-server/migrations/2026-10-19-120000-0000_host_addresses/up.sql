@@ -0,0 +1,14 @@
+create table if not exists host_addresses (
+    host_uuid text not null,
+    address text not null,
+    port integer not null,
+    -- Last time the host has been seen at this address
+    last_seen bigint not null,
+    primary key (host_uuid, address, port),
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
+
+insert into host_addresses (host_uuid, address, port, last_seen)
+select uuid, address, port, discovery_time from hosts;
@@ -0,0 +1,3 @@
+alter table hosts drop column capabilities;
+alter table hosts drop column build_version;
+alter table hosts drop column protocol_version;
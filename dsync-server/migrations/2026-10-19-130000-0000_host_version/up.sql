@@ -0,0 +1,4 @@
+alter table hosts add column protocol_version integer not null default 0;
+alter table hosts add column build_version text not null default '';
+-- Comma separated list
+alter table hosts add column capabilities text not null default '';
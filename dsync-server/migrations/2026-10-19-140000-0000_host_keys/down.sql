@@ -0,0 +1,2 @@
+drop table local_identity;
+alter table hosts drop column public_key;
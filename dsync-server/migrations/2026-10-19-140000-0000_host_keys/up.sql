@@ -0,0 +1,8 @@
+-- Hex encoded ed25519 public key. For remote hosts set only once pairing is confirmed.
+alter table hosts add column public_key text;
+
+-- Long-term keypair of this server. Single row.
+create table local_identity (
+  id integer primary key not null check (id = 0),
+  secret_key blob not null
+);
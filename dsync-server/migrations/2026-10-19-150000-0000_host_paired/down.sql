@@ -0,0 +1,2 @@
+update hosts set public_key = null where paired = false;
+alter table hosts drop column paired;
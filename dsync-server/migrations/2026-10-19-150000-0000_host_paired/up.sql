@@ -0,0 +1,3 @@
+-- Keys are pinned on the first contact now, pairing marks them as verified by the user.
+alter table hosts add column paired boolean not null default false;
+update hosts set paired = true where public_key is not null;
@@ -0,0 +1,2 @@
+drop table if exists file_access;
+drop table if exists group_access;
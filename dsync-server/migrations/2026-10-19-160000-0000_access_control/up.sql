@@ -0,0 +1,28 @@
+-- Access of remote hosts to local groups & files. Hosts with no entry have no access.
+create table if not exists group_access (
+    group_id integer not null,
+    host_uuid text not null,
+    can_read boolean not null default false,
+    can_write boolean not null default false,
+    primary key (group_id, host_uuid),
+    foreign key (group_id) references groups_local (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
+
+create table if not exists file_access (
+    file_id integer not null,
+    host_uuid text not null,
+    can_read boolean not null default false,
+    can_write boolean not null default false,
+    primary key (file_id, host_uuid),
+    foreign key (file_id) references files_local (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
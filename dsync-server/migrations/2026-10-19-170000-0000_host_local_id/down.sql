@@ -0,0 +1,2 @@
+drop index hosts_local_id;
+alter table hosts drop column local_id;
@@ -0,0 +1,8 @@
+-- Stable ids, which the user refers to hosts by. The local host is always 0,
+-- remote hosts are numbered in the order they have been discovered in.
+alter table hosts add column local_id integer not null default 0;
+update hosts set local_id = case
+    when is_remote = false then 0
+    else (select count(*) from hosts h where h.is_remote = true and h.rowid <= hosts.rowid)
+end;
+create unique index hosts_local_id on hosts (local_id);
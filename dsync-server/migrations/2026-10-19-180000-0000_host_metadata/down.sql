@@ -0,0 +1,28 @@
+drop table host_tags;
+drop table host_aliases;
+
+create table hosts_old (
+    uuid text not null unique primary key,
+    name text not null unique,
+    hostname text not null unique,
+    is_remote boolean not null check (is_remote in (0, 1)),
+    address text not null,
+    discovery_time bigint not null,
+    port integer not null default 50051,
+    protocol_version integer not null default 0,
+    build_version text not null default '',
+    capabilities text not null default '',
+    public_key text,
+    paired boolean not null default false,
+    local_id integer not null default 0
+);
+
+insert into hosts_old
+select
+    uuid, name, hostname, is_remote, address, discovery_time, port, protocol_version,
+    build_version, capabilities, public_key, paired, local_id
+from hosts;
+
+drop table hosts;
+alter table hosts_old rename to hosts;
+create unique index hosts_local_id on hosts (local_id);
@@ -0,0 +1,49 @@
+-- Names are editable display names now, distinct hosts might share a hostname.
+-- SQLite can not drop unique constraints, the table has to be rebuilt.
+create table hosts_new (
+    uuid text not null unique primary key,
+    name text not null,
+    hostname text not null,
+    is_remote boolean not null check (is_remote in (0, 1)),
+    address text not null,
+    discovery_time bigint not null,
+    port integer not null default 50051,
+    protocol_version integer not null default 0,
+    build_version text not null default '',
+    capabilities text not null default '',
+    public_key text,
+    paired boolean not null default false,
+    local_id integer not null default 0,
+    notes text not null default ''
+);
+
+insert into hosts_new (
+    uuid, name, hostname, is_remote, address, discovery_time, port, protocol_version,
+    build_version, capabilities, public_key, paired, local_id
+)
+select
+    uuid, name, hostname, is_remote, address, discovery_time, port, protocol_version,
+    build_version, capabilities, public_key, paired, local_id
+from hosts;
+
+drop table hosts;
+alter table hosts_new rename to hosts;
+create unique index hosts_local_id on hosts (local_id);
+
+-- Alternative names of hosts, usable wherever a host is specified.
+create table if not exists host_aliases (
+    alias text not null primary key,
+    host_uuid text not null,
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
+
+create table if not exists host_tags (
+    host_uuid text not null,
+    tag text not null,
+    primary key (host_uuid, tag),
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
@@ -0,0 +1,5 @@
+-- Hosts which are never stored nor contacted. A rule is a uuid, an address or a subnet
+-- in CIDR notation.
+create table if not exists host_blocklist (
+    rule text not null primary key
+);
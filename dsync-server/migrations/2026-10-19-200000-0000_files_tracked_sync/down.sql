@@ -0,0 +1,9 @@
+drop table files_tracked;
+create table if not exists files_tracked (
+    local_id integer not null primary key,
+    peer_uuid text not null,
+    remote_id integer not null unique,
+    foreign key (local_id) references files_local (
+        id
+    ) on update cascade on delete cascade
+);
@@ -0,0 +1,18 @@
+-- A local file might be synced with one file of every peer. Remote ids are ids of files
+-- tracked by the peer, so they are unique per peer only.
+drop table files_tracked;
+create table if not exists files_tracked (
+    local_id integer not null,
+    peer_uuid text not null,
+    remote_id integer not null,
+    -- Hash both sides had after the last successful sync, null until the first one.
+    last_synced_hash text,
+    primary key (local_id, peer_uuid),
+    unique (peer_uuid, remote_id),
+    foreign key (local_id) references files_local (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (peer_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
@@ -0,0 +1,12 @@
+create table files_local_old (
+    id integer not null primary key,
+    file_path text not null unique,
+    hash_sha1 text not null unique
+);
+
+insert into files_local_old (id, file_path, hash_sha1)
+select id, file_path, hash_sha1
+from files_local;
+
+drop table files_local;
+alter table files_local_old rename to files_local;
@@ -0,0 +1,16 @@
+-- Hashes follow the file contents now, distinct files might have the same content.
+-- SQLite can not drop unique constraints, the table has to be rebuilt.
+create table files_local_new (
+    id integer not null primary key,
+    file_path text not null unique,
+    hash_sha1 text not null,
+    -- Unix timestamp (nanoseconds) of the last modification of the file.
+    mtime bigint not null default 0
+);
+
+insert into files_local_new (id, file_path, hash_sha1)
+select id, file_path, hash_sha1
+from files_local;
+
+drop table files_local;
+alter table files_local_new rename to files_local;
@@ -0,0 +1,3 @@
+alter table files_local drop column gone;
+alter table files_local drop column inode;
+alter table files_local drop column size;
@@ -0,0 +1,5 @@
+-- Compared with the file on disk, so that only files which have changed are rehashed.
+alter table files_local add column size bigint not null default 0;
+alter table files_local add column inode bigint not null default 0;
+-- The file has not been found on disk during the last check.
+alter table files_local add column gone boolean not null default false;
@@ -0,0 +1,5 @@
+drop table roots_tracked;
+drop table root_access;
+delete from files_local where root_id is not null;
+alter table files_local drop column root_id;
+drop table sync_roots;
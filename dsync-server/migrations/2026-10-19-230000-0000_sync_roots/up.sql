@@ -0,0 +1,39 @@
+-- Directories whose whole subtrees are tracked. Files found in a root are tracked in files_local.
+create table if not exists sync_roots (
+    id integer not null primary key,
+    path text not null unique
+);
+
+alter table files_local add column root_id integer references sync_roots (
+    id
+) on update cascade on delete cascade;
+
+-- Access of remote hosts to all files of a root, including files which do not exist yet.
+create table if not exists root_access (
+    root_id integer not null,
+    host_uuid text not null,
+    can_read boolean not null default false,
+    can_write boolean not null default false,
+    primary key (root_id, host_uuid),
+    foreign key (root_id) references sync_roots (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (host_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
+
+-- Local roots synced with roots of peers, matching files by their relative paths.
+create table if not exists roots_tracked (
+    root_id integer not null,
+    peer_uuid text not null,
+    remote_root_id integer not null,
+    primary key (root_id, peer_uuid),
+    unique (peer_uuid, remote_root_id),
+    foreign key (root_id) references sync_roots (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (peer_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
@@ -0,0 +1,2 @@
+drop table file_conflicts;
+alter table files_local drop column version;
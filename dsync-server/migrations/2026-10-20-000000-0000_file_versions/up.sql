@@ -0,0 +1,26 @@
+-- Version vector of the file as a JSON object: modifications counted per host uuid.
+alter table files_local add column version text not null default '{}';
+
+-- Files tracked so far have been modified on this host only, as far as it knows.
+update files_local set version = coalesce(
+    (select '{"' || uuid || '":1}' from hosts where is_remote = false), '{}'
+);
+
+-- Synced files modified concurrently on both sides, kept until the user resolves them.
+create table if not exists file_conflicts (
+    file_id integer not null,
+    peer_uuid text not null,
+    -- Hash & version of the remote file, when the conflict was last looked at.
+    remote_hash text not null,
+    remote_version text not null,
+    -- Where the remote version is fetched to, next to the database.
+    copy_path text not null,
+    detected_at bigint not null,
+    primary key (file_id, peer_uuid),
+    foreign key (file_id) references files_local (
+        id
+    ) on update cascade on delete cascade,
+    foreign key (peer_uuid) references hosts (
+        uuid
+    ) on update cascade on delete cascade
+);
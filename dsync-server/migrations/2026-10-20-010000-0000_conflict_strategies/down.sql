@@ -0,0 +1,2 @@
+alter table groups_local drop column host_priority;
+alter table groups_local drop column conflict_strategy;
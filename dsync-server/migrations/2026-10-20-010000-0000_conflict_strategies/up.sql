@@ -0,0 +1,4 @@
+-- How conflicts of files of the group are resolved, see `conflict::ConflictStrategy`.
+alter table groups_local add column conflict_strategy text not null default 'manual';
+-- Comma separated uuids of hosts, the first one wins conflicts of the host-priority strategy.
+alter table groups_local add column host_priority text not null default '';
@@ -0,0 +1,12 @@
+-- Deletions of tracked files, kept for peers to learn about them until garbage-collected.
+create table if not exists file_tombstones (
+    file_id integer primary key not null,
+    -- Host the file has been deleted on first.
+    host_uuid text not null,
+    -- Version vector of the deletion as a JSON object.
+    version text not null,
+    deleted_at bigint not null,
+    foreign key (file_id) references files_local (
+        id
+    ) on update cascade on delete cascade
+);
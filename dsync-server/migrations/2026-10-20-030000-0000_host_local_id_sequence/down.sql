@@ -0,0 +1 @@
+drop table host_local_id_sequence;
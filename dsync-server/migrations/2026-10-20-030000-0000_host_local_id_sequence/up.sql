@@ -0,0 +1,8 @@
+-- Local ids are never handed out again, not even those of removed hosts.
+-- Single row table with the id the next inserted host gets.
+create table if not exists host_local_id_sequence (
+    id integer primary key not null check (id = 0),
+    next_local_id integer not null
+);
+insert into host_local_id_sequence (id, next_local_id)
+    select 0, coalesce(max(local_id) + 1, 0) from hosts;
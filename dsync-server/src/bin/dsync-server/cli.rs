@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use dsync_server::server::config::DiscoveryBackendKind;
 
 #[derive(Parser, Clone)]
 #[command(about)]
@@ -13,6 +14,12 @@ pub(crate) struct Args {
     )]
     pub env_file: Option<PathBuf>,
 
+    #[arg(
+        long,
+        help = "Path to TOML configuration file. When not specified, CONFIG_FILE env variable is used & then `config.toml` from the XDG config directory (if present)."
+    )]
+    pub config_file: Option<PathBuf>,
+
     #[arg(
         long,
         help = "Path to local db. This will overwrite DATABASE_URL env variable if set. Might be necessary to use in case of running the server binary outside of dsync-server workspace dir."
@@ -21,10 +28,29 @@ pub(crate) struct Args {
 
     #[arg(
         long,
-        help = "Port number for the server to listen on. This will overwrite SERVER_PORT env variable if set. **Currently it is not recommended to use this option as the host discovery mechanism relies on a fixed port number.**"
+        help = "Port number for the server to listen on. This will overwrite SERVER_PORT env variable if set. Peers record this port during handshake; sweep & neighbour discovery probe the port set by SCAN_PORT."
     )]
     pub port: Option<u16>,
 
+    #[arg(
+        long,
+        help = "Port number for the control services (used by dsync-cli) to listen on, loopback only. This will overwrite CONTROL_PORT env variable if set."
+    )]
+    pub control_port: Option<u16>,
+
+    #[arg(
+        long,
+        help = "Path of the Unix socket for the control services. This will overwrite CONTROL_SOCKET env variable if set. Defaults to control.sock in the dsync XDG runtime dir."
+    )]
+    pub control_socket: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Mechanism used to discover peers in local network. This will overwrite DISCOVERY_BACKEND env variable if set."
+    )]
+    pub discovery_backend: Option<DiscoveryBackendKind>,
+
     #[arg(short = 'l', long, help = "Configure logging level of the client.")]
     pub log_level: Option<log::LevelFilter>,
 }
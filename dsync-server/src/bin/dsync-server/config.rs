@@ -1,28 +1,83 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
-use dsync_server::server::config::{Config, keys::ENV_FILE};
+use dsync_server::server::config::{
+    BlockRule, Config, DiscoveryBackendKind, DiscoveryConfig, StaticPeer, defaults, keys::ENV_FILE,
+};
+use ipnet::Ipv4Net;
+use serde::Deserialize;
 
 use crate::config::provider::PartialConfigProvider;
 
 pub mod error;
 pub mod provider;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PartialConfig {
     pub port: Option<u16>,
+    pub control_port: Option<u16>,
+    pub control_socket: Option<PathBuf>,
     pub database_url: Option<PathBuf>,
+    pub discovery_backend: Option<DiscoveryBackendKind>,
+    pub beacon_port: Option<u16>,
+    pub beacon_interval_secs: Option<u64>,
+    pub beacon_secret: Option<String>,
+    pub scan_port: Option<u16>,
+    pub scan_subnets: Option<Vec<Ipv4Net>>,
+    pub scan_timeout_ms: Option<u64>,
+    pub scan_concurrency: Option<usize>,
+    pub hello_concurrency: Option<usize>,
+    pub heartbeat_interval_secs: Option<u64>,
+    pub static_peers: Option<Vec<StaticPeer>>,
+    pub static_peers_interval_secs: Option<u64>,
+    pub discovery_interfaces: Option<Vec<String>>,
+    pub blocklist: Option<Vec<BlockRule>>,
+    pub sync_interval_secs: Option<u64>,
+    pub rescan_interval_secs: Option<u64>,
+    pub tombstone_retention_secs: Option<u64>,
+    pub ignore_patterns: Option<Vec<String>>,
 }
 
 impl PartialConfig {
     pub fn new(port: Option<u16>, database_url: Option<PathBuf>) -> Self {
-        Self { port, database_url }
+        Self {
+            port,
+            database_url,
+            ..Default::default()
+        }
     }
 
     /// Merge two PartialConfig instances, prioritizing values from self.
     pub fn merge(self, other: PartialConfig) -> Self {
         Self {
             port: self.port.or(other.port),
+            control_port: self.control_port.or(other.control_port),
+            control_socket: self.control_socket.or(other.control_socket),
             database_url: self.database_url.or(other.database_url),
+            discovery_backend: self.discovery_backend.or(other.discovery_backend),
+            beacon_port: self.beacon_port.or(other.beacon_port),
+            beacon_interval_secs: self.beacon_interval_secs.or(other.beacon_interval_secs),
+            beacon_secret: self.beacon_secret.or(other.beacon_secret),
+            scan_port: self.scan_port.or(other.scan_port),
+            scan_subnets: self.scan_subnets.or(other.scan_subnets),
+            scan_timeout_ms: self.scan_timeout_ms.or(other.scan_timeout_ms),
+            scan_concurrency: self.scan_concurrency.or(other.scan_concurrency),
+            hello_concurrency: self.hello_concurrency.or(other.hello_concurrency),
+            heartbeat_interval_secs: self
+                .heartbeat_interval_secs
+                .or(other.heartbeat_interval_secs),
+            static_peers: self.static_peers.or(other.static_peers),
+            static_peers_interval_secs: self
+                .static_peers_interval_secs
+                .or(other.static_peers_interval_secs),
+            discovery_interfaces: self.discovery_interfaces.or(other.discovery_interfaces),
+            blocklist: self.blocklist.or(other.blocklist),
+            sync_interval_secs: self.sync_interval_secs.or(other.sync_interval_secs),
+            rescan_interval_secs: self.rescan_interval_secs.or(other.rescan_interval_secs),
+            tombstone_retention_secs: self
+                .tombstone_retention_secs
+                .or(other.tombstone_retention_secs),
+            ignore_patterns: self.ignore_patterns.or(other.ignore_patterns),
         }
     }
 }
@@ -38,7 +93,107 @@ impl TryInto<Config> for PartialConfig {
             .database_url
             .ok_or_else(|| anyhow::anyhow!("Database URL is required"))?;
 
-        Ok(Config { port, database_url })
+        let discovery = DiscoveryConfig {
+            backend: self
+                .discovery_backend
+                .unwrap_or(defaults::DISCOVERY_BACKEND),
+            beacon_port: self.beacon_port.unwrap_or(defaults::BEACON_PORT),
+            beacon_interval: self
+                .beacon_interval_secs
+                .map(Duration::from_secs)
+                .unwrap_or(defaults::BEACON_INTERVAL),
+            beacon_secret: self
+                .beacon_secret
+                .unwrap_or_else(|| defaults::BEACON_SECRET.to_owned()),
+            scan_port: self.scan_port.unwrap_or(defaults::SERVER_PORT),
+            scan_subnets: self.scan_subnets.unwrap_or_default(),
+            scan_timeout: self
+                .scan_timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults::SCAN_TIMEOUT),
+            scan_concurrency: self.scan_concurrency.unwrap_or(defaults::SCAN_CONCURRENCY),
+            hello_concurrency: self
+                .hello_concurrency
+                .unwrap_or(defaults::HELLO_CONCURRENCY),
+            static_peers: self.static_peers.unwrap_or_default(),
+            static_peers_interval: self
+                .static_peers_interval_secs
+                .map(Duration::from_secs)
+                .unwrap_or(defaults::STATIC_PEERS_INTERVAL),
+            interfaces: self.discovery_interfaces.unwrap_or_default(),
+        };
+
+        if discovery.beacon_interval.is_zero() {
+            anyhow::bail!("Beacon interval must be greater than zero");
+        }
+
+        if discovery.static_peers_interval.is_zero() {
+            anyhow::bail!("Static peers interval must be greater than zero");
+        }
+
+        if discovery.scan_concurrency == 0 || discovery.hello_concurrency == 0 {
+            anyhow::bail!("Scan & hello concurrency must be greater than zero");
+        }
+
+        let heartbeat_interval = self
+            .heartbeat_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(defaults::HEARTBEAT_INTERVAL);
+
+        if heartbeat_interval.is_zero() {
+            anyhow::bail!("Heartbeat interval must be greater than zero");
+        }
+
+        let sync_interval = self
+            .sync_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(defaults::SYNC_INTERVAL);
+
+        if sync_interval.is_zero() {
+            anyhow::bail!("Sync interval must be greater than zero");
+        }
+
+        let rescan_interval = self
+            .rescan_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(defaults::RESCAN_INTERVAL);
+
+        if rescan_interval.is_zero() {
+            anyhow::bail!("Rescan interval must be greater than zero");
+        }
+
+        let tombstone_retention = self
+            .tombstone_retention_secs
+            .map(Duration::from_secs)
+            .unwrap_or(defaults::TOMBSTONE_RETENTION);
+
+        if tombstone_retention.is_zero() {
+            anyhow::bail!("Tombstone retention must be greater than zero");
+        }
+
+        let control_port = self.control_port.unwrap_or(defaults::CONTROL_PORT);
+        if control_port == port {
+            anyhow::bail!("Control port must differ from the port peers connect to");
+        }
+
+        Ok(Config {
+            port,
+            control_port,
+            control_socket: self.control_socket,
+            database_url,
+            discovery,
+            heartbeat_interval,
+            blocklist: self.blocklist.unwrap_or_default(),
+            sync_interval,
+            rescan_interval,
+            tombstone_retention,
+            ignore_patterns: self.ignore_patterns.unwrap_or_else(|| {
+                defaults::IGNORE_PATTERNS
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect()
+            }),
+        })
     }
 }
 
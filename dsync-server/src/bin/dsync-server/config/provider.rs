@@ -2,10 +2,12 @@ use crate::config::{PartialConfig, error::ConfigLoadError};
 
 mod cli_provider;
 mod env_provider;
+mod file_provider;
 mod xdg_provider;
 
 pub use cli_provider::CliArgsConfigProvider;
 pub use env_provider::EnvConfigProvider;
+pub use file_provider::FileConfigProvider;
 pub use xdg_provider::XdgConfigProvider;
 
 pub trait PartialConfigProvider {
@@ -26,11 +26,23 @@ impl PartialConfigProvider for CliArgsConfigProvider {
             config.port = Some(port);
         }
 
+        if let Some(control_port) = self.args.control_port {
+            config.control_port = Some(control_port);
+        }
+
+        if let Some(ref control_socket) = self.args.control_socket {
+            config.control_socket = Some(control_socket.clone());
+        }
+
         if let Some(ref database_url) = self.args.db_file {
             let db_path = PathBuf::from(database_url);
             config.database_url = Some(db_path);
         }
 
+        if let Some(discovery_backend) = self.args.discovery_backend {
+            config.discovery_backend = Some(discovery_backend);
+        }
+
         Ok(config)
     }
 }
@@ -1,6 +1,10 @@
 use std::path::PathBuf;
 
-use dsync_server::server;
+use dsync_server::server::{
+    self,
+    config::{BlockRule, StaticPeer},
+};
+use ipnet::Ipv4Net;
 
 use crate::config::{
     PartialConfig,
@@ -34,6 +38,165 @@ impl PartialConfigProvider for EnvConfigProvider {
             config.port = Some(port);
         }
 
+        if let Ok(control_port) = dotenvy::var(server::config::keys::CONTROL_PORT) {
+            let port = control_port
+                .parse::<u16>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.control_port = Some(port);
+        }
+
+        if let Ok(control_socket) = dotenvy::var(server::config::keys::CONTROL_SOCKET) {
+            config.control_socket = Some(PathBuf::from(control_socket));
+        }
+
+        if let Ok(backend) = dotenvy::var(server::config::keys::DISCOVERY_BACKEND) {
+            let backend = backend
+                .parse()
+                .map_err(|err: anyhow::Error| ConfigLoadError::ParseError(err.to_string()))?;
+            config.discovery_backend = Some(backend);
+        }
+
+        if let Ok(beacon_port) = dotenvy::var(server::config::keys::BEACON_PORT) {
+            let beacon_port = beacon_port
+                .parse::<u16>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.beacon_port = Some(beacon_port);
+        }
+
+        if let Ok(interval) = dotenvy::var(server::config::keys::BEACON_INTERVAL_SECS) {
+            let interval = interval
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.beacon_interval_secs = Some(interval);
+        }
+
+        if let Ok(secret) = dotenvy::var(server::config::keys::BEACON_SECRET) {
+            config.beacon_secret = Some(secret);
+        }
+
+        if let Ok(scan_port) = dotenvy::var(server::config::keys::SCAN_PORT) {
+            let scan_port = scan_port
+                .parse::<u16>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.scan_port = Some(scan_port);
+        }
+
+        // Comma separated list of subnets in CIDR notation, e.g. `192.168.1.0/24,10.0.0.0/24`.
+        if let Ok(subnets) = dotenvy::var(server::config::keys::SCAN_SUBNETS) {
+            let subnets = subnets
+                .split(',')
+                .map(str::trim)
+                .filter(|subnet| !subnet.is_empty())
+                .map(|subnet| subnet.parse::<Ipv4Net>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.scan_subnets = Some(subnets);
+        }
+
+        if let Ok(timeout) = dotenvy::var(server::config::keys::SCAN_TIMEOUT_MS) {
+            let timeout = timeout
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.scan_timeout_ms = Some(timeout);
+        }
+
+        if let Ok(concurrency) = dotenvy::var(server::config::keys::SCAN_CONCURRENCY) {
+            let concurrency = concurrency
+                .parse::<usize>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.scan_concurrency = Some(concurrency);
+        }
+
+        if let Ok(concurrency) = dotenvy::var(server::config::keys::HELLO_CONCURRENCY) {
+            let concurrency = concurrency
+                .parse::<usize>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.hello_concurrency = Some(concurrency);
+        }
+
+        if let Ok(interval) = dotenvy::var(server::config::keys::HEARTBEAT_INTERVAL_SECS) {
+            let interval = interval
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.heartbeat_interval_secs = Some(interval);
+        }
+
+        // Comma separated list of `[UUID@]ADDRESS[:PORT]` entries.
+        if let Ok(static_peers) = dotenvy::var(server::config::keys::STATIC_PEERS) {
+            let static_peers = static_peers
+                .split(',')
+                .map(str::trim)
+                .filter(|peer| !peer.is_empty())
+                .map(|peer| peer.parse::<StaticPeer>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.static_peers = Some(static_peers);
+        }
+
+        if let Ok(interval) = dotenvy::var(server::config::keys::STATIC_PEERS_INTERVAL_SECS) {
+            let interval = interval
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.static_peers_interval_secs = Some(interval);
+        }
+
+        if let Ok(interval) = dotenvy::var(server::config::keys::SYNC_INTERVAL_SECS) {
+            let interval = interval
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.sync_interval_secs = Some(interval);
+        }
+
+        if let Ok(interval) = dotenvy::var(server::config::keys::RESCAN_INTERVAL_SECS) {
+            let interval = interval
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.rescan_interval_secs = Some(interval);
+        }
+
+        if let Ok(retention) = dotenvy::var(server::config::keys::TOMBSTONE_RETENTION_SECS) {
+            let retention = retention
+                .parse::<u64>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.tombstone_retention_secs = Some(retention);
+        }
+
+        // Comma separated list of patterns.
+        if let Ok(patterns) = dotenvy::var(server::config::keys::IGNORE_PATTERNS) {
+            config.ignore_patterns = Some(
+                patterns
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+            );
+        }
+
+        // Comma separated list of interface names.
+        if let Ok(interfaces) = dotenvy::var(server::config::keys::DISCOVERY_INTERFACES) {
+            config.discovery_interfaces = Some(
+                interfaces
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|iface| !iface.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+            );
+        }
+
+        // Comma separated list of uuids, addresses & subnets.
+        if let Ok(blocklist) = dotenvy::var(server::config::keys::BLOCKLIST) {
+            let blocklist = blocklist
+                .split(',')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(|rule| rule.parse::<BlockRule>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ConfigLoadError::ParseError(err.to_string()))?;
+            config.blocklist = Some(blocklist);
+        }
+
         Ok(config)
     }
 }
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use dsync_server::server;
+
+use crate::config::{
+    PartialConfig,
+    provider::{ConfigLoadError, PartialConfigProvider},
+};
+
+/// FileConfigProvider loads configuration from a TOML file. Keys are the same as
+/// the fields of `PartialConfig`, e.g.:
+///
+/// ```toml
+/// port = 50051
+/// discovery_backend = "broadcast"
+/// beacon_secret = "my-home-network"
+/// discovery_interfaces = ["eth0"]
+/// blocklist = ["10.8.0.0/16", "192.168.1.7", "5f0c..."]
+/// ignore_patterns = [".git/", "target/", "node_modules/", "*.swp"]
+///
+/// [[static_peers]]
+/// address = "10.8.0.2"
+/// port = 50051
+/// uuid = "5f0c..."
+/// ```
+///
+/// The file is looked up in following order: explicitly passed path, `CONFIG_FILE` env variable,
+/// `config.toml` in the XDG config directory. Only an explicitly requested file is required to exist.
+pub struct FileConfigProvider {
+    explicit_path: Option<PathBuf>,
+}
+
+impl FileConfigProvider {
+    pub fn new(explicit_path: Option<PathBuf>) -> Self {
+        Self { explicit_path }
+    }
+
+    fn resolve_path(&self) -> Option<(PathBuf, bool)> {
+        if let Some(ref path) = self.explicit_path {
+            return Some((path.clone(), true));
+        }
+
+        if let Ok(path) = dotenvy::var(server::config::keys::CONFIG_FILE) {
+            return Some((PathBuf::from(path), true));
+        }
+
+        xdg::BaseDirectories::with_prefix("dsync")
+            .find_config_file("config.toml")
+            .map(|path| (path, false))
+    }
+}
+
+impl PartialConfigProvider for FileConfigProvider {
+    fn load_partial_config(&self) -> Result<PartialConfig, ConfigLoadError> {
+        let Some((path, required)) = self.resolve_path() else {
+            return Ok(PartialConfig::default());
+        };
+
+        if !path.is_file() {
+            if required {
+                return Err(ConfigLoadError::Other(format!(
+                    "Config file {path:?} does not exist or is not a regular file"
+                )));
+            }
+            return Ok(PartialConfig::default());
+        }
+
+        log::info!("Loading config file {path:?}");
+
+        let content = std::fs::read_to_string(&path).map_err(ConfigLoadError::IoError)?;
+        toml::from_str(&content).map_err(|err| ConfigLoadError::ParseError(err.to_string()))
+    }
+}
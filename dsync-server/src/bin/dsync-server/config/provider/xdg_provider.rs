@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use crate::config::{PartialConfig, error::ConfigLoadError, provider::PartialConfigProvider};
 
-/// This one provides only path for the database
+/// This one provides only paths: of the database & of the control socket
 pub struct XdgConfigProvider {}
 
 impl XdgConfigProvider {
@@ -19,6 +19,12 @@ impl PartialConfigProvider for XdgConfigProvider {
 
         let db_path = xdg_dirs.place_state_file(&relative_db_path);
 
-        Ok(PartialConfig::new(None, db_path.ok()))
+        let control_socket =
+            xdg_dirs.place_runtime_file(dsync_shared::default::CONTROL_SOCKET_NAME);
+
+        Ok(PartialConfig {
+            control_socket: control_socket.ok(),
+            ..PartialConfig::new(None, db_path.ok())
+        })
     }
 }
@@ -22,9 +22,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         log::warn!("Failed to load environment variables: {}", err);
     };
 
+    let config_file = args.config_file.clone();
     let config = load_config(&[
         &config::provider::CliArgsConfigProvider::new(args),
         &config::provider::EnvConfigProvider::new(),
+        &config::provider::FileConfigProvider::new(config_file),
         &config::provider::XdgConfigProvider::new(),
     ])
     .context("Failed to load configuration")?;
@@ -1,30 +1,52 @@
 use std::{
-    net::{Ipv4Addr, SocketAddrV4},
+    fs::Permissions,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    os::unix::fs::PermissionsExt,
     process::Command,
     sync::Arc,
 };
 
 use anyhow::Context;
-use config::Config;
+use config::{Config, defaults};
 use context::ServerContext;
 use data::repo::{DataRepository, MainDataRepository};
 use data::source::SqliteDataSource;
-use diesel::{Connection, SqliteConnection, sqlite::Sqlite};
+use diesel::{Connection, SqliteConnection};
 use dsync_proto::services::{
     file_transfer::file_transfer_service_server::FileTransferServiceServer,
     host_discovery::host_discovery_service_server::HostDiscoveryServiceServer,
     server_control::server_control_service_server::ServerControlServiceServer,
     user_agent::user_agent_service_server::UserAgentServiceServer,
 };
-use tokio::sync::oneshot;
+use identity::Identity;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::{
+    net::{TcpListener, UnixListener},
+    sync::{oneshot, watch},
+};
+use tokio_stream::wrappers::UnixListenerStream;
+use tonic::transport::server::TcpIncoming;
 use uuid::Uuid;
 
-use crate::server::data::source::sqlite::database::models::HostsRow;
+use crate::server::data::source::sqlite::database::models::{HostsRow, HostsVersionFragmentUpdate};
 
+pub(crate) mod blocklist;
 pub mod config;
+pub(crate) mod conflict;
 pub mod context;
 pub(crate) mod data;
+pub(crate) mod identity;
+pub(crate) mod ignore_rules;
+pub(crate) mod index;
+pub(crate) mod pairing;
+pub(crate) mod peers;
 pub(crate) mod service;
+pub(crate) mod sync;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub(crate) mod transfers;
+pub(crate) mod version_vector;
+pub(crate) mod watcher;
 
 pub struct Server {
     config: Config,
@@ -39,7 +61,25 @@ impl Server {
         log::info!("Starting the server instance");
 
         let repo_arc = self.create_data_repository().await?;
-        let server_ctx = Arc::new(ServerContext::new(self.config.clone(), repo_arc));
+        let identity = Self::load_identity(repo_arc.as_ref()).await?;
+        let discovery = service::tools::net::addr_discovery::make_discovery_backend(&self.config);
+        let server_ctx = Arc::new(ServerContext::new(
+            self.config.clone(),
+            repo_arc,
+            discovery.clone(),
+            identity,
+        ));
+
+        discovery
+            .start(server_ctx.clone())
+            .await
+            .context("Failed to start host discovery backend")?;
+
+        service::tools::net::heartbeat::spawn_heartbeat(server_ctx.clone());
+        service::tools::net::static_peers::spawn_static_peers(server_ctx.clone());
+        sync::spawn_file_sync(server_ctx.clone());
+        watcher::spawn_file_watcher(server_ctx.clone());
+        index::spawn_rescan(server_ctx.clone());
 
         let user_agent_service_instance =
             service::user_agent::UserAgentServiceImpl::new(server_ctx.clone());
@@ -53,34 +93,99 @@ impl Server {
         let server_control_service =
             service::server_control::ServerControlServiceImpl::new(server_ctx.clone(), signal_tx);
 
-        let server_addr = self.get_server_addr();
+        let tls_config = dsync_shared::conn::tls::server_config(server_ctx.identity.tls())
+            .context("Failed to create TLS config")?;
+        let listener = self.bind_listener()?;
+        let control_listener = self.bind_control_listener().await?;
+        let control_socket = self.bind_control_socket()?;
         log::info!(
-            "Starting server at {:?}, with config: {:?}",
-            &server_addr,
+            "Starting server at {:?} (control services at {:?} & {:?}), with config: {:?}",
+            listener.local_addr()?,
+            control_listener.local_addr()?,
+            self.config.control_socket,
             &self.config
         );
 
-        tonic::transport::Server::builder()
-            .add_service(UserAgentServiceServer::new(user_agent_service_instance))
+        // Both listeners stop on the same signal.
+        let (stop_tx, stop_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            Self::shutdown_feature(signal_rx).await;
+            let _ = stop_tx.send(true);
+        });
+        let stopped = |mut stop_rx: watch::Receiver<bool>| async move {
+            let _ = stop_rx.wait_for(|stop| *stop).await;
+        };
+
+        // Services for other daemons, over mutually authenticated TLS.
+        let peer_server = tonic::transport::Server::builder()
             .add_service(HostDiscoveryServiceServer::new(peer_service_instance))
             .add_service(FileTransferServiceServer::new(file_transfer_service))
-            .add_service(ServerControlServiceServer::new(server_control_service))
-            .serve_with_shutdown(server_addr.into(), Self::shutdown_feature(signal_rx))
-            .await?;
+            .serve_with_incoming_shutdown(
+                service::tools::net::tls::incoming(listener, tls_config),
+                stopped(stop_rx.clone()),
+            );
+
+        // Services for the local user, on loopback & on the Unix socket.
+        let local_only = service::tools::net::auth::LocalOnly;
+        let user_agent_server =
+            UserAgentServiceServer::with_interceptor(user_agent_service_instance, local_only);
+        let server_control_server =
+            ServerControlServiceServer::with_interceptor(server_control_service, local_only);
+
+        let control_server = tonic::transport::Server::builder()
+            .add_service(user_agent_server.clone())
+            .add_service(server_control_server.clone())
+            .serve_with_incoming_shutdown(
+                TcpIncoming::from(control_listener),
+                stopped(stop_rx.clone()),
+            );
+
+        let control_socket_server = async {
+            let Some(control_socket) = control_socket else {
+                return Ok(());
+            };
+            let result = tonic::transport::Server::builder()
+                .add_service(user_agent_server)
+                .add_service(server_control_server)
+                .serve_with_incoming_shutdown(
+                    UnixListenerStream::new(control_socket),
+                    stopped(stop_rx),
+                )
+                .await;
+            if let Some(path) = &self.config.control_socket
+                && let Err(err) = std::fs::remove_file(path)
+            {
+                log::warn!("Failed to remove control socket {path:?}: {err}");
+            }
+            result
+        };
+
+        tokio::try_join!(peer_server, control_server, control_socket_server)?;
 
         anyhow::Ok(())
     }
 
     fn create_this_server_info(&self) -> HostsRow {
         let hostname = self.get_hostname().expect("Error while resolving hostname");
+        let version = HostsVersionFragmentUpdate::from_daemon_version(
+            &service::tools::net::handshake::local_daemon_version(),
+        );
 
         HostsRow {
             uuid: Uuid::new_v4().to_string(),
             name: hostname.clone(),
             hostname,
             is_remote: false,
-            ipv4_addr: String::from("127.0.0.1"),
+            address: String::from(defaults::ADDR_LOOPBACK_V4),
             discovery_time: service::tools::time::get_current_timestamp(),
+            port: self.config.port.into(),
+            protocol_version: version.protocol_version,
+            build_version: version.build_version,
+            capabilities: version.capabilities,
+            public_key: None,
+            paired: false,
+            local_id: 0,
+            notes: String::new(),
         }
     }
 
@@ -95,8 +200,68 @@ impl Server {
         anyhow::Ok(output_string)
     }
 
-    fn get_server_addr(&self) -> SocketAddrV4 {
-        SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, self.config.port)
+    /// Listen on all interfaces, on both IPv6 & IPv4 when the system allows it.
+    /// Falls back to IPv4 only on hosts with IPv6 disabled.
+    fn bind_listener(&self) -> anyhow::Result<TcpListener> {
+        let dual_stack_addr = SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), self.config.port);
+        match Self::bind_dual_stack(dual_stack_addr) {
+            Ok(listener) => return Ok(listener),
+            Err(err) => {
+                log::warn!("Failed to bind dual-stack listener, falling back to IPv4: {err}")
+            }
+        }
+
+        let std_listener = std::net::TcpListener::bind(SocketAddr::new(
+            Ipv4Addr::UNSPECIFIED.into(),
+            self.config.port,
+        ))
+        .context("Failed to bind server listener")?;
+        std_listener.set_nonblocking(true)?;
+        Ok(TcpListener::from_std(std_listener)?)
+    }
+
+    /// Control services are reachable from this host only.
+    async fn bind_control_listener(&self) -> anyhow::Result<TcpListener> {
+        TcpListener::bind(SocketAddr::new(
+            Ipv4Addr::LOCALHOST.into(),
+            self.config.control_port,
+        ))
+        .await
+        .context("Failed to bind control listener")
+    }
+
+    /// Control services are reachable over a Unix socket as well, by the owner only.
+    /// The socket lives in the runtime directory, which is private to the user anyway.
+    fn bind_control_socket(&self) -> anyhow::Result<Option<UnixListener>> {
+        let Some(path) = &self.config.control_socket else {
+            return Ok(None);
+        };
+
+        if path.exists() {
+            // Left behind by a daemon which has not shut down cleanly, unless it is still running.
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                anyhow::bail!("Control socket {path:?} is used by another daemon");
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale control socket {path:?}"))?;
+        }
+
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind control socket {path:?}"))?;
+        std::fs::set_permissions(path, Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to restrict access to control socket {path:?}"))?;
+        Ok(Some(listener))
+    }
+
+    fn bind_dual_stack(addr: SocketAddr) -> std::io::Result<TcpListener> {
+        let socket = Socket::new(Domain::IPV6, Type::STREAM, Some(Protocol::TCP))?;
+        // Accept IPv4 connections as well, as IPv4-mapped addresses.
+        socket.set_only_v6(false)?;
+        socket.set_reuse_address(true)?;
+        socket.set_nonblocking(true)?;
+        socket.bind(&addr.into())?;
+        socket.listen(1024)?;
+        TcpListener::from_std(socket.into())
     }
 
     async fn shutdown_feature(signal_rx: oneshot::Receiver<()>) -> () {
@@ -108,6 +273,19 @@ impl Server {
         log::info!("Requesting runtime shutdown");
     }
 
+    async fn load_identity(repo: &dyn DataRepository) -> anyhow::Result<Identity> {
+        let identity = Identity::load_or_generate(repo)
+            .await
+            .context("Failed to load keypair of this server")?;
+
+        // Stored with the local host, so that it can authenticate requests sent to itself.
+        let local_server_info = repo.fetch_local_server_info().await?;
+        repo.pair_host(&local_server_info.uuid, &identity.public_key_hex())
+            .await?;
+
+        Ok(identity)
+    }
+
     fn create_database_connection(config: &Config) -> Result<SqliteConnection, anyhow::Error> {
         log::trace!("Creating database connection");
         let connection = SqliteConnection::establish(config.database_url.to_str().unwrap())
@@ -126,6 +304,25 @@ impl Server {
         let repo_arc: Arc<dyn DataRepository> =
             Arc::new(MainDataRepository::new(Box::new(sqlite_ds)));
 
+        // Port & version might have changed since the last run, peers & local clients need the current ones.
+        let local_server_info = repo_arc.fetch_local_server_info().await?;
+        repo_arc
+            .update_host_addr(
+                &local_server_info.uuid,
+                defaults::ADDR_LOOPBACK_V4,
+                self.config.port,
+                service::tools::time::get_current_timestamp(),
+            )
+            .await?;
+        repo_arc
+            .update_host_version(
+                &local_server_info.uuid,
+                &HostsVersionFragmentUpdate::from_daemon_version(
+                    &service::tools::net::handshake::local_daemon_version(),
+                ),
+            )
+            .await?;
+
         Ok(repo_arc)
     }
 }
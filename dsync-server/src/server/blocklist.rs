@@ -0,0 +1,49 @@
+//! Hosts this server refuses to store or contact.
+//!
+//! Rules come from the configuration & from the database, where the user adds them with
+//! `dsync-cli host block`. They are checked on every handshake, in both directions,
+//! & before connecting to known hosts.
+
+use std::net::IpAddr;
+
+use super::{config::BlockRule, context::ServerContext};
+
+/// First rule matching a host with the uuid and/or the address, none if the host is not blocked.
+pub(crate) async fn find_rule(
+    ctx: &ServerContext,
+    uuid: Option<&str>,
+    ip: Option<IpAddr>,
+) -> anyhow::Result<Option<BlockRule>> {
+    if let Some(rule) = ctx.cfg.blocklist.iter().find(|rule| rule.matches(uuid, ip)) {
+        return Ok(Some(rule.clone()));
+    }
+
+    for rule in ctx.repo.fetch_blocklist().await? {
+        match rule.parse::<BlockRule>() {
+            Ok(rule) if rule.matches(uuid, ip) => return Ok(Some(rule)),
+            Ok(_) => {}
+            Err(err) => {
+                log::warn!(target: "pslog", "Skipping invalid blocklist rule {rule}: {err}");
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fails if a host with the uuid and/or the address is blocked.
+pub(crate) async fn ensure_not_blocked(
+    ctx: &ServerContext,
+    uuid: Option<&str>,
+    ip: Option<IpAddr>,
+) -> anyhow::Result<()> {
+    match find_rule(ctx, uuid, ip).await? {
+        Some(rule) => anyhow::bail!(
+            "Host {} is blocked by rule {rule}",
+            uuid.map(str::to_owned)
+                .or_else(|| ip.map(|ip| ip.to_string()))
+                .unwrap_or_default()
+        ),
+        None => Ok(()),
+    }
+}
@@ -1,25 +1,400 @@
 #![allow(unused)]
 
-use std::path::PathBuf;
+use std::{fmt::Display, net::IpAddr, path::PathBuf, str::FromStr, time::Duration};
+
+use ipnet::{IpNet, Ipv4Net};
+use serde::Deserialize;
 
 pub mod defaults {
+    use std::time::Duration;
+
+    use super::DiscoveryBackendKind;
+
     pub const SERVER_PORT: u16 = 50051;
+    pub const CONTROL_PORT: u16 = 50050;
     pub const ADDR_LOOPBACK_V4: &str = "127.0.0.1";
     pub const ADDR_ALL_INTERFACES_V4: &str = "0.0.0.0";
+    pub const ADDR_ALL_INTERFACES_V6: &str = "::";
+    pub const DISCOVERY_BACKEND: DiscoveryBackendKind = DiscoveryBackendKind::Sweep;
+    pub const SCAN_TIMEOUT: Duration = Duration::from_millis(500);
+    pub const SCAN_CONCURRENCY: usize = 128;
+    pub const HELLO_CONCURRENCY: usize = 16;
+    pub const BEACON_PORT: u16 = 50052;
+    pub const BEACON_INTERVAL: Duration = Duration::from_secs(30);
+    /// Used when no secret is configured. Beacons signed with it are only protected against
+    /// corruption & accidental collisions with other protocols, not against forgery.
+    pub const BEACON_SECRET: &str = "dsync-beacon";
+    pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+    pub const STATIC_PEERS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+    pub const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+    pub const RESCAN_INTERVAL: Duration = Duration::from_secs(10 * 60);
+    pub const TOMBSTONE_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+    /// Version control metadata & editor swap files.
+    pub const IGNORE_PATTERNS: &[&str] = &[".git/", "*.swp", "*.swo", "*.swx", "*~", ".#*"];
 }
 
 pub mod keys {
     pub const DATABASE_URL: &str = "DATABASE_URL";
     pub const ENV_FILE: &str = "ENV_FILE";
+    pub const CONFIG_FILE: &str = "CONFIG_FILE";
     pub const SERVER_PORT: &str = "SERVER_PORT";
+    pub const CONTROL_PORT: &str = "CONTROL_PORT";
+    pub const CONTROL_SOCKET: &str = "CONTROL_SOCKET";
+    pub const DISCOVERY_BACKEND: &str = "DISCOVERY_BACKEND";
+    pub const BEACON_PORT: &str = "BEACON_PORT";
+    pub const BEACON_INTERVAL_SECS: &str = "BEACON_INTERVAL_SECS";
+    pub const BEACON_SECRET: &str = "BEACON_SECRET";
+    pub const SCAN_PORT: &str = "SCAN_PORT";
+    pub const SCAN_SUBNETS: &str = "SCAN_SUBNETS";
+    pub const SCAN_TIMEOUT_MS: &str = "SCAN_TIMEOUT_MS";
+    pub const SCAN_CONCURRENCY: &str = "SCAN_CONCURRENCY";
+    pub const HELLO_CONCURRENCY: &str = "HELLO_CONCURRENCY";
+    pub const HEARTBEAT_INTERVAL_SECS: &str = "HEARTBEAT_INTERVAL_SECS";
+    pub const STATIC_PEERS: &str = "STATIC_PEERS";
+    pub const STATIC_PEERS_INTERVAL_SECS: &str = "STATIC_PEERS_INTERVAL_SECS";
+    pub const DISCOVERY_INTERFACES: &str = "DISCOVERY_INTERFACES";
+    pub const BLOCKLIST: &str = "BLOCKLIST";
+    pub const SYNC_INTERVAL_SECS: &str = "SYNC_INTERVAL_SECS";
+    pub const RESCAN_INTERVAL_SECS: &str = "RESCAN_INTERVAL_SECS";
+    pub const TOMBSTONE_RETENTION_SECS: &str = "TOMBSTONE_RETENTION_SECS";
+    pub const IGNORE_PATTERNS: &str = "IGNORE_PATTERNS";
 }
 
 /// Running configuration for the server.
 #[derive(Debug, Clone)]
 pub struct Config {
-    /// Port the server should listen on.
+    /// Port the server should listen on for peers.
     pub port: u16,
 
+    /// Port the control services (used by the CLI) listen on. Bound to loopback only.
+    pub control_port: u16,
+
+    /// Unix socket the control services listen on as well, access is limited to the owner.
+    /// None when no runtime directory is available.
+    pub control_socket: Option<PathBuf>,
+
     /// Path to local storage database.
     pub database_url: PathBuf,
+
+    /// Host discovery settings.
+    pub discovery: DiscoveryConfig,
+
+    /// How often known peers are pinged to check whether they are online.
+    pub heartbeat_interval: Duration,
+
+    /// Hosts which are never stored nor contacted. Rules added with the CLI apply as well.
+    pub blocklist: Vec<BlockRule>,
+
+    /// How often synced files are compared with their remote counterparts.
+    pub sync_interval: Duration,
+
+    /// How often tracked files are checked for changes the watcher has missed.
+    pub rescan_interval: Duration,
+
+    /// How long deletions of tracked files are remembered, so that peers learn about them.
+    pub tombstone_retention: Duration,
+
+    /// Gitignore-syntax patterns applying to every sync root, next to `.dsyncignore` files.
+    pub ignore_patterns: Vec<String>,
+}
+
+impl Config {
+    /// Directory remote versions of conflicted files are fetched to, next to the database.
+    pub fn conflicts_dir(&self) -> PathBuf {
+        std::path::absolute(&self.database_url)
+            .unwrap_or_else(|_| self.database_url.clone())
+            .with_file_name("conflicts")
+    }
+}
+
+/// Host (or hosts) this server refuses to talk to.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum BlockRule {
+    /// Host with the given uuid, at whatever address.
+    Uuid(String),
+    /// Hosts connecting from, or found at, an address in the subnet.
+    /// Single addresses are stored as full-length subnets.
+    Subnet(IpNet),
+}
+
+impl BlockRule {
+    /// Whether the rule matches a host with the uuid and/or the address, any of them might be unknown.
+    pub fn matches(&self, uuid: Option<&str>, ip: Option<IpAddr>) -> bool {
+        match self {
+            Self::Uuid(blocked) => uuid.is_some_and(|uuid| uuid == blocked),
+            Self::Subnet(subnet) => ip.is_some_and(|ip| {
+                // Dual-stack listeners see IPv4 peers as IPv4-mapped IPv6 addresses.
+                let ip = match ip {
+                    IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+                    IpAddr::V4(_) => ip,
+                };
+                subnet.contains(&ip)
+            }),
+        }
+    }
+}
+
+/// Accepted syntax: an IP address, a subnet in CIDR notation or anything else, which is taken for a uuid.
+impl FromStr for BlockRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            anyhow::bail!("Empty block rule");
+        }
+
+        if let Ok(subnet) = s.parse::<IpNet>() {
+            return Ok(Self::Subnet(subnet.trunc()));
+        }
+
+        if let Ok(ip) = s.parse::<IpAddr>() {
+            return Ok(Self::Subnet(IpNet::from(ip)));
+        }
+
+        if s.contains(['/', ':']) {
+            anyhow::bail!("Invalid address or subnet in block rule: {s}");
+        }
+
+        Ok(Self::Uuid(s.to_owned()))
+    }
+}
+
+impl TryFrom<String> for BlockRule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Inverse of [`FromStr`], single addresses are rendered w/o the prefix length.
+impl Display for BlockRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uuid(uuid) => write!(f, "{uuid}"),
+            Self::Subnet(subnet) if subnet.prefix_len() == subnet.max_prefix_len() => {
+                write!(f, "{}", subnet.addr())
+            }
+            Self::Subnet(subnet) => write!(f, "{subnet}"),
+        }
+    }
+}
+
+/// Mechanism used to find candidate peers in the local network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiscoveryBackendKind {
+    /// Probe every address of local subnets with a TCP connect to the dsync port.
+    Sweep,
+    /// Read system neighbour tables (`arp` / `ip neigh`). Finds only hosts
+    /// this machine has recently talked to.
+    Neighbours,
+    /// Exchange signed UDP broadcast beacons with other daemons.
+    Broadcast,
+}
+
+impl FromStr for DiscoveryBackendKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sweep" => Ok(Self::Sweep),
+            "neighbours" => Ok(Self::Neighbours),
+            "broadcast" => Ok(Self::Broadcast),
+            _ => anyhow::bail!("Unknown discovery backend: {s}"),
+        }
+    }
+}
+
+/// Peer declared in the configuration. It is contacted directly, w/o any discovery,
+/// so it might live on another subnet or behind a VPN.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StaticPeer {
+    /// IP address or domain name, resolved on every contact.
+    pub address: String,
+
+    /// Port the peer listens on, `scan_port` when not set.
+    pub port: Option<u16>,
+
+    /// Expected uuid of the peer. Peers answering with another uuid are not stored.
+    pub uuid: Option<String>,
+}
+
+/// Accepted syntax: `[UUID@]ADDRESS[:PORT]`, where IPv6 addresses with port have to be
+/// bracketed (`[fe80::1%2]:50051`).
+impl FromStr for StaticPeer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (uuid, addr_spec) = match s.split_once('@') {
+            Some((uuid, addr_spec)) => (Some(uuid.to_owned()), addr_spec),
+            None => (None, s),
+        };
+
+        let (address, port) = if let Some(rest) = addr_spec.strip_prefix('[') {
+            let Some((address, rest)) = rest.split_once(']') else {
+                anyhow::bail!("Missing closing bracket in static peer: {s}");
+            };
+            match rest.strip_prefix(':') {
+                Some(port) => (address, Some(port)),
+                None if rest.is_empty() => (address, None),
+                None => anyhow::bail!("Unexpected characters after address in static peer: {s}"),
+            }
+        } else {
+            match addr_spec.split_once(':') {
+                // More colons - a bare IPv6 address.
+                Some((address, port)) if !port.contains(':') => (address, Some(port)),
+                _ => (addr_spec, None),
+            }
+        };
+
+        if address.is_empty() {
+            anyhow::bail!("Missing address in static peer: {s}");
+        }
+
+        let port = port
+            .map(|port| port.parse::<u16>())
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("Invalid port in static peer {s}: {err}"))?;
+
+        Ok(Self {
+            address: address.to_owned(),
+            port,
+            uuid,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    /// Backend used to find candidate peers.
+    pub backend: DiscoveryBackendKind,
+
+    /// UDP port beacons are sent to & received on.
+    pub beacon_port: u16,
+
+    /// How often this server announces itself with a beacon.
+    pub beacon_interval: Duration,
+
+    /// Pre-shared key beacons are signed with. Daemons using different
+    /// keys ignore each other's beacons.
+    pub beacon_secret: String,
+
+    /// Port peers are expected to listen on. Probed by sweep & neighbour discovery.
+    pub scan_port: u16,
+
+    /// Subnets to sweep. When empty, subnets of local interfaces are used.
+    pub scan_subnets: Vec<Ipv4Net>,
+
+    /// How long to wait for a single probe to connect.
+    pub scan_timeout: Duration,
+
+    /// Maximum number of probes running at the same time.
+    pub scan_concurrency: usize,
+
+    /// Maximum number of `HelloThere` handshakes with candidates running at the same time.
+    pub hello_concurrency: usize,
+
+    /// Peers contacted directly, next to whatever the discovery backend finds.
+    pub static_peers: Vec<StaticPeer>,
+
+    /// How often static peers are contacted.
+    pub static_peers_interval: Duration,
+
+    /// Interfaces discovery is restricted to. When empty, all interfaces are used.
+    /// Does not affect static peers.
+    pub interfaces: Vec<String>,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            backend: defaults::DISCOVERY_BACKEND,
+            beacon_port: defaults::BEACON_PORT,
+            beacon_interval: defaults::BEACON_INTERVAL,
+            beacon_secret: defaults::BEACON_SECRET.to_owned(),
+            scan_port: defaults::SERVER_PORT,
+            scan_subnets: Vec::new(),
+            scan_timeout: defaults::SCAN_TIMEOUT,
+            scan_concurrency: defaults::SCAN_CONCURRENCY,
+            hello_concurrency: defaults::HELLO_CONCURRENCY,
+            static_peers: Vec::new(),
+            static_peers_interval: defaults::STATIC_PEERS_INTERVAL,
+            interfaces: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BlockRule, StaticPeer};
+
+    fn peer(address: &str, port: Option<u16>, uuid: Option<&str>) -> StaticPeer {
+        StaticPeer {
+            address: address.to_owned(),
+            port,
+            uuid: uuid.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_static_peer_from_str() {
+        assert_eq!(
+            "10.8.0.2".parse::<StaticPeer>().unwrap(),
+            peer("10.8.0.2", None, None)
+        );
+        assert_eq!(
+            "nas.lan:50061".parse::<StaticPeer>().unwrap(),
+            peer("nas.lan", Some(50061), None)
+        );
+        assert_eq!(
+            "abc@10.8.0.2:50061".parse::<StaticPeer>().unwrap(),
+            peer("10.8.0.2", Some(50061), Some("abc"))
+        );
+        assert_eq!(
+            "fe80::1%2".parse::<StaticPeer>().unwrap(),
+            peer("fe80::1%2", None, None)
+        );
+        assert_eq!(
+            "[fe80::1%2]:50061".parse::<StaticPeer>().unwrap(),
+            peer("fe80::1%2", Some(50061), None)
+        );
+    }
+
+    #[test]
+    fn test_static_peer_from_str_invalid() {
+        assert!("".parse::<StaticPeer>().is_err());
+        assert!("10.8.0.2:port".parse::<StaticPeer>().is_err());
+        assert!("[fe80::1:50061".parse::<StaticPeer>().is_err());
+        assert!("[fe80::1]x".parse::<StaticPeer>().is_err());
+    }
+
+    #[test]
+    fn test_block_rule_roundtrip() {
+        for rule in ["10.8.0.2", "10.8.0.0/16", "fe80::/10", "5f0c6d1e-uuid"] {
+            assert_eq!(rule.parse::<BlockRule>().unwrap().to_string(), rule);
+        }
+        assert_eq!(
+            "10.8.1.2/16".parse::<BlockRule>().unwrap().to_string(),
+            "10.8.0.0/16"
+        );
+        assert!("10.8.0.0/33".parse::<BlockRule>().is_err());
+        assert!("".parse::<BlockRule>().is_err());
+    }
+
+    #[test]
+    fn test_block_rule_matches() {
+        let subnet: BlockRule = "10.8.0.0/16".parse().unwrap();
+        assert!(subnet.matches(None, Some("10.8.3.4".parse().unwrap())));
+        assert!(subnet.matches(Some("abc"), Some("::ffff:10.8.3.4".parse().unwrap())));
+        assert!(!subnet.matches(Some("abc"), Some("10.9.0.1".parse().unwrap())));
+        assert!(!subnet.matches(Some("abc"), None));
+
+        let uuid: BlockRule = "abc".parse().unwrap();
+        assert!(uuid.matches(Some("abc"), None));
+        assert!(!uuid.matches(Some("abd"), Some("10.8.3.4".parse().unwrap())));
+    }
 }
@@ -0,0 +1,349 @@
+//! Resolving conflicts of synced files (see `sync`).
+//!
+//! Conflicts are resolved by the user (`dsync-cli conflict resolve`), or as they are detected by
+//! the strategy of the groups the file belongs to. Keeping the local version makes it newer
+//! than both, so the next sync pushes it to the peer. Taking the remote version replaces the
+//! file with the fetched copy, taking over its version, so the next sync finds both equal.
+//! Keeping both moves the copy next to the file, where it is indexed as a new file of the sync
+//! root, if any, & the local version is kept.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use dsync_proto::services::user_agent;
+
+use crate::server::{
+    context::ServerContext,
+    data::source::sqlite::database::models::{FileConflictRow, FilesLocalRow},
+    index::{self, LocalFileState},
+    service::{file_transfer, tools},
+    version_vector::VersionVector,
+};
+
+/// How conflicts of files of a group are resolved as they are detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Left for the user to resolve.
+    Manual,
+    /// The version modified last on disk wins, the greater host uuid breaks ties.
+    NewestWins,
+    /// The version of the host listed first in the priority of the group wins.
+    HostPriority,
+}
+
+impl fmt::Display for ConflictStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Manual => "manual",
+            Self::NewestWins => "newest-wins",
+            Self::HostPriority => "host-priority",
+        })
+    }
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manual" => Ok(Self::Manual),
+            "newest-wins" => Ok(Self::NewestWins),
+            "host-priority" => Ok(Self::HostPriority),
+            _ => anyhow::bail!("Unknown conflict strategy `{s}`"),
+        }
+    }
+}
+
+impl From<user_agent::ConflictStrategy> for ConflictStrategy {
+    fn from(strategy: user_agent::ConflictStrategy) -> Self {
+        match strategy {
+            user_agent::ConflictStrategy::Manual => Self::Manual,
+            user_agent::ConflictStrategy::NewestWins => Self::NewestWins,
+            user_agent::ConflictStrategy::HostPriority => Self::HostPriority,
+        }
+    }
+}
+
+/// Which version of a conflicted file is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Resolution {
+    Local,
+    Remote,
+    /// The local version stays in place, the remote one is kept as a new file.
+    KeepBoth,
+}
+
+impl From<user_agent::ConflictResolution> for Resolution {
+    fn from(resolution: user_agent::ConflictResolution) -> Self {
+        match resolution {
+            user_agent::ConflictResolution::Local => Self::Local,
+            user_agent::ConflictResolution::Remote => Self::Remote,
+            user_agent::ConflictResolution::KeepBoth => Self::KeepBoth,
+        }
+    }
+}
+
+/// One side of a conflict, as seen by the strategies.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConflictSide<'a> {
+    pub host_uuid: &'a str,
+    pub mtime: i64,
+}
+
+/// Resolution picked by the strategy, none when it is up to the user.
+pub(crate) fn pick_resolution(
+    strategy: ConflictStrategy,
+    host_priority: &[&str],
+    local: ConflictSide,
+    remote: ConflictSide,
+) -> Option<Resolution> {
+    match strategy {
+        ConflictStrategy::Manual => None,
+        ConflictStrategy::NewestWins => {
+            // Both hosts have to come to the same conclusion, uuids are unique.
+            if (local.mtime, local.host_uuid) > (remote.mtime, remote.host_uuid) {
+                Some(Resolution::Local)
+            } else {
+                Some(Resolution::Remote)
+            }
+        }
+        ConflictStrategy::HostPriority => {
+            host_priority.iter().find_map(|&host_uuid| match host_uuid {
+                _ if host_uuid == local.host_uuid => Some(Resolution::Local),
+                _ if host_uuid == remote.host_uuid => Some(Resolution::Remote),
+                _ => None,
+            })
+        }
+    }
+}
+
+/// Resolution picked by the strategies of the groups of the file, none when it is up to the
+/// user - also when the groups do not agree with each other.
+pub(crate) async fn group_resolution(
+    ctx: &ServerContext,
+    file_id: i32,
+    local: ConflictSide<'_>,
+    remote: ConflictSide<'_>,
+) -> anyhow::Result<Option<Resolution>> {
+    let groups = ctx.repo.fetch_file_groups(file_id).await?;
+
+    let mut resolutions = groups.iter().map(|group| {
+        let host_priority: Vec<&str> = group.host_priority().collect();
+        pick_resolution(group.conflict_strategy(), &host_priority, local, remote)
+    });
+    let Some(resolution) = resolutions.next().flatten() else {
+        return Ok(None);
+    };
+    if resolutions.any(|other| other != Some(resolution)) {
+        return Ok(None);
+    }
+
+    Ok(Some(resolution))
+}
+
+/// Make the local version newer than both versions, the next sync pushes it to the peer.
+pub(crate) async fn keep_local_version(
+    ctx: &ServerContext,
+    file_id: i32,
+    local_version: &VersionVector,
+    remote_version: &VersionVector,
+) -> anyhow::Result<()> {
+    let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+    let version = local_version
+        .clone()
+        .merged(remote_version)
+        .bumped(&local_uuid);
+    ctx.repo
+        .update_local_file_version(file_id, &version.to_string())
+        .await
+}
+
+/// Whether the remote version the conflict has been last recorded with has arrived.
+pub(crate) async fn is_copy_fetched(conflict: &FileConflictRow) -> bool {
+    tools::file::compute_sha1_hash_from_file_async(&conflict.copy_path, None)
+        .await
+        .is_ok_and(|hash_sha1| hash_sha1 == conflict.remote_hash)
+}
+
+/// Resolve the conflict by hand. Returns where the remote version has been kept, if it has.
+pub(crate) async fn resolve(
+    ctx: &ServerContext,
+    local_file: &FilesLocalRow,
+    conflict: &FileConflictRow,
+    peer_name: &str,
+    resolution: Resolution,
+) -> anyhow::Result<Option<PathBuf>> {
+    let remote_version: VersionVector = conflict.remote_version.parse()?;
+    // The watcher might not have caught up with the latest change yet.
+    let local_version = match index::refresh_local_file(ctx, local_file).await? {
+        LocalFileState::Unchanged => local_file.version_vector(),
+        LocalFileState::Modified { version, .. } => version,
+        LocalFileState::Removed | LocalFileState::Gone => {
+            anyhow::bail!("File {} is gone", local_file.file_path)
+        }
+    };
+    if resolution != Resolution::Local && !is_copy_fetched(conflict).await {
+        anyhow::bail!("The remote version has not been fetched yet, try again after the next sync");
+    }
+
+    let kept_path = match resolution {
+        Resolution::Local => {
+            keep_local_version(ctx, local_file.id, &local_version, &remote_version).await?;
+            None
+        }
+        Resolution::Remote => {
+            let part_path = file_transfer::partial_path(&local_file.file_path)
+                .ok_or_else(|| anyhow::anyhow!("Invalid path {}", local_file.file_path))?;
+            // The copy might be on another file system, it is removed below anyway.
+            tokio::fs::copy(&conflict.copy_path, &part_path).await?;
+            index::store_received_file(
+                ctx,
+                &part_path,
+                &local_file.file_path,
+                conflict.remote_hash.clone(),
+                remote_version,
+            )
+            .await?;
+            None
+        }
+        Resolution::KeepBoth => {
+            let kept_path = kept_copy_path(
+                Path::new(&local_file.file_path),
+                peer_name,
+                tools::time::get_current_timestamp(),
+            );
+            if kept_path.exists() {
+                anyhow::bail!("{kept_path:?} already exists");
+            }
+            tokio::fs::copy(&conflict.copy_path, &kept_path).await?;
+            keep_local_version(ctx, local_file.id, &local_version, &remote_version).await?;
+            Some(kept_path)
+        }
+    };
+
+    ctx.repo
+        .delete_file_conflicts(conflict.file_id, Some(&conflict.peer_uuid))
+        .await?;
+    let _ = tokio::fs::remove_file(&conflict.copy_path).await;
+    log::info!(
+        target: "pslog",
+        "Conflict of {} with host {} has been resolved: {resolution:?}",
+        local_file.file_path,
+        conflict.peer_uuid
+    );
+    ctx.sync_requested.notify_one();
+
+    Ok(kept_path)
+}
+
+/// Unified diff from the local version to the fetched remote one. Both have to be text.
+pub(crate) async fn diff(
+    local_file: &FilesLocalRow,
+    conflict: &FileConflictRow,
+) -> anyhow::Result<String> {
+    let local = tokio::fs::read(&local_file.file_path).await?;
+    let remote = tokio::fs::read(&conflict.copy_path).await?;
+    let (Ok(local), Ok(remote)) = (String::from_utf8(local), String::from_utf8(remote)) else {
+        anyhow::bail!("Binary files differ");
+    };
+
+    Ok(similar::TextDiff::from_lines(&local, &remote)
+        .unified_diff()
+        .header(&local_file.file_path, &conflict.copy_path)
+        .to_string())
+}
+
+/// `file.conflict-<host>-<ts>.ext` next to the file, for the remote version kept aside.
+pub(crate) fn kept_copy_path(file_path: &Path, host_name: &str, timestamp: i64) -> PathBuf {
+    let host_name: String = host_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = file_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let mut file_name = format!("{stem}.conflict-{host_name}-{timestamp}");
+    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
+        file_name = format!("{file_name}.{extension}");
+    }
+    file_path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pick_resolution() {
+        let local = ConflictSide {
+            host_uuid: "a",
+            mtime: 20,
+        };
+        let remote = ConflictSide {
+            host_uuid: "b",
+            mtime: 10,
+        };
+
+        assert_eq!(
+            pick_resolution(ConflictStrategy::Manual, &[], local, remote),
+            None
+        );
+        assert_eq!(
+            pick_resolution(ConflictStrategy::NewestWins, &[], local, remote),
+            Some(Resolution::Local)
+        );
+        assert_eq!(
+            pick_resolution(ConflictStrategy::NewestWins, &[], remote, local),
+            Some(Resolution::Remote)
+        );
+        // Ties are broken the same way on both sides.
+        let tied = ConflictSide {
+            mtime: 20,
+            ..remote
+        };
+        assert_eq!(
+            pick_resolution(ConflictStrategy::NewestWins, &[], local, tied),
+            Some(Resolution::Remote)
+        );
+        assert_eq!(
+            pick_resolution(ConflictStrategy::NewestWins, &[], tied, local),
+            Some(Resolution::Local)
+        );
+
+        let priority = ["c", "b", "a"];
+        assert_eq!(
+            pick_resolution(ConflictStrategy::HostPriority, &priority, local, remote),
+            Some(Resolution::Remote)
+        );
+        assert_eq!(
+            pick_resolution(ConflictStrategy::HostPriority, &["a"], local, remote),
+            Some(Resolution::Local)
+        );
+        assert_eq!(
+            pick_resolution(ConflictStrategy::HostPriority, &["c"], local, remote),
+            None
+        );
+    }
+
+    #[test]
+    fn test_kept_copy_path() {
+        assert_eq!(
+            kept_copy_path(Path::new("/data/notes.txt"), "vm-b", 1700000000),
+            PathBuf::from("/data/notes.conflict-vm-b-1700000000.txt")
+        );
+        assert_eq!(
+            kept_copy_path(Path::new("/data/Makefile"), "my host/2", 1),
+            PathBuf::from("/data/Makefile.conflict-my_host_2-1")
+        );
+    }
+}
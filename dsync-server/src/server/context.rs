@@ -2,18 +2,49 @@
 
 use std::sync::Arc;
 
+use tokio::sync::Notify;
+
 use super::config::Config;
 use super::data::repo::DataRepository;
+use super::identity::Identity;
+use super::pairing::PairingRegistry;
+use super::peers::PeerRegistry;
+use super::service::tools::net::addr_discovery::DiscoveryBackend;
+use super::transfers::ExpectedTransfers;
+use super::watcher::FileWatcher;
 
 /// Context that might be shared between different services.
 /// This should be thread-safe.
 pub(crate) struct ServerContext {
     pub cfg: Config,
     pub repo: Arc<dyn DataRepository>,
+    pub discovery: Arc<dyn DiscoveryBackend>,
+    pub peers: PeerRegistry,
+    pub identity: Identity,
+    pub pairings: PairingRegistry,
+    pub expected_transfers: ExpectedTransfers,
+    /// Wakes up the file sync task ahead of its schedule.
+    pub sync_requested: Notify,
+    pub watcher: FileWatcher,
 }
 
 impl ServerContext {
-    pub fn new(cfg: Config, repo: Arc<dyn DataRepository>) -> Self {
-        Self { cfg, repo }
+    pub fn new(
+        cfg: Config,
+        repo: Arc<dyn DataRepository>,
+        discovery: Arc<dyn DiscoveryBackend>,
+        identity: Identity,
+    ) -> Self {
+        Self {
+            cfg,
+            repo,
+            discovery,
+            peers: PeerRegistry::new(),
+            identity,
+            pairings: PairingRegistry::new(),
+            expected_transfers: ExpectedTransfers::new(),
+            sync_requested: Notify::new(),
+            watcher: FileWatcher::new(),
+        }
     }
 }
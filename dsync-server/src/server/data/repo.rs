@@ -2,10 +2,14 @@ use dsync_proto::model::server::{GroupInfo, HostInfo};
 
 use crate::server::data::source::DataSource;
 use crate::server::data::source::sqlite::database::error::{
-    DeleteLocalGroupError, FileAddError, LocalServerBaseInfoError, SaveLocalGroupError,
+    AccessControlError, DeleteLocalGroupError, FileAddError, FileSyncError, HostAliasError,
+    LocalServerBaseInfoError, SaveLocalGroupError, SyncRootError,
 };
 use crate::server::data::source::sqlite::database::models::{
-    FilesLocalFragmentInsert, FilesLocalRow, HostsRow,
+    Access, FileConflictRow, FileTombstoneRow, FilesLocalContentFragmentUpdate,
+    FilesLocalFragmentInsert, FilesLocalRow, FilesTrackedRow, GroupsLocalRow, HostAddressRow,
+    HostsMetadataFragmentUpdate, HostsRow, HostsVersionFragmentUpdate, RootsTrackedRow,
+    SyncRootRow,
 };
 
 /// DataRepository defines the data access API used by services.
@@ -21,9 +25,59 @@ pub(crate) trait DataRepository: Send + Sync {
     async fn insert_hosts(&self, hosts_rows: &[HostsRow]) -> anyhow::Result<()>;
     async fn fetch_hosts(&self) -> anyhow::Result<Vec<HostInfo>>;
     async fn fetch_host_by_uuid(&self, uuid: &str) -> anyhow::Result<HostsRow>;
+    /// Host with the uuid, none if the host is not known.
+    async fn find_host_by_uuid(&self, uuid: &str) -> anyhow::Result<Option<HostsRow>>;
     async fn fetch_host_by_name(&self, name: &str) -> anyhow::Result<HostsRow>;
+    /// Host by the stable id shown to the user, 0 is the local host.
     async fn fetch_host_by_local_id(&self, local_id: i32) -> anyhow::Result<HostsRow>;
     async fn delete_host_with_uuid(&self, host_uuid: &str) -> anyhow::Result<()>;
+    async fn update_host_addr(
+        &self,
+        host_uuid: &str,
+        address: &str,
+        port: u16,
+        seen_time: i64,
+    ) -> anyhow::Result<()>;
+    /// Known addresses of the host, most recently seen first.
+    async fn fetch_host_addresses(&self, host_uuid: &str) -> anyhow::Result<Vec<HostAddressRow>>;
+    async fn update_host_version(
+        &self,
+        host_uuid: &str,
+        version: &HostsVersionFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    async fn fetch_host_by_public_key(&self, public_key: &str) -> anyhow::Result<Option<HostsRow>>;
+    /// Save the key of the host, unless some key is pinned already.
+    async fn pin_host_public_key(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()>;
+    /// Save the key of the host, verified by the user.
+    async fn pair_host(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()>;
+    /// Set the fields edited by the user. Unset fields are left untouched.
+    async fn update_host_metadata(
+        &self,
+        host_uuid: &str,
+        metadata: &HostsMetadataFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    /// Host with the given alias, none if no host has it.
+    async fn fetch_host_by_alias(&self, alias: &str) -> anyhow::Result<Option<HostsRow>>;
+    /// Aliases are unique across all hosts.
+    async fn save_host_alias(&self, host_uuid: &str, alias: &str) -> Result<(), HostAliasError>;
+    /// Returns the number of removed aliases (0 when the host has no such alias).
+    async fn delete_host_alias(&self, host_uuid: &str, alias: &str) -> anyhow::Result<usize>;
+    /// Tags the host has already are skipped.
+    async fn save_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()>;
+    async fn delete_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()>;
+
+    // Blocklist
+    /// Rules added by the user, rules from the configuration are not stored.
+    async fn fetch_blocklist(&self) -> anyhow::Result<Vec<String>>;
+    /// Returns the number of added rules (0 when the rule is present already).
+    async fn save_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize>;
+    /// Returns the number of removed rules (0 when there is no such rule).
+    async fn delete_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize>;
+
+    // Identity (local)
+    /// None until the keypair of this server is generated.
+    async fn fetch_local_secret_key(&self) -> anyhow::Result<Option<Vec<u8>>>;
+    async fn save_local_secret_key(&self, secret_key: &[u8]) -> anyhow::Result<()>;
 
     // Files (local)
     async fn save_local_files(
@@ -32,11 +86,122 @@ pub(crate) trait DataRepository: Send + Sync {
     ) -> Result<(), FileAddError>;
     async fn fetch_local_files(&self) -> anyhow::Result<Vec<FilesLocalRow>>;
     async fn delete_local_file(&self, file_path: &str) -> anyhow::Result<usize>;
+    /// Record the current contents of the file, which is no longer gone.
+    async fn update_local_file_content(
+        &self,
+        file_id: i32,
+        content: &FilesLocalContentFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    /// The file has been moved, groups, grants & sync pairings stay with it.
+    async fn update_local_file_path(&self, file_id: i32, file_path: &str) -> anyhow::Result<()>;
+    /// The file has not been found on disk, it stays tracked - along with the tombstone of
+    /// the deletion, which takes over the version of the file - until it shows up again or
+    /// the tombstone expires.
+    async fn mark_local_file_gone(&self, tombstone: &FileTombstoneRow) -> anyhow::Result<()>;
+    async fn fetch_file_tombstones(&self) -> anyhow::Result<Vec<FileTombstoneRow>>;
+    /// The file has caught up with modifications made elsewhere, its contents stay the same.
+    async fn update_local_file_version(&self, file_id: i32, version: &str) -> anyhow::Result<()>;
+
+    // Files (tracked)
+    /// Sync the local file with a file of the peer, replacing the previous pairing of the local
+    /// file with that peer, if any. A remote file might be synced with a single local file only.
+    async fn save_tracked_file(&self, tracked_file: &FilesTrackedRow) -> Result<(), FileSyncError>;
+    async fn fetch_tracked_files(&self) -> anyhow::Result<Vec<FilesTrackedRow>>;
+    /// Stop syncing the local file with the peer, or with all peers when none is given.
+    /// Returns the number of removed pairings.
+    async fn delete_tracked_files(
+        &self,
+        local_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
+    /// Record the hash both sides have after a successful sync.
+    async fn update_tracked_file_hash(
+        &self,
+        local_id: i32,
+        peer_uuid: &str,
+        hash_sha1: &str,
+    ) -> anyhow::Result<()>;
+
+    // File conflicts
+    /// Record the conflict of the local file with the file of the peer, replacing the previous
+    /// record of the pair, if any.
+    async fn save_file_conflict(&self, conflict: &FileConflictRow) -> anyhow::Result<()>;
+    async fn fetch_file_conflicts(&self) -> anyhow::Result<Vec<FileConflictRow>>;
+    /// The conflict of the local file with the peer, or with all peers when none is given,
+    /// has been resolved. Returns the number of removed conflicts.
+    async fn delete_file_conflicts(
+        &self,
+        file_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
+
+    // Sync roots
+    /// Track the directory with its whole subtree. Roots must not be nested in each other.
+    /// Returns id of the root.
+    async fn save_sync_root(&self, path: &str) -> Result<i32, SyncRootError>;
+    async fn fetch_sync_roots(&self) -> anyhow::Result<Vec<SyncRootRow>>;
+    /// Remove the root along with its files, grants & sync pairings. Returns the number of removed roots.
+    async fn delete_sync_root(&self, root_id: i32) -> anyhow::Result<usize>;
+    async fn save_root_access(
+        &self,
+        root_id: i32,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    async fn fetch_root_access(&self, root_id: i32, host_uuid: &str) -> anyhow::Result<Access>;
+
+    // Roots (tracked)
+    /// Sync the local root with a root of the peer, replacing the previous pairing of the local
+    /// root with that peer, if any. A remote root might be synced with a single local root only.
+    async fn save_tracked_root(&self, tracked_root: &RootsTrackedRow) -> Result<(), FileSyncError>;
+    async fn fetch_tracked_roots(&self) -> anyhow::Result<Vec<RootsTrackedRow>>;
+    /// Stop syncing the local root with the peer, or with all peers when none is given. Pairings
+    /// of files of the root go along. Returns the number of removed root pairings.
+    async fn delete_tracked_roots(
+        &self,
+        root_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
 
     // Groups (local)
     async fn save_local_group(&self, group_id: &str) -> Result<usize, SaveLocalGroupError>;
     async fn delete_group_by_name(&self, group_name: &str) -> Result<(), DeleteLocalGroupError>;
     async fn fetch_local_groups(&self) -> anyhow::Result<Vec<GroupInfo>>;
+    /// Groups the local file belongs to.
+    async fn fetch_file_groups(&self, file_id: i32) -> anyhow::Result<Vec<GroupsLocalRow>>;
+    /// Set how conflicts of files of the group are resolved. `host_priority` holds comma
+    /// separated uuids of hosts.
+    async fn update_group_strategy(
+        &self,
+        group_name: &str,
+        conflict_strategy: &str,
+        host_priority: &str,
+    ) -> Result<(), AccessControlError>;
+    /// Add tracked files to the group. Untracked paths are skipped.
+    async fn save_group_files(
+        &self,
+        group_name: &str,
+        file_paths: &[String],
+    ) -> Result<(), AccessControlError>;
+
+    // Access control
+    /// Set access of the host to the group. No access at all removes the entry.
+    async fn save_group_access(
+        &self,
+        group_name: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    /// Set access of the host to the file. No access at all removes the entry.
+    async fn save_file_access(
+        &self,
+        file_path: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    /// Access of the host to the file, granted directly or through any group of the file.
+    /// Hosts have no access to untracked files.
+    async fn fetch_file_access(&self, file_path: &str, host_uuid: &str) -> anyhow::Result<Access>;
 }
 
 /// MainRepository is the concrete repository used by services.
@@ -74,19 +239,113 @@ where
         self.data_source.fetch_host_by_uuid(uuid).await
     }
 
+    async fn find_host_by_uuid(&self, uuid: &str) -> anyhow::Result<Option<HostsRow>> {
+        self.data_source.find_host_by_uuid(uuid).await
+    }
+
     async fn fetch_host_by_name(&self, name: &str) -> anyhow::Result<HostsRow> {
         self.data_source.fetch_host_by_name(name).await
     }
 
-    async fn fetch_host_by_local_id(&self, _local_id: i32) -> anyhow::Result<HostsRow> {
-        // Stub implementation to preserve behavior; data source should implement real logic later.
-        anyhow::bail!("fetch_host_by_local_id is not implemented yet")
+    async fn fetch_host_by_local_id(&self, local_id: i32) -> anyhow::Result<HostsRow> {
+        self.data_source.fetch_host_by_local_id(local_id).await
     }
 
     async fn delete_host_with_uuid(&self, host_uuid: &str) -> anyhow::Result<()> {
         self.data_source.delete_host_with_uuid(host_uuid).await
     }
 
+    async fn update_host_addr(
+        &self,
+        host_uuid: &str,
+        address: &str,
+        port: u16,
+        seen_time: i64,
+    ) -> anyhow::Result<()> {
+        self.data_source
+            .update_host_addr(host_uuid, address, port, seen_time)
+            .await
+    }
+
+    async fn fetch_host_addresses(&self, host_uuid: &str) -> anyhow::Result<Vec<HostAddressRow>> {
+        self.data_source.fetch_host_addresses(host_uuid).await
+    }
+
+    async fn update_host_version(
+        &self,
+        host_uuid: &str,
+        version: &HostsVersionFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        self.data_source
+            .update_host_version(host_uuid, version)
+            .await
+    }
+
+    async fn fetch_host_by_public_key(&self, public_key: &str) -> anyhow::Result<Option<HostsRow>> {
+        self.data_source.fetch_host_by_public_key(public_key).await
+    }
+
+    async fn pin_host_public_key(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()> {
+        self.data_source
+            .pin_host_public_key(host_uuid, public_key)
+            .await
+    }
+
+    async fn pair_host(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()> {
+        self.data_source.pair_host(host_uuid, public_key).await
+    }
+
+    async fn update_host_metadata(
+        &self,
+        host_uuid: &str,
+        metadata: &HostsMetadataFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        self.data_source
+            .update_host_metadata(host_uuid, metadata)
+            .await
+    }
+
+    async fn fetch_host_by_alias(&self, alias: &str) -> anyhow::Result<Option<HostsRow>> {
+        self.data_source.fetch_host_by_alias(alias).await
+    }
+
+    async fn save_host_alias(&self, host_uuid: &str, alias: &str) -> Result<(), HostAliasError> {
+        self.data_source.save_host_alias(host_uuid, alias).await
+    }
+
+    async fn delete_host_alias(&self, host_uuid: &str, alias: &str) -> anyhow::Result<usize> {
+        self.data_source.delete_host_alias(host_uuid, alias).await
+    }
+
+    async fn save_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()> {
+        self.data_source.save_host_tags(host_uuid, tags).await
+    }
+
+    async fn delete_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()> {
+        self.data_source.delete_host_tags(host_uuid, tags).await
+    }
+
+    async fn fetch_blocklist(&self) -> anyhow::Result<Vec<String>> {
+        self.data_source.fetch_blocklist().await
+    }
+
+    async fn save_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize> {
+        self.data_source.save_blocklist_rule(rule).await
+    }
+
+    async fn delete_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize> {
+        self.data_source.delete_blocklist_rule(rule).await
+    }
+
+    // Identity (local)
+    async fn fetch_local_secret_key(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        self.data_source.fetch_local_secret_key().await
+    }
+
+    async fn save_local_secret_key(&self, secret_key: &[u8]) -> anyhow::Result<()> {
+        self.data_source.save_local_secret_key(secret_key).await
+    }
+
     // Files (local)
     async fn save_local_files(
         &self,
@@ -103,6 +362,128 @@ where
         self.data_source.delete_local_file(file_path).await
     }
 
+    async fn update_local_file_content(
+        &self,
+        file_id: i32,
+        content: &FilesLocalContentFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        self.data_source
+            .update_local_file_content(file_id, content)
+            .await
+    }
+
+    async fn update_local_file_path(&self, file_id: i32, file_path: &str) -> anyhow::Result<()> {
+        self.data_source
+            .update_local_file_path(file_id, file_path)
+            .await
+    }
+
+    async fn mark_local_file_gone(&self, tombstone: &FileTombstoneRow) -> anyhow::Result<()> {
+        self.data_source.mark_local_file_gone(tombstone).await
+    }
+
+    async fn fetch_file_tombstones(&self) -> anyhow::Result<Vec<FileTombstoneRow>> {
+        self.data_source.fetch_file_tombstones().await
+    }
+
+    async fn update_local_file_version(&self, file_id: i32, version: &str) -> anyhow::Result<()> {
+        self.data_source
+            .update_local_file_version(file_id, version)
+            .await
+    }
+
+    async fn save_tracked_file(&self, tracked_file: &FilesTrackedRow) -> Result<(), FileSyncError> {
+        self.data_source.save_tracked_file(tracked_file).await
+    }
+
+    async fn fetch_tracked_files(&self) -> anyhow::Result<Vec<FilesTrackedRow>> {
+        self.data_source.fetch_tracked_files().await
+    }
+
+    async fn delete_tracked_files(
+        &self,
+        local_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        self.data_source
+            .delete_tracked_files(local_id, peer_uuid)
+            .await
+    }
+
+    async fn update_tracked_file_hash(
+        &self,
+        local_id: i32,
+        peer_uuid: &str,
+        hash_sha1: &str,
+    ) -> anyhow::Result<()> {
+        self.data_source
+            .update_tracked_file_hash(local_id, peer_uuid, hash_sha1)
+            .await
+    }
+
+    async fn save_file_conflict(&self, conflict: &FileConflictRow) -> anyhow::Result<()> {
+        self.data_source.save_file_conflict(conflict).await
+    }
+
+    async fn fetch_file_conflicts(&self) -> anyhow::Result<Vec<FileConflictRow>> {
+        self.data_source.fetch_file_conflicts().await
+    }
+
+    async fn delete_file_conflicts(
+        &self,
+        file_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        self.data_source
+            .delete_file_conflicts(file_id, peer_uuid)
+            .await
+    }
+
+    async fn save_sync_root(&self, path: &str) -> Result<i32, SyncRootError> {
+        self.data_source.save_sync_root(path).await
+    }
+
+    async fn fetch_sync_roots(&self) -> anyhow::Result<Vec<SyncRootRow>> {
+        self.data_source.fetch_sync_roots().await
+    }
+
+    async fn delete_sync_root(&self, root_id: i32) -> anyhow::Result<usize> {
+        self.data_source.delete_sync_root(root_id).await
+    }
+
+    async fn save_root_access(
+        &self,
+        root_id: i32,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        self.data_source
+            .save_root_access(root_id, host_uuid, access)
+            .await
+    }
+
+    async fn fetch_root_access(&self, root_id: i32, host_uuid: &str) -> anyhow::Result<Access> {
+        self.data_source.fetch_root_access(root_id, host_uuid).await
+    }
+
+    async fn save_tracked_root(&self, tracked_root: &RootsTrackedRow) -> Result<(), FileSyncError> {
+        self.data_source.save_tracked_root(tracked_root).await
+    }
+
+    async fn fetch_tracked_roots(&self) -> anyhow::Result<Vec<RootsTrackedRow>> {
+        self.data_source.fetch_tracked_roots().await
+    }
+
+    async fn delete_tracked_roots(
+        &self,
+        root_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        self.data_source
+            .delete_tracked_roots(root_id, peer_uuid)
+            .await
+    }
+
     // Groups (local)
     async fn save_local_group(&self, group_id: &str) -> Result<usize, SaveLocalGroupError> {
         self.data_source.save_local_group(group_id).await
@@ -115,4 +496,57 @@ where
     async fn fetch_local_groups(&self) -> anyhow::Result<Vec<GroupInfo>> {
         self.data_source.fetch_local_groups().await
     }
+
+    async fn fetch_file_groups(&self, file_id: i32) -> anyhow::Result<Vec<GroupsLocalRow>> {
+        self.data_source.fetch_file_groups(file_id).await
+    }
+
+    async fn update_group_strategy(
+        &self,
+        group_name: &str,
+        conflict_strategy: &str,
+        host_priority: &str,
+    ) -> Result<(), AccessControlError> {
+        self.data_source
+            .update_group_strategy(group_name, conflict_strategy, host_priority)
+            .await
+    }
+
+    async fn save_group_files(
+        &self,
+        group_name: &str,
+        file_paths: &[String],
+    ) -> Result<(), AccessControlError> {
+        self.data_source
+            .save_group_files(group_name, file_paths)
+            .await
+    }
+
+    async fn save_group_access(
+        &self,
+        group_name: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        self.data_source
+            .save_group_access(group_name, host_uuid, access)
+            .await
+    }
+
+    async fn save_file_access(
+        &self,
+        file_path: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        self.data_source
+            .save_file_access(file_path, host_uuid, access)
+            .await
+    }
+
+    async fn fetch_file_access(&self, file_path: &str, host_uuid: &str) -> anyhow::Result<Access> {
+        self.data_source
+            .fetch_file_access(file_path, host_uuid)
+            .await
+    }
 }
@@ -4,8 +4,16 @@ use dsync_proto::model::server::{GroupInfo, HostInfo};
 pub use sqlite::SqliteDataSource;
 
 use crate::server::data::source::sqlite::database::{
-    error::{DeleteLocalGroupError, FileAddError, LocalServerBaseInfoError, SaveLocalGroupError},
-    models::{FilesLocalFragmentInsert, FilesLocalRow, HostsRow},
+    error::{
+        AccessControlError, DeleteLocalGroupError, FileAddError, FileSyncError, HostAliasError,
+        LocalServerBaseInfoError, SaveLocalGroupError, SyncRootError,
+    },
+    models::{
+        Access, FileConflictRow, FileTombstoneRow, FilesLocalContentFragmentUpdate,
+        FilesLocalFragmentInsert, FilesLocalRow, FilesTrackedRow, GroupsLocalRow, HostAddressRow,
+        HostsMetadataFragmentUpdate, HostsRow, HostsVersionFragmentUpdate, RootsTrackedRow,
+        SyncRootRow,
+    },
 };
 
 /// The data source interface that `MainRepository` depends on.
@@ -18,10 +26,46 @@ pub(crate) trait DataSource: Send + Sync {
     async fn insert_hosts(&self, hosts_rows: &[HostsRow]) -> anyhow::Result<()>;
     async fn fetch_hosts(&self) -> anyhow::Result<Vec<HostInfo>>;
     async fn fetch_host_by_uuid(&self, uuid: &str) -> anyhow::Result<HostsRow>;
+    async fn find_host_by_uuid(&self, uuid: &str) -> anyhow::Result<Option<HostsRow>>;
     async fn fetch_host_by_name(&self, name: &str) -> anyhow::Result<HostsRow>;
     #[allow(unused)]
     async fn fetch_host_by_local_id(&self, local_id: i32) -> anyhow::Result<HostsRow>;
     async fn delete_host_with_uuid(&self, host_uuid: &str) -> anyhow::Result<()>;
+    async fn update_host_addr(
+        &self,
+        host_uuid: &str,
+        address: &str,
+        port: u16,
+        seen_time: i64,
+    ) -> anyhow::Result<()>;
+    async fn fetch_host_addresses(&self, host_uuid: &str) -> anyhow::Result<Vec<HostAddressRow>>;
+    async fn update_host_version(
+        &self,
+        host_uuid: &str,
+        version: &HostsVersionFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    async fn fetch_host_by_public_key(&self, public_key: &str) -> anyhow::Result<Option<HostsRow>>;
+    async fn pin_host_public_key(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()>;
+    async fn pair_host(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()>;
+    async fn update_host_metadata(
+        &self,
+        host_uuid: &str,
+        metadata: &HostsMetadataFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    async fn fetch_host_by_alias(&self, alias: &str) -> anyhow::Result<Option<HostsRow>>;
+    async fn save_host_alias(&self, host_uuid: &str, alias: &str) -> Result<(), HostAliasError>;
+    async fn delete_host_alias(&self, host_uuid: &str, alias: &str) -> anyhow::Result<usize>;
+    async fn save_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()>;
+    async fn delete_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()>;
+
+    // Blocklist
+    async fn fetch_blocklist(&self) -> anyhow::Result<Vec<String>>;
+    async fn save_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize>;
+    async fn delete_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize>;
+
+    // Identity (local)
+    async fn fetch_local_secret_key(&self) -> anyhow::Result<Option<Vec<u8>>>;
+    async fn save_local_secret_key(&self, secret_key: &[u8]) -> anyhow::Result<()>;
 
     // Files (local)
     async fn save_local_files(
@@ -30,9 +74,90 @@ pub(crate) trait DataSource: Send + Sync {
     ) -> Result<(), FileAddError>;
     async fn fetch_local_files(&self) -> anyhow::Result<Vec<FilesLocalRow>>;
     async fn delete_local_file(&self, file_path: &str) -> anyhow::Result<usize>;
+    async fn update_local_file_content(
+        &self,
+        file_id: i32,
+        content: &FilesLocalContentFragmentUpdate,
+    ) -> anyhow::Result<()>;
+    async fn update_local_file_path(&self, file_id: i32, file_path: &str) -> anyhow::Result<()>;
+    async fn mark_local_file_gone(&self, tombstone: &FileTombstoneRow) -> anyhow::Result<()>;
+    async fn fetch_file_tombstones(&self) -> anyhow::Result<Vec<FileTombstoneRow>>;
+    async fn update_local_file_version(&self, file_id: i32, version: &str) -> anyhow::Result<()>;
+
+    // Files (tracked)
+    async fn save_tracked_file(&self, tracked_file: &FilesTrackedRow) -> Result<(), FileSyncError>;
+    async fn fetch_tracked_files(&self) -> anyhow::Result<Vec<FilesTrackedRow>>;
+    async fn delete_tracked_files(
+        &self,
+        local_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
+    async fn update_tracked_file_hash(
+        &self,
+        local_id: i32,
+        peer_uuid: &str,
+        hash_sha1: &str,
+    ) -> anyhow::Result<()>;
+
+    // File conflicts
+    async fn save_file_conflict(&self, conflict: &FileConflictRow) -> anyhow::Result<()>;
+    async fn fetch_file_conflicts(&self) -> anyhow::Result<Vec<FileConflictRow>>;
+    async fn delete_file_conflicts(
+        &self,
+        file_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
+
+    // Sync roots
+    async fn save_sync_root(&self, path: &str) -> Result<i32, SyncRootError>;
+    async fn fetch_sync_roots(&self) -> anyhow::Result<Vec<SyncRootRow>>;
+    async fn delete_sync_root(&self, root_id: i32) -> anyhow::Result<usize>;
+    async fn save_root_access(
+        &self,
+        root_id: i32,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    async fn fetch_root_access(&self, root_id: i32, host_uuid: &str) -> anyhow::Result<Access>;
+
+    // Roots (tracked)
+    async fn save_tracked_root(&self, tracked_root: &RootsTrackedRow) -> Result<(), FileSyncError>;
+    async fn fetch_tracked_roots(&self) -> anyhow::Result<Vec<RootsTrackedRow>>;
+    async fn delete_tracked_roots(
+        &self,
+        root_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize>;
 
     // Groups (local)
     async fn save_local_group(&self, group_id: &str) -> Result<usize, SaveLocalGroupError>;
     async fn delete_group_by_name(&self, group_name: &str) -> Result<(), DeleteLocalGroupError>;
     async fn fetch_local_groups(&self) -> anyhow::Result<Vec<GroupInfo>>;
+    async fn fetch_file_groups(&self, file_id: i32) -> anyhow::Result<Vec<GroupsLocalRow>>;
+    async fn update_group_strategy(
+        &self,
+        group_name: &str,
+        conflict_strategy: &str,
+        host_priority: &str,
+    ) -> Result<(), AccessControlError>;
+    async fn save_group_files(
+        &self,
+        group_name: &str,
+        file_paths: &[String],
+    ) -> Result<(), AccessControlError>;
+
+    // Access control
+    async fn save_group_access(
+        &self,
+        group_name: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    async fn save_file_access(
+        &self,
+        file_path: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError>;
+    async fn fetch_file_access(&self, file_path: &str, host_uuid: &str) -> anyhow::Result<Access>;
 }
@@ -1,11 +1,11 @@
 use anyhow::Context;
 use async_trait::async_trait;
 use diesel::{
-    ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection,
-    result::DatabaseErrorKind,
+    BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+    SelectableHelper, SqliteConnection, result::DatabaseErrorKind,
 };
 use dsync_proto::model::server::{GroupInfo, HostInfo};
-use std::ops::DerefMut;
+use std::{ops::DerefMut, path::Path};
 
 pub(crate) mod database;
 
@@ -13,11 +13,16 @@ use crate::server::data::source::{
     DataSource,
     sqlite::database::{
         error::{
-            DeleteLocalGroupError, FileAddError, LocalServerBaseInfoError, SaveLocalGroupError,
+            AccessControlError, DeleteLocalGroupError, FileAddError, FileSyncError, HostAliasError,
+            LocalServerBaseInfoError, SaveLocalGroupError, SyncRootError,
         },
         models::{
-            FilesLocalFragmentInsert, FilesLocalRow, GroupsLocalFragmentInsert, GroupsLocalRow,
-            HostsRow,
+            Access, FileAccessRow, FileConflictRow, FileTombstoneRow,
+            FilesLocalContentFragmentUpdate, FilesLocalFragmentInsert, FilesLocalRow,
+            FilesTrackedRow, GroupAccessRow, GroupFilesLocalRow, GroupsLocalFragmentInsert,
+            GroupsLocalRow, HostAddressRow, HostAliasRow, HostBlocklistRow, HostTagRow,
+            HostsMetadataFragmentUpdate, HostsRow, HostsVersionFragmentUpdate, LocalIdentityRow,
+            RootAccessRow, RootsTrackedRow, SyncRootRow,
         },
         schema,
     },
@@ -60,6 +65,91 @@ impl SqliteDataSource {
 
         Ok(ds)
     }
+
+    fn find_file_id(conn: &mut SqliteConnection, file_path: &str) -> anyhow::Result<Option<i32>> {
+        use schema::files_local::dsl as fl;
+
+        fl::files_local
+            .filter(fl::file_path.eq(file_path))
+            .select(fl::id)
+            .first(conn)
+            .optional()
+            .context("Failed to fetch local file id from db")
+    }
+
+    /// Conflicts of the files with the peer, or with all peers when none is given.
+    fn delete_conflicts_of(
+        conn: &mut SqliteConnection,
+        file_ids: &[i32],
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        use schema::file_conflicts::dsl as fc;
+
+        let result = match peer_uuid {
+            Some(peer_uuid) => diesel::delete(QueryDsl::filter(
+                fc::file_conflicts,
+                fc::file_id
+                    .eq_any(file_ids)
+                    .and(fc::peer_uuid.eq(peer_uuid)),
+            ))
+            .execute(conn),
+            None => diesel::delete(QueryDsl::filter(
+                fc::file_conflicts,
+                fc::file_id.eq_any(file_ids),
+            ))
+            .execute(conn),
+        };
+
+        result.context("Failed to delete file conflicts")
+    }
+
+    fn delete_tombstones_of(conn: &mut SqliteConnection, file_ids: &[i32]) -> anyhow::Result<()> {
+        use schema::file_tombstones::dsl as fts;
+
+        diesel::delete(QueryDsl::filter(
+            fts::file_tombstones,
+            fts::file_id.eq_any(file_ids),
+        ))
+        .execute(conn)
+        .context("Failed to delete file tombstones")?;
+
+        Ok(())
+    }
+
+    /// Access of the host granted through any of the roots matching the filter.
+    fn find_root_access(
+        conn: &mut SqliteConnection,
+        root_filter: impl Fn(i32) -> bool,
+        host_uuid: &str,
+    ) -> anyhow::Result<Access> {
+        use schema::root_access::dsl as ra;
+
+        let rows: Vec<RootAccessRow> =
+            QueryDsl::filter(ra::root_access, ra::host_uuid.eq(host_uuid))
+                .select(RootAccessRow::as_select())
+                .load(conn)
+                .context("Failed to fetch root access from db")?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|row| root_filter(row.root_id))
+            .map(|row| Access {
+                read: row.can_read,
+                write: row.can_write,
+            })
+            .fold(Access::default(), Access::union))
+    }
+
+    fn find_group_id(conn: &mut SqliteConnection, group_name: &str) -> anyhow::Result<Option<i32>> {
+        use schema::groups_local::dsl as gl;
+
+        gl::groups_local
+            .filter(gl::name.eq(group_name))
+            .select(gl::id)
+            .first(conn)
+            .optional()
+            .context("Failed to fetch local group id from db")
+    }
 }
 
 #[async_trait]
@@ -96,39 +186,93 @@ impl DataSource for SqliteDataSource {
     }
 
     async fn insert_hosts(&self, hosts_rows: &[HostsRow]) -> anyhow::Result<()> {
+        use schema::host_addresses::dsl as hat;
+        use schema::host_local_id_sequence::dsl as hls;
         use schema::hosts::dsl as ht;
 
         let mut connection = self.conn.lock().await;
-        let conn_ref_mut: &mut SqliteConnection = &mut connection;
 
         // See Diesel note in original implementation: batch insert resolves to multiple queries in SQLite.
-        for info in hosts_rows {
-            diesel::insert_into(ht::hosts)
-                .values(info)
-                .on_conflict_do_nothing()
-                .execute(conn_ref_mut)
-                .context("Failed to insert peer info to db")?;
-        }
+        connection.transaction(|conn| {
+            for info in hosts_rows {
+                let next_local_id: i32 = hls::host_local_id_sequence
+                    .select(hls::next_local_id)
+                    .first(conn)
+                    .context("Failed to fetch next host local id from db")?;
 
-        Ok(())
+                // Known hosts keep their ids, conflicting rows are not inserted at all.
+                let inserted_rows = diesel::insert_into(ht::hosts)
+                    .values(HostsRow {
+                        local_id: next_local_id,
+                        ..info.clone()
+                    })
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .context("Failed to insert peer info to db")?;
+
+                if inserted_rows > 0 {
+                    diesel::update(hls::host_local_id_sequence)
+                        .set(hls::next_local_id.eq(next_local_id + 1))
+                        .execute(conn)
+                        .context("Failed to advance host local id sequence")?;
+                }
+
+                diesel::insert_into(hat::host_addresses)
+                    .values(HostAddressRow {
+                        host_uuid: info.uuid.clone(),
+                        address: info.address.clone(),
+                        port: info.port,
+                        last_seen: info.discovery_time,
+                    })
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .context("Failed to insert peer address to db")?;
+            }
+
+            Ok(())
+        })
     }
 
     async fn fetch_hosts(&self) -> anyhow::Result<Vec<HostInfo>> {
+        use schema::host_aliases::dsl as hal;
+        use schema::host_tags::dsl as htg;
         use schema::hosts::dsl::*;
 
         let qr_result = {
             let mut connection = self.conn.lock().await;
-            hosts.select(HostsRow::as_select()).load(&mut *connection)
+            hosts
+                .order(local_id)
+                .select(HostsRow::as_select())
+                .load(&mut *connection)
+                .and_then(|rows| {
+                    let aliases = hal::host_aliases
+                        .order(hal::alias)
+                        .select(HostAliasRow::as_select())
+                        .load(&mut *connection)?;
+                    let tags = htg::host_tags
+                        .order(htg::tag)
+                        .select(HostTagRow::as_select())
+                        .load(&mut *connection)?;
+                    Ok((rows, aliases, tags))
+                })
         };
 
         match qr_result {
-            Ok(data) => Ok(data
-                .into_iter()
-                .map(|host_info| HostInfo {
-                    uuid: host_info.uuid,
-                    name: host_info.name,
-                    hostname: host_info.hostname,
-                    address: host_info.ipv4_addr,
+            Ok((rows, aliases, tags)) => Ok(rows
+                .iter()
+                .map(|row| {
+                    let mut info = row.to_host_info();
+                    info.aliases = aliases
+                        .iter()
+                        .filter(|alias_row| alias_row.host_uuid == row.uuid)
+                        .map(|alias_row| alias_row.alias.clone())
+                        .collect();
+                    info.tags = tags
+                        .iter()
+                        .filter(|tag_row| tag_row.host_uuid == row.uuid)
+                        .map(|tag_row| tag_row.tag.clone())
+                        .collect();
+                    info
                 })
                 .collect()),
             Err(error) => {
@@ -155,36 +299,425 @@ impl DataSource for SqliteDataSource {
         Ok(row)
     }
 
+    async fn find_host_by_uuid(&self, uuid: &str) -> anyhow::Result<Option<HostsRow>> {
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        QueryDsl::filter(ht::hosts, ht::uuid.eq(uuid))
+            .select(HostsRow::as_select())
+            .first(&mut *connection)
+            .optional()
+            .context("Failed to fetch host by uuid")
+    }
+
     async fn fetch_host_by_name(&self, name: &str) -> anyhow::Result<HostsRow> {
         use schema::hosts::dsl as ht;
 
         let qr_result = {
             let mut connection = self.conn.lock().await;
             QueryDsl::filter(ht::hosts, ht::name.eq(name))
+                .select(HostsRow::as_select())
+                .limit(2)
+                .load(connection.deref_mut())
+        };
+
+        let Ok(mut rows) = qr_result else {
+            anyhow::bail!("Failed to fetch the row by name");
+        };
+
+        // Names are not unique, e.g. many hosts might be called `localhost`.
+        if rows.len() > 1 {
+            anyhow::bail!("Host name `{name}` is ambiguous, use local id or an alias instead");
+        }
+
+        rows.pop()
+            .ok_or_else(|| anyhow::anyhow!("Failed to fetch the row by name"))
+    }
+
+    async fn fetch_host_by_local_id(&self, local_id: i32) -> anyhow::Result<HostsRow> {
+        use schema::hosts::dsl as ht;
+
+        let qr_result = {
+            let mut connection = self.conn.lock().await;
+            QueryDsl::filter(ht::hosts, ht::local_id.eq(local_id))
                 .select(HostsRow::as_select())
                 .first(connection.deref_mut())
         };
 
         let Ok(row) = qr_result else {
-            anyhow::bail!("Failed to fetch the row by name");
+            anyhow::bail!("Failed to fetch the row by local id");
         };
 
         Ok(row)
     }
 
-    async fn fetch_host_by_local_id(&self, _local_id: i32) -> anyhow::Result<HostsRow> {
-        anyhow::bail!("fetch_host_by_local_id is not implemented yet")
+    async fn delete_host_with_uuid(&self, host_uuid: &str) -> anyhow::Result<()> {
+        use schema::host_addresses::dsl as hat;
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        connection.transaction(|conn| {
+            let filtered_table = QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid));
+            let _ = diesel::delete(filtered_table)
+                .execute(conn)
+                .context("Failed to delete host from db")?;
+
+            // Foreign keys are not enforced, cascade by hand.
+            let _ = diesel::delete(QueryDsl::filter(
+                hat::host_addresses,
+                hat::host_uuid.eq(host_uuid),
+            ))
+            .execute(conn)
+            .context("Failed to delete host addresses from db")?;
+
+            {
+                use schema::file_access::dsl as fa;
+                use schema::group_access::dsl as ga;
+
+                let _ = diesel::delete(QueryDsl::filter(
+                    ga::group_access,
+                    ga::host_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete group access of the host from db")?;
+                let _ = diesel::delete(QueryDsl::filter(
+                    fa::file_access,
+                    fa::host_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete file access of the host from db")?;
+            }
+
+            {
+                use schema::host_aliases::dsl as hal;
+                use schema::host_tags::dsl as htg;
+
+                let _ = diesel::delete(QueryDsl::filter(
+                    hal::host_aliases,
+                    hal::host_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete aliases of the host from db")?;
+                let _ = diesel::delete(QueryDsl::filter(
+                    htg::host_tags,
+                    htg::host_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete tags of the host from db")?;
+            }
+
+            {
+                use schema::file_conflicts::dsl as fc;
+                use schema::files_tracked::dsl as ft;
+                use schema::root_access::dsl as ra;
+                use schema::roots_tracked::dsl as rt;
+
+                let _ = diesel::delete(QueryDsl::filter(
+                    ft::files_tracked,
+                    ft::peer_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete files synced with the host from db")?;
+                let _ = diesel::delete(QueryDsl::filter(
+                    fc::file_conflicts,
+                    fc::peer_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete conflicts with files of the host from db")?;
+                let _ = diesel::delete(QueryDsl::filter(
+                    rt::roots_tracked,
+                    rt::peer_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete roots synced with the host from db")?;
+                let _ = diesel::delete(QueryDsl::filter(
+                    ra::root_access,
+                    ra::host_uuid.eq(host_uuid),
+                ))
+                .execute(conn)
+                .context("Failed to delete root access of the host from db")?;
+            }
+
+            Ok(())
+        })
     }
 
-    async fn delete_host_with_uuid(&self, host_uuid: &str) -> anyhow::Result<()> {
+    async fn update_host_addr(
+        &self,
+        host_uuid: &str,
+        address: &str,
+        port: u16,
+        seen_time: i64,
+    ) -> anyhow::Result<()> {
+        use schema::host_addresses::dsl as hat;
         use schema::hosts::dsl as ht;
 
         let mut connection = self.conn.lock().await;
-        let conn_ref_mut: &mut SqliteConnection = &mut connection;
-        let filtered_table = QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid));
-        let _ = diesel::delete(filtered_table)
-            .execute(conn_ref_mut)
-            .context("Failed to delete host from db")?;
+        connection.transaction(|conn| {
+            let updated_rows = diesel::update(QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid)))
+                .set((ht::address.eq(address), ht::port.eq(i32::from(port))))
+                .execute(conn)
+                .context("Failed to update host address")?;
+
+            if updated_rows == 0 {
+                anyhow::bail!("No host with uuid: {host_uuid}");
+            }
+
+            diesel::insert_into(hat::host_addresses)
+                .values(HostAddressRow {
+                    host_uuid: host_uuid.to_owned(),
+                    address: address.to_owned(),
+                    port: i32::from(port),
+                    last_seen: seen_time,
+                })
+                .on_conflict((hat::host_uuid, hat::address, hat::port))
+                .do_update()
+                .set(hat::last_seen.eq(seen_time))
+                .execute(conn)
+                .context("Failed to save host address")?;
+
+            Ok(())
+        })
+    }
+
+    async fn fetch_host_addresses(&self, host_uuid: &str) -> anyhow::Result<Vec<HostAddressRow>> {
+        use schema::host_addresses::dsl as hat;
+
+        let mut connection = self.conn.lock().await;
+        QueryDsl::filter(hat::host_addresses, hat::host_uuid.eq(host_uuid))
+            .order(hat::last_seen.desc())
+            .select(HostAddressRow::as_select())
+            .load(&mut *connection)
+            .context("Failed to fetch host addresses")
+    }
+
+    async fn update_host_version(
+        &self,
+        host_uuid: &str,
+        version: &HostsVersionFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        let updated_rows = diesel::update(QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid)))
+            .set(version)
+            .execute(&mut *connection)
+            .context("Failed to update host version")?;
+
+        if updated_rows == 0 {
+            anyhow::bail!("No host with uuid: {host_uuid}");
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_host_by_public_key(&self, public_key: &str) -> anyhow::Result<Option<HostsRow>> {
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        QueryDsl::filter(ht::hosts, ht::public_key.eq(public_key))
+            .select(HostsRow::as_select())
+            .first(&mut *connection)
+            .optional()
+            .context("Failed to fetch host by public key")
+    }
+
+    async fn pin_host_public_key(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()> {
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        diesel::update(QueryDsl::filter(
+            ht::hosts,
+            ht::uuid.eq(host_uuid).and(ht::public_key.is_null()),
+        ))
+        .set(ht::public_key.eq(public_key))
+        .execute(&mut *connection)
+        .context("Failed to pin host public key")?;
+
+        Ok(())
+    }
+
+    async fn pair_host(&self, host_uuid: &str, public_key: &str) -> anyhow::Result<()> {
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        let updated_rows = diesel::update(QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid)))
+            .set((ht::public_key.eq(public_key), ht::paired.eq(true)))
+            .execute(&mut *connection)
+            .context("Failed to save paired host key")?;
+
+        if updated_rows == 0 {
+            anyhow::bail!("No host with uuid: {host_uuid}");
+        }
+
+        Ok(())
+    }
+
+    async fn update_host_metadata(
+        &self,
+        host_uuid: &str,
+        metadata: &HostsMetadataFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        use schema::hosts::dsl as ht;
+
+        // Diesel refuses empty changesets.
+        if metadata.name.is_none() && metadata.notes.is_none() {
+            return Ok(());
+        }
+
+        let mut connection = self.conn.lock().await;
+        let updated_rows = diesel::update(QueryDsl::filter(ht::hosts, ht::uuid.eq(host_uuid)))
+            .set(metadata)
+            .execute(&mut *connection)
+            .context("Failed to update host metadata")?;
+
+        if updated_rows == 0 {
+            anyhow::bail!("No host with uuid: {host_uuid}");
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_host_by_alias(&self, alias: &str) -> anyhow::Result<Option<HostsRow>> {
+        use schema::host_aliases::dsl as hal;
+        use schema::hosts::dsl as ht;
+
+        let mut connection = self.conn.lock().await;
+        ht::hosts
+            .inner_join(hal::host_aliases)
+            .filter(hal::alias.eq(alias))
+            .select(HostsRow::as_select())
+            .first(&mut *connection)
+            .optional()
+            .context("Failed to fetch host by alias")
+    }
+
+    async fn save_host_alias(&self, host_uuid: &str, alias: &str) -> Result<(), HostAliasError> {
+        use schema::host_aliases as hal;
+
+        let query_res = {
+            let mut connection = self.conn.lock().await;
+            diesel::insert_into(hal::table)
+                .values(HostAliasRow {
+                    alias: alias.to_owned(),
+                    host_uuid: host_uuid.to_owned(),
+                })
+                .execute(&mut *connection)
+        };
+
+        match query_res {
+            Ok(_) => Ok(()),
+            Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                Err(HostAliasError::AlreadyTaken(alias.to_owned()))
+            }
+            Err(error) => Err(HostAliasError::Other(error.into())),
+        }
+    }
+
+    async fn delete_host_alias(&self, host_uuid: &str, alias: &str) -> anyhow::Result<usize> {
+        use schema::host_aliases::dsl as hal;
+
+        let mut connection = self.conn.lock().await;
+        diesel::delete(QueryDsl::filter(
+            hal::host_aliases,
+            hal::alias.eq(alias).and(hal::host_uuid.eq(host_uuid)),
+        ))
+        .execute(&mut *connection)
+        .context("Failed to delete host alias")
+    }
+
+    async fn save_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()> {
+        use schema::host_tags as htg;
+
+        let rows: Vec<HostTagRow> = tags
+            .iter()
+            .map(|tag| HostTagRow {
+                host_uuid: host_uuid.to_owned(),
+                tag: tag.clone(),
+            })
+            .collect();
+
+        let mut connection = self.conn.lock().await;
+        diesel::insert_or_ignore_into(htg::table)
+            .values(&rows)
+            .execute(&mut *connection)
+            .context("Failed to save host tags")?;
+
+        Ok(())
+    }
+
+    async fn delete_host_tags(&self, host_uuid: &str, tags: &[String]) -> anyhow::Result<()> {
+        use schema::host_tags::dsl as htg;
+
+        let mut connection = self.conn.lock().await;
+        diesel::delete(QueryDsl::filter(
+            htg::host_tags,
+            htg::host_uuid.eq(host_uuid).and(htg::tag.eq_any(tags)),
+        ))
+        .execute(&mut *connection)
+        .context("Failed to delete host tags")?;
+
+        Ok(())
+    }
+
+    // Blocklist
+    async fn fetch_blocklist(&self) -> anyhow::Result<Vec<String>> {
+        use schema::host_blocklist::dsl as hbl;
+
+        let mut connection = self.conn.lock().await;
+        hbl::host_blocklist
+            .select(hbl::rule)
+            .order(hbl::rule)
+            .load(&mut *connection)
+            .context("Failed to fetch blocklist")
+    }
+
+    async fn save_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize> {
+        use schema::host_blocklist as hbl;
+
+        let mut connection = self.conn.lock().await;
+        diesel::insert_or_ignore_into(hbl::table)
+            .values(HostBlocklistRow {
+                rule: rule.to_owned(),
+            })
+            .execute(&mut *connection)
+            .context("Failed to save blocklist rule")
+    }
+
+    async fn delete_blocklist_rule(&self, rule: &str) -> anyhow::Result<usize> {
+        use schema::host_blocklist::dsl as hbl;
+
+        let mut connection = self.conn.lock().await;
+        diesel::delete(QueryDsl::filter(hbl::host_blocklist, hbl::rule.eq(rule)))
+            .execute(&mut *connection)
+            .context("Failed to delete blocklist rule")
+    }
+
+    // Identity (local)
+    async fn fetch_local_secret_key(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        use schema::local_identity::dsl as li;
+
+        let mut connection = self.conn.lock().await;
+        let row = li::local_identity
+            .select(LocalIdentityRow::as_select())
+            .first(&mut *connection)
+            .optional()
+            .context("Failed to fetch local identity")?;
+
+        Ok(row.map(|row| row.secret_key))
+    }
+
+    async fn save_local_secret_key(&self, secret_key: &[u8]) -> anyhow::Result<()> {
+        use schema::local_identity::dsl as li;
+
+        let mut connection = self.conn.lock().await;
+        diesel::replace_into(li::local_identity)
+            .values(LocalIdentityRow {
+                id: 0,
+                secret_key: secret_key.to_vec(),
+            })
+            .execute(&mut *connection)
+            .context("Failed to save local identity")?;
 
         Ok(())
     }
@@ -232,18 +765,502 @@ impl DataSource for SqliteDataSource {
     }
 
     async fn delete_local_file(&self, file_path: &str) -> anyhow::Result<usize> {
+        use schema::file_access::dsl as fa;
+        use schema::file_conflicts::dsl as fc;
         use schema::files_local::dsl as fl;
+        use schema::files_tracked::dsl as ft;
+        use schema::group_files_local::dsl as gfl;
 
         let mut connection = self.conn.lock().await;
         let conn_ref_mut = &mut *connection;
 
+        let Some(file_id) = Self::find_file_id(conn_ref_mut, file_path)? else {
+            return anyhow::Ok(0);
+        };
+
         let result = diesel::delete(fl::files_local)
-            .filter(fl::file_path.eq(file_path))
+            .filter(fl::id.eq(file_id))
             .execute(conn_ref_mut)?;
 
+        // Foreign keys are not enforced, cascade by hand.
+        let _ = diesel::delete(QueryDsl::filter(
+            gfl::group_files_local,
+            gfl::file_id.eq(file_id),
+        ))
+        .execute(conn_ref_mut)
+        .context("Failed to delete group membership of the file from db")?;
+        let _ = diesel::delete(QueryDsl::filter(fa::file_access, fa::file_id.eq(file_id)))
+            .execute(conn_ref_mut)
+            .context("Failed to delete access to the file from db")?;
+        let _ = diesel::delete(QueryDsl::filter(
+            ft::files_tracked,
+            ft::local_id.eq(file_id),
+        ))
+        .execute(conn_ref_mut)
+        .context("Failed to delete sync pairings of the file from db")?;
+        let _ = diesel::delete(QueryDsl::filter(
+            fc::file_conflicts,
+            fc::file_id.eq(file_id),
+        ))
+        .execute(conn_ref_mut)
+        .context("Failed to delete conflicts of the file from db")?;
+        Self::delete_tombstones_of(conn_ref_mut, &[file_id])?;
+
         anyhow::Ok(result)
     }
 
+    async fn update_local_file_content(
+        &self,
+        file_id: i32,
+        content: &FilesLocalContentFragmentUpdate,
+    ) -> anyhow::Result<()> {
+        use schema::files_local::dsl as fl;
+
+        let mut connection = self.conn.lock().await;
+        diesel::update(QueryDsl::filter(fl::files_local, fl::id.eq(file_id)))
+            .set(content)
+            .execute(&mut *connection)
+            .context("Failed to update hash of the local file")?;
+        // The file is there (again).
+        Self::delete_tombstones_of(&mut connection, &[file_id])?;
+
+        Ok(())
+    }
+
+    async fn update_local_file_path(&self, file_id: i32, file_path: &str) -> anyhow::Result<()> {
+        use schema::files_local::dsl as fl;
+
+        let mut connection = self.conn.lock().await;
+        diesel::update(QueryDsl::filter(fl::files_local, fl::id.eq(file_id)))
+            .set(fl::file_path.eq(file_path))
+            .execute(&mut *connection)
+            .context("Failed to update path of the local file")?;
+
+        Ok(())
+    }
+
+    async fn mark_local_file_gone(&self, tombstone: &FileTombstoneRow) -> anyhow::Result<()> {
+        use schema::file_tombstones::dsl as fts;
+        use schema::files_local::dsl as fl;
+
+        let mut connection = self.conn.lock().await;
+        connection.transaction(|conn| {
+            diesel::update(QueryDsl::filter(
+                fl::files_local,
+                fl::id.eq(tombstone.file_id),
+            ))
+            .set((fl::gone.eq(true), fl::version.eq(&tombstone.version)))
+            .execute(conn)
+            .context("Failed to mark the local file as gone")?;
+            diesel::replace_into(fts::file_tombstones)
+                .values(tombstone)
+                .execute(conn)
+                .context("Failed to save the tombstone of the local file")?;
+
+            anyhow::Ok(())
+        })
+    }
+
+    async fn fetch_file_tombstones(&self) -> anyhow::Result<Vec<FileTombstoneRow>> {
+        use schema::file_tombstones::dsl as fts;
+
+        let mut connection = self.conn.lock().await;
+        let result = fts::file_tombstones
+            .select(FileTombstoneRow::as_select())
+            .load(&mut *connection)
+            .context("Failed to fetch file tombstones")?;
+
+        Ok(result)
+    }
+
+    async fn update_local_file_version(&self, file_id: i32, version: &str) -> anyhow::Result<()> {
+        use schema::files_local::dsl as fl;
+
+        let mut connection = self.conn.lock().await;
+        diesel::update(QueryDsl::filter(fl::files_local, fl::id.eq(file_id)))
+            .set(fl::version.eq(version))
+            .execute(&mut *connection)
+            .context("Failed to update version of the local file")?;
+
+        Ok(())
+    }
+
+    // Files (tracked)
+    async fn save_tracked_file(&self, tracked_file: &FilesTrackedRow) -> Result<(), FileSyncError> {
+        use schema::files_tracked::dsl as ft;
+
+        let mut connection = self.conn.lock().await;
+        let synced_local_id = connection.transaction(|conn| {
+            let synced_local_id: Option<i32> = QueryDsl::filter(
+                ft::files_tracked,
+                ft::peer_uuid
+                    .eq(&tracked_file.peer_uuid)
+                    .and(ft::remote_id.eq(tracked_file.remote_id))
+                    .and(ft::local_id.ne(tracked_file.local_id)),
+            )
+            .select(ft::local_id)
+            .first(conn)
+            .optional()
+            .context("Failed to fetch sync pairings")?;
+
+            if synced_local_id.is_none() {
+                diesel::replace_into(ft::files_tracked)
+                    .values(tracked_file)
+                    .execute(conn)
+                    .context("Failed to save sync pairing")?;
+            }
+
+            anyhow::Ok(synced_local_id)
+        })?;
+
+        match synced_local_id {
+            Some(local_id) => Err(FileSyncError::AlreadySynced {
+                peer_uuid: tracked_file.peer_uuid.clone(),
+                remote_id: tracked_file.remote_id,
+                local_id,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    async fn fetch_tracked_files(&self) -> anyhow::Result<Vec<FilesTrackedRow>> {
+        use schema::files_tracked::dsl as ft;
+
+        let mut connection = self.conn.lock().await;
+        ft::files_tracked
+            .select(FilesTrackedRow::as_select())
+            .order((ft::local_id, ft::peer_uuid))
+            .load(&mut *connection)
+            .context("Failed to fetch sync pairings")
+    }
+
+    async fn delete_tracked_files(
+        &self,
+        local_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        use schema::files_tracked::dsl as ft;
+
+        let mut connection = self.conn.lock().await;
+        let result = match peer_uuid {
+            Some(peer_uuid) => diesel::delete(QueryDsl::filter(
+                ft::files_tracked,
+                ft::local_id.eq(local_id).and(ft::peer_uuid.eq(peer_uuid)),
+            ))
+            .execute(&mut *connection),
+            None => diesel::delete(QueryDsl::filter(
+                ft::files_tracked,
+                ft::local_id.eq(local_id),
+            ))
+            .execute(&mut *connection),
+        };
+        let result = result.context("Failed to delete sync pairings")?;
+
+        // Conflicts are there for pairings only.
+        Self::delete_conflicts_of(&mut connection, &[local_id], peer_uuid)?;
+
+        Ok(result)
+    }
+
+    async fn update_tracked_file_hash(
+        &self,
+        local_id: i32,
+        peer_uuid: &str,
+        hash_sha1: &str,
+    ) -> anyhow::Result<()> {
+        use schema::files_tracked::dsl as ft;
+
+        let mut connection = self.conn.lock().await;
+        diesel::update(QueryDsl::filter(
+            ft::files_tracked,
+            ft::local_id.eq(local_id).and(ft::peer_uuid.eq(peer_uuid)),
+        ))
+        .set(ft::last_synced_hash.eq(hash_sha1))
+        .execute(&mut *connection)
+        .context("Failed to update hash of the synced file")?;
+
+        Ok(())
+    }
+
+    // File conflicts
+    async fn save_file_conflict(&self, conflict: &FileConflictRow) -> anyhow::Result<()> {
+        use schema::file_conflicts::dsl as fc;
+
+        let mut connection = self.conn.lock().await;
+        diesel::replace_into(fc::file_conflicts)
+            .values(conflict)
+            .execute(&mut *connection)
+            .context("Failed to save file conflict")?;
+
+        Ok(())
+    }
+
+    async fn fetch_file_conflicts(&self) -> anyhow::Result<Vec<FileConflictRow>> {
+        use schema::file_conflicts::dsl as fc;
+
+        let mut connection = self.conn.lock().await;
+        fc::file_conflicts
+            .select(FileConflictRow::as_select())
+            .order((fc::file_id, fc::peer_uuid))
+            .load(&mut *connection)
+            .context("Failed to fetch file conflicts")
+    }
+
+    async fn delete_file_conflicts(
+        &self,
+        file_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        let mut connection = self.conn.lock().await;
+        Self::delete_conflicts_of(&mut connection, &[file_id], peer_uuid)
+    }
+
+    // Sync roots
+    async fn save_sync_root(&self, path: &str) -> Result<i32, SyncRootError> {
+        use schema::sync_roots::dsl as sr;
+
+        // The connection is locked for the whole check, no transaction is needed.
+        let mut connection = self.conn.lock().await;
+        let conn = &mut *connection;
+
+        let roots: Vec<String> = sr::sync_roots
+            .select(sr::path)
+            .load(conn)
+            .context("Failed to fetch sync roots")?;
+
+        for root_path in roots {
+            if root_path == path {
+                return Err(SyncRootError::AlreadyExists(root_path));
+            }
+            if Path::new(path).starts_with(&root_path) || Path::new(&root_path).starts_with(path) {
+                return Err(SyncRootError::Overlapping {
+                    path: path.to_owned(),
+                    root_path,
+                });
+            }
+        }
+
+        let root_id = diesel::insert_into(sr::sync_roots)
+            .values(sr::path.eq(path))
+            .returning(sr::id)
+            .get_result(conn)
+            .context("Failed to save sync root")?;
+
+        Ok(root_id)
+    }
+
+    async fn fetch_sync_roots(&self) -> anyhow::Result<Vec<SyncRootRow>> {
+        use schema::sync_roots::dsl as sr;
+
+        let mut connection = self.conn.lock().await;
+        sr::sync_roots
+            .select(SyncRootRow::as_select())
+            .order(sr::id)
+            .load(&mut *connection)
+            .context("Failed to fetch sync roots")
+    }
+
+    async fn delete_sync_root(&self, root_id: i32) -> anyhow::Result<usize> {
+        use schema::file_access::dsl as fa;
+        use schema::files_local::dsl as fl;
+        use schema::files_tracked::dsl as ft;
+        use schema::group_files_local::dsl as gfl;
+        use schema::root_access::dsl as ra;
+        use schema::roots_tracked::dsl as rt;
+        use schema::sync_roots::dsl as sr;
+
+        let mut connection = self.conn.lock().await;
+        connection.transaction(|conn| {
+            let result = diesel::delete(QueryDsl::filter(sr::sync_roots, sr::id.eq(root_id)))
+                .execute(conn)
+                .context("Failed to delete sync root")?;
+
+            // Foreign keys are not enforced, cascade by hand.
+            let file_ids: Vec<i32> = QueryDsl::filter(fl::files_local, fl::root_id.eq(root_id))
+                .select(fl::id)
+                .load(conn)
+                .context("Failed to fetch files of the sync root")?;
+            let _ = diesel::delete(QueryDsl::filter(
+                gfl::group_files_local,
+                gfl::file_id.eq_any(&file_ids),
+            ))
+            .execute(conn)
+            .context("Failed to delete group membership of files of the sync root")?;
+            let _ = diesel::delete(QueryDsl::filter(
+                fa::file_access,
+                fa::file_id.eq_any(&file_ids),
+            ))
+            .execute(conn)
+            .context("Failed to delete access to files of the sync root")?;
+            let _ = diesel::delete(QueryDsl::filter(
+                ft::files_tracked,
+                ft::local_id.eq_any(&file_ids),
+            ))
+            .execute(conn)
+            .context("Failed to delete sync pairings of files of the sync root")?;
+            Self::delete_conflicts_of(conn, &file_ids, None)?;
+            Self::delete_tombstones_of(conn, &file_ids)?;
+            let _ = diesel::delete(QueryDsl::filter(fl::files_local, fl::root_id.eq(root_id)))
+                .execute(conn)
+                .context("Failed to delete files of the sync root")?;
+            let _ = diesel::delete(QueryDsl::filter(ra::root_access, ra::root_id.eq(root_id)))
+                .execute(conn)
+                .context("Failed to delete access to the sync root")?;
+            let _ = diesel::delete(QueryDsl::filter(rt::roots_tracked, rt::root_id.eq(root_id)))
+                .execute(conn)
+                .context("Failed to delete sync pairings of the sync root")?;
+
+            anyhow::Ok(result)
+        })
+    }
+
+    async fn save_root_access(
+        &self,
+        root_id: i32,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        use schema::root_access::dsl as ra;
+        use schema::sync_roots::dsl as sr;
+
+        let mut connection = self.conn.lock().await;
+        let conn_ref_mut = &mut *connection;
+
+        let root_exists: Option<i32> = QueryDsl::filter(sr::sync_roots, sr::id.eq(root_id))
+            .select(sr::id)
+            .first(conn_ref_mut)
+            .optional()
+            .context("Failed to fetch sync root")?;
+        if root_exists.is_none() {
+            return Err(AccessControlError::NoSuchRoot(root_id));
+        }
+
+        if access.is_none() {
+            diesel::delete(QueryDsl::filter(
+                ra::root_access,
+                ra::root_id.eq(root_id).and(ra::host_uuid.eq(host_uuid)),
+            ))
+            .execute(conn_ref_mut)
+            .context("Failed to delete root access")?;
+        } else {
+            diesel::replace_into(ra::root_access)
+                .values(RootAccessRow {
+                    root_id,
+                    host_uuid: host_uuid.to_owned(),
+                    can_read: access.read,
+                    can_write: access.write,
+                })
+                .execute(conn_ref_mut)
+                .context("Failed to save root access")?;
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_root_access(&self, root_id: i32, host_uuid: &str) -> anyhow::Result<Access> {
+        let mut connection = self.conn.lock().await;
+        Self::find_root_access(&mut connection, |id| id == root_id, host_uuid)
+    }
+
+    // Roots (tracked)
+    async fn save_tracked_root(&self, tracked_root: &RootsTrackedRow) -> Result<(), FileSyncError> {
+        use schema::roots_tracked::dsl as rt;
+
+        let mut connection = self.conn.lock().await;
+        let synced_root_id = connection.transaction(|conn| {
+            let synced_root_id: Option<i32> = QueryDsl::filter(
+                rt::roots_tracked,
+                rt::peer_uuid
+                    .eq(&tracked_root.peer_uuid)
+                    .and(rt::remote_root_id.eq(tracked_root.remote_root_id))
+                    .and(rt::root_id.ne(tracked_root.root_id)),
+            )
+            .select(rt::root_id)
+            .first(conn)
+            .optional()
+            .context("Failed to fetch root sync pairings")?;
+
+            if synced_root_id.is_none() {
+                diesel::replace_into(rt::roots_tracked)
+                    .values(tracked_root)
+                    .execute(conn)
+                    .context("Failed to save root sync pairing")?;
+            }
+
+            anyhow::Ok(synced_root_id)
+        })?;
+
+        match synced_root_id {
+            Some(root_id) => Err(FileSyncError::RootAlreadySynced {
+                peer_uuid: tracked_root.peer_uuid.clone(),
+                remote_root_id: tracked_root.remote_root_id,
+                root_id,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    async fn fetch_tracked_roots(&self) -> anyhow::Result<Vec<RootsTrackedRow>> {
+        use schema::roots_tracked::dsl as rt;
+
+        let mut connection = self.conn.lock().await;
+        rt::roots_tracked
+            .select(RootsTrackedRow::as_select())
+            .order((rt::root_id, rt::peer_uuid))
+            .load(&mut *connection)
+            .context("Failed to fetch root sync pairings")
+    }
+
+    async fn delete_tracked_roots(
+        &self,
+        root_id: i32,
+        peer_uuid: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        use schema::files_local::dsl as fl;
+        use schema::files_tracked::dsl as ft;
+        use schema::roots_tracked::dsl as rt;
+
+        let mut connection = self.conn.lock().await;
+        connection
+            .transaction(|conn| {
+                let root_files: Vec<i32> =
+                    QueryDsl::filter(fl::files_local, fl::root_id.eq(root_id))
+                        .select(fl::id)
+                        .load(conn)?;
+                Self::delete_conflicts_of(conn, &root_files, peer_uuid)?;
+
+                // Files of the root have been paired by the root sync, they go along with it.
+                let result = match peer_uuid {
+                    Some(peer_uuid) => {
+                        diesel::delete(QueryDsl::filter(
+                            ft::files_tracked,
+                            ft::local_id
+                                .eq_any(&root_files)
+                                .and(ft::peer_uuid.eq(peer_uuid)),
+                        ))
+                        .execute(conn)?;
+                        diesel::delete(QueryDsl::filter(
+                            rt::roots_tracked,
+                            rt::root_id.eq(root_id).and(rt::peer_uuid.eq(peer_uuid)),
+                        ))
+                        .execute(conn)?
+                    }
+                    None => {
+                        diesel::delete(QueryDsl::filter(
+                            ft::files_tracked,
+                            ft::local_id.eq_any(&root_files),
+                        ))
+                        .execute(conn)?;
+                        diesel::delete(QueryDsl::filter(rt::roots_tracked, rt::root_id.eq(root_id)))
+                            .execute(conn)?
+                    }
+                };
+
+                anyhow::Ok(result)
+            })
+            .context("Failed to delete root sync pairings")
+    }
+
     // Groups (local)
     async fn save_local_group(&self, group_id: &str) -> Result<usize, SaveLocalGroupError> {
         use schema::groups_local as gl;
@@ -275,20 +1292,38 @@ impl DataSource for SqliteDataSource {
     }
 
     async fn delete_group_by_name(&self, group_name: &str) -> Result<(), DeleteLocalGroupError> {
+        use schema::group_access::dsl as ga;
+        use schema::group_files_local::dsl as gfl;
         use schema::groups_local::dsl as gl;
 
-        let query_res = {
-            let mut conn = self.conn.lock().await;
-            diesel::delete(QueryDsl::filter(gl::groups_local, gl::name.eq(group_name)))
-                .execute(&mut *conn)
-                .map_err(|error| DeleteLocalGroupError::Other(error.into()))
-        }?;
-
-        if query_res == 0 {
-            Err(DeleteLocalGroupError::DoesNotExist)
-        } else {
-            Ok(())
-        }
+        let mut conn = self.conn.lock().await;
+        let conn_ref_mut = &mut *conn;
+
+        let Some(group_id) =
+            Self::find_group_id(conn_ref_mut, group_name).map_err(DeleteLocalGroupError::Other)?
+        else {
+            return Err(DeleteLocalGroupError::DoesNotExist);
+        };
+
+        diesel::delete(QueryDsl::filter(gl::groups_local, gl::id.eq(group_id)))
+            .execute(conn_ref_mut)
+            .map_err(|error| DeleteLocalGroupError::Other(error.into()))?;
+
+        // Foreign keys are not enforced, cascade by hand.
+        diesel::delete(QueryDsl::filter(
+            gfl::group_files_local,
+            gfl::group_id.eq(group_id),
+        ))
+        .execute(conn_ref_mut)
+        .map_err(|error| DeleteLocalGroupError::Other(error.into()))?;
+        diesel::delete(QueryDsl::filter(
+            ga::group_access,
+            ga::group_id.eq(group_id),
+        ))
+        .execute(conn_ref_mut)
+        .map_err(|error| DeleteLocalGroupError::Other(error.into()))?;
+
+        Ok(())
     }
 
     async fn fetch_local_groups(&self) -> anyhow::Result<Vec<GroupInfo>> {
@@ -305,8 +1340,246 @@ impl DataSource for SqliteDataSource {
             .into_iter()
             .map(|row| GroupInfo {
                 local_id: row.id,
+                host_priority: row.host_priority().map(str::to_owned).collect(),
                 name: row.name,
+                conflict_strategy: row.conflict_strategy,
             })
             .collect())
     }
+
+    async fn fetch_file_groups(&self, file_id: i32) -> anyhow::Result<Vec<GroupsLocalRow>> {
+        use schema::group_files_local as gfl;
+        use schema::groups_local as gl;
+
+        let mut connection = self.conn.lock().await;
+        let result = gl::table
+            .inner_join(gfl::table)
+            .filter(gfl::file_id.eq(file_id))
+            .select(GroupsLocalRow::as_select())
+            .load(&mut *connection)
+            .context("Failed to fetch groups of the file")?;
+
+        Ok(result)
+    }
+
+    async fn update_group_strategy(
+        &self,
+        group_name: &str,
+        conflict_strategy: &str,
+        host_priority: &str,
+    ) -> Result<(), AccessControlError> {
+        use schema::groups_local::dsl as gl;
+
+        let mut connection = self.conn.lock().await;
+        let updated = diesel::update(gl::groups_local.filter(gl::name.eq(group_name)))
+            .set((
+                gl::conflict_strategy.eq(conflict_strategy),
+                gl::host_priority.eq(host_priority),
+            ))
+            .execute(&mut *connection)
+            .context("Failed to update conflict strategy of the group")?;
+        if updated == 0 {
+            return Err(AccessControlError::NoSuchGroup(group_name.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    async fn save_group_files(
+        &self,
+        group_name: &str,
+        file_paths: &[String],
+    ) -> Result<(), AccessControlError> {
+        use schema::files_local::dsl as fl;
+        use schema::group_files_local as gfl;
+
+        let mut connection = self.conn.lock().await;
+        let conn_ref_mut = &mut *connection;
+
+        let Some(group_id) = Self::find_group_id(conn_ref_mut, group_name)? else {
+            return Err(AccessControlError::NoSuchGroup(group_name.to_owned()));
+        };
+
+        let file_ids: Vec<i32> = fl::files_local
+            .filter(fl::file_path.eq_any(file_paths))
+            .select(fl::id)
+            .load(conn_ref_mut)
+            .context("Failed to fetch local file ids from db")?;
+
+        let rows: Vec<GroupFilesLocalRow> = file_ids
+            .into_iter()
+            .map(|file_id| GroupFilesLocalRow { file_id, group_id })
+            .collect();
+
+        diesel::insert_or_ignore_into(gfl::table)
+            .values(&rows)
+            .execute(conn_ref_mut)
+            .context("Failed to save group files")?;
+
+        Ok(())
+    }
+
+    // Access control
+    async fn save_group_access(
+        &self,
+        group_name: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        use schema::group_access::dsl as ga;
+
+        let mut connection = self.conn.lock().await;
+        let conn_ref_mut = &mut *connection;
+
+        let Some(group_id) = Self::find_group_id(conn_ref_mut, group_name)? else {
+            return Err(AccessControlError::NoSuchGroup(group_name.to_owned()));
+        };
+
+        if access.is_none() {
+            diesel::delete(QueryDsl::filter(
+                ga::group_access,
+                ga::group_id.eq(group_id).and(ga::host_uuid.eq(host_uuid)),
+            ))
+            .execute(conn_ref_mut)
+            .context("Failed to delete group access")?;
+        } else {
+            diesel::replace_into(ga::group_access)
+                .values(GroupAccessRow {
+                    group_id,
+                    host_uuid: host_uuid.to_owned(),
+                    can_read: access.read,
+                    can_write: access.write,
+                })
+                .execute(conn_ref_mut)
+                .context("Failed to save group access")?;
+        }
+
+        Ok(())
+    }
+
+    async fn save_file_access(
+        &self,
+        file_path: &str,
+        host_uuid: &str,
+        access: Access,
+    ) -> Result<(), AccessControlError> {
+        use schema::file_access::dsl as fa;
+
+        let mut connection = self.conn.lock().await;
+        let conn_ref_mut = &mut *connection;
+
+        let Some(file_id) = Self::find_file_id(conn_ref_mut, file_path)? else {
+            return Err(AccessControlError::NoSuchFile(file_path.to_owned()));
+        };
+
+        if access.is_none() {
+            diesel::delete(QueryDsl::filter(
+                fa::file_access,
+                fa::file_id.eq(file_id).and(fa::host_uuid.eq(host_uuid)),
+            ))
+            .execute(conn_ref_mut)
+            .context("Failed to delete file access")?;
+        } else {
+            diesel::replace_into(fa::file_access)
+                .values(FileAccessRow {
+                    file_id,
+                    host_uuid: host_uuid.to_owned(),
+                    can_read: access.read,
+                    can_write: access.write,
+                })
+                .execute(conn_ref_mut)
+                .context("Failed to save file access")?;
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_file_access(&self, file_path: &str, host_uuid: &str) -> anyhow::Result<Access> {
+        use schema::file_access::dsl as fa;
+        use schema::group_access::dsl as ga;
+        use schema::group_files_local::dsl as gfl;
+
+        let mut connection = self.conn.lock().await;
+        let conn_ref_mut = &mut *connection;
+
+        let through_roots = {
+            let roots: Vec<SyncRootRow> = schema::sync_roots::table
+                .select(SyncRootRow::as_select())
+                .load(conn_ref_mut)
+                .context("Failed to fetch sync roots")?;
+            let containing_roots: Vec<i32> = roots
+                .iter()
+                .filter(|root| Path::new(file_path).starts_with(&root.path))
+                .map(|root| root.id)
+                .collect();
+            Self::find_root_access(conn_ref_mut, |id| containing_roots.contains(&id), host_uuid)?
+        };
+
+        // Files of roots might not be tracked yet.
+        let Some(file_id) = Self::find_file_id(conn_ref_mut, file_path)? else {
+            return Ok(through_roots);
+        };
+
+        let direct: Vec<(bool, bool)> = fa::file_access
+            .filter(fa::file_id.eq(file_id).and(fa::host_uuid.eq(host_uuid)))
+            .select((fa::can_read, fa::can_write))
+            .load(conn_ref_mut)
+            .context("Failed to fetch file access from db")?;
+
+        let through_groups: Vec<(bool, bool)> = ga::group_access
+            .filter(ga::host_uuid.eq(host_uuid))
+            .filter(
+                ga::group_id.eq_any(
+                    gfl::group_files_local
+                        .filter(gfl::file_id.eq(file_id))
+                        .select(gfl::group_id),
+                ),
+            )
+            .select((ga::can_read, ga::can_write))
+            .load(conn_ref_mut)
+            .context("Failed to fetch group access from db")?;
+
+        Ok(direct
+            .into_iter()
+            .chain(through_groups)
+            .map(|(read, write)| Access { read, write })
+            .fold(through_roots, Access::union))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::server::test_utils;
+
+    #[tokio::test]
+    async fn test_host_local_ids_are_not_reused() {
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        ctx.repo
+            .insert_hosts(&[
+                test_utils::host_row("first", true),
+                test_utils::host_row("second", true),
+            ])
+            .await
+            .unwrap();
+        let removed = ctx.repo.fetch_host_by_uuid("second").await.unwrap();
+        assert_eq!(removed.local_id, 2);
+
+        ctx.repo.delete_host_with_uuid("second").await.unwrap();
+        ctx.repo
+            .insert_hosts(&[test_utils::host_row("third", true)])
+            .await
+            .unwrap();
+
+        let inserted = ctx.repo.fetch_host_by_uuid("third").await.unwrap();
+        assert_eq!(inserted.local_id, 3);
+        // Known hosts keep their ids.
+        ctx.repo
+            .insert_hosts(&[test_utils::host_row("first", true)])
+            .await
+            .unwrap();
+        assert_eq!(
+            ctx.repo.fetch_host_by_uuid("first").await.unwrap().local_id,
+            1
+        );
+    }
 }
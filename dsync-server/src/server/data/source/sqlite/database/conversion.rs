@@ -1,6 +1,23 @@
-use dsync_proto::model::server::HostInfo;
+use std::{
+    fs::Metadata,
+    net::SocketAddr,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
 
-use crate::server::{data::source::sqlite::database::models::HostsRow, service::tools};
+use anyhow::Context;
+use dsync_proto::model::server::{DaemonVersion, HostInfo};
+
+use crate::server::{
+    config::defaults,
+    conflict::ConflictStrategy,
+    data::source::sqlite::database::models::{
+        FileTombstoneRow, FilesLocalContentFragmentUpdate, FilesLocalFragmentInsert, FilesLocalRow,
+        GroupsLocalRow, HostsRow, HostsVersionFragmentUpdate, SyncRootRow,
+    },
+    service::tools::{self, net::addr},
+    version_vector::VersionVector,
+};
 
 impl HostsRow {
     pub fn from_host_info(
@@ -8,21 +25,186 @@ impl HostsRow {
         is_remote: bool,
         discovery_time: Option<i64>,
     ) -> Self {
-        let discovery_time = discovery_time.unwrap_or_else(|| tools::time::get_current_timestamp());
+        let discovery_time = discovery_time.unwrap_or_else(tools::time::get_current_timestamp);
+        let version = HostsVersionFragmentUpdate::from_daemon_version(
+            host_info
+                .version
+                .as_ref()
+                .unwrap_or(&DaemonVersion::default()),
+        );
 
         HostsRow {
             uuid: host_info.uuid.clone(),
             name: host_info.name.clone(),
             hostname: host_info.hostname.clone(),
-            is_remote: is_remote,
-            ipv4_addr: host_info.address.clone(),
+            is_remote,
+            address: host_info.address.clone(),
             discovery_time,
+            port: port_or_default(host_info.port),
+            protocol_version: version.protocol_version,
+            build_version: version.build_version,
+            capabilities: version.capabilities,
+            public_key: None,
+            paired: false,
+            local_id: 0,
+            notes: String::new(),
+        }
+    }
+
+    pub fn to_host_info(&self) -> HostInfo {
+        HostInfo {
+            uuid: self.uuid.clone(),
+            name: self.name.clone(),
+            hostname: self.hostname.clone(),
+            address: self.address.clone(),
+            port: self.port as u32,
+            version: self.daemon_version(),
+            paired: self.paired,
+            local_id: self.local_id,
+            notes: self.notes.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// None for hosts which have not reported their version.
+    pub fn daemon_version(&self) -> Option<DaemonVersion> {
+        if self.protocol_version == 0 {
+            return None;
+        }
+
+        Some(DaemonVersion {
+            protocol_version: self.protocol_version as u32,
+            build_version: self.build_version.clone(),
+            capabilities: self
+                .capabilities
+                .split(',')
+                .filter(|capability| !capability.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        })
+    }
+
+    /// Address the daemon of this host listens on.
+    pub fn socket_addr(&self) -> anyhow::Result<SocketAddr> {
+        let port = u16::try_from(self.port)
+            .with_context(|| format!("Invalid port of host {}: {}", &self.uuid, self.port))?;
+        addr::parse_socket_addr(&self.address, port)
+            .with_context(|| format!("Failed to parse host address: {}", &self.address))
+    }
+}
+
+impl HostsVersionFragmentUpdate {
+    pub fn from_daemon_version(version: &DaemonVersion) -> Self {
+        Self {
+            protocol_version: version.protocol_version as i32,
+            build_version: version.build_version.clone(),
+            capabilities: version.capabilities.join(","),
         }
     }
 }
 
+impl FilesLocalRow {
+    /// Whether the file on disk still looks like the one the hash has been computed for.
+    pub fn matches_metadata(&self, metadata: &Metadata) -> bool {
+        !self.gone
+            && self.mtime == tools::file::modification_time(metadata)
+            && self.size == metadata.len() as i64
+            && self.inode == metadata.ino() as i64
+    }
+
+    /// Versions are written by this server only, a broken one is taken for no version at all.
+    pub fn version_vector(&self) -> VersionVector {
+        self.version.parse().unwrap_or_else(|err| {
+            log::warn!(target: "pslog", "Invalid version of {}: {err}", self.file_path);
+            VersionVector::default()
+        })
+    }
+}
+
+impl FileTombstoneRow {
+    /// Same as [`FilesLocalRow::version_vector`].
+    pub fn version_vector(&self) -> VersionVector {
+        self.version.parse().unwrap_or_else(|err| {
+            log::warn!(target: "pslog", "Invalid version of tombstone of {}: {err}", self.file_id);
+            VersionVector::default()
+        })
+    }
+}
+
+impl GroupsLocalRow {
+    /// Strategies are written by this server only, a broken one leaves conflicts to the user.
+    pub fn conflict_strategy(&self) -> ConflictStrategy {
+        self.conflict_strategy.parse().unwrap_or_else(|err| {
+            log::warn!(target: "pslog", "Invalid conflict strategy of group {}: {err}", self.name);
+            ConflictStrategy::Manual
+        })
+    }
+
+    /// Uuids of hosts, the first one wins conflicts of [`ConflictStrategy::HostPriority`].
+    pub fn host_priority(&self) -> impl Iterator<Item = &str> {
+        self.host_priority
+            .split(',')
+            .filter(|host_uuid| !host_uuid.is_empty())
+    }
+}
+
+impl FilesLocalFragmentInsert {
+    pub fn from_metadata(
+        file_path: String,
+        hash_sha1: String,
+        metadata: &Metadata,
+        root_id: Option<i32>,
+        version: &VersionVector,
+    ) -> Self {
+        Self {
+            file_path,
+            hash_sha1,
+            mtime: tools::file::modification_time(metadata),
+            size: metadata.len() as i64,
+            inode: metadata.ino() as i64,
+            root_id,
+            version: version.to_string(),
+        }
+    }
+}
+
+impl SyncRootRow {
+    /// Path of the file relative to the root, none for files outside of the root.
+    pub fn relative_path(&self, file_path: impl AsRef<Path>) -> Option<PathBuf> {
+        file_path
+            .as_ref()
+            .strip_prefix(&self.path)
+            .ok()
+            .filter(|relative_path| !relative_path.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+    }
+}
+
+impl FilesLocalContentFragmentUpdate {
+    pub fn from_metadata(hash_sha1: String, metadata: &Metadata, version: &VersionVector) -> Self {
+        Self {
+            hash_sha1,
+            mtime: tools::file::modification_time(metadata),
+            size: metadata.len() as i64,
+            inode: metadata.ino() as i64,
+            gone: false,
+            version: version.to_string(),
+        }
+    }
+}
+
+fn port_or_default(port: u32) -> i32 {
+    if port == 0 {
+        defaults::SERVER_PORT.into()
+    } else {
+        port as i32
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::net::IpAddr;
+
     use super::*;
 
     #[test]
@@ -32,6 +214,8 @@ mod test {
             name: "name".to_string(),
             hostname: "hostname".to_string(),
             address: "address".to_string(),
+            port: 50052,
+            ..Default::default()
         };
 
         let host_row = HostsRow::from_host_info(&host_info, true, None);
@@ -45,6 +229,8 @@ mod test {
             name: "name".to_string(),
             hostname: "hostname".to_string(),
             address: "address".to_string(),
+            port: 50052,
+            ..Default::default()
         };
 
         let host_row = HostsRow::from_host_info(&host_info, false, None);
@@ -58,6 +244,8 @@ mod test {
             name: "name".to_string(),
             hostname: "hostname".to_string(),
             address: "address".to_string(),
+            port: 50052,
+            ..Default::default()
         };
 
         let discovery_time: i64 = 1234567890;
@@ -71,6 +259,97 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_from_host_info_unknown_port() {
+        let host_info = HostInfo {
+            uuid: "uuid".to_string(),
+            name: "name".to_string(),
+            hostname: "hostname".to_string(),
+            address: "192.168.1.2".to_string(),
+            port: 0,
+            ..Default::default()
+        };
+
+        let host_row = HostsRow::from_host_info(&host_info, true, None);
+        assert_eq!(host_row.port, defaults::SERVER_PORT as i32);
+        assert_eq!(
+            host_row.socket_addr().unwrap(),
+            SocketAddr::new(IpAddr::from([192, 168, 1, 2]), defaults::SERVER_PORT)
+        );
+    }
+
+    #[test]
+    fn test_daemon_version_roundtrip() {
+        let version = DaemonVersion {
+            protocol_version: 1,
+            build_version: "0.1.0".to_string(),
+            capabilities: vec!["hash:sha1".to_string(), "delta-transfer".to_string()],
+        };
+        let host_info = HostInfo {
+            uuid: "uuid".to_string(),
+            name: "name".to_string(),
+            hostname: "hostname".to_string(),
+            address: "192.168.1.2".to_string(),
+            port: 50052,
+            version: Some(version.clone()),
+            ..Default::default()
+        };
+
+        let host_row = HostsRow::from_host_info(&host_info, true, None);
+        assert_eq!(host_row.capabilities, "hash:sha1,delta-transfer");
+        assert_eq!(host_row.daemon_version(), Some(version));
+    }
+
+    #[test]
+    fn test_daemon_version_unknown() {
+        let host_info = HostInfo {
+            uuid: "uuid".to_string(),
+            name: "name".to_string(),
+            hostname: "hostname".to_string(),
+            address: "192.168.1.2".to_string(),
+            port: 50052,
+            ..Default::default()
+        };
+
+        let host_row = HostsRow::from_host_info(&host_info, true, None);
+        assert_eq!(host_row.protocol_version, 0);
+        assert_eq!(host_row.daemon_version(), None);
+    }
+
+    #[test]
+    fn test_socket_addr_ipv6() {
+        let host_info = HostInfo {
+            uuid: "uuid".to_string(),
+            name: "name".to_string(),
+            hostname: "hostname".to_string(),
+            address: "fe80::1".to_string(),
+            port: 50052,
+            ..Default::default()
+        };
+
+        let host_row = HostsRow::from_host_info(&host_info, true, None);
+        assert_eq!(
+            host_row.socket_addr().unwrap().to_string(),
+            "[fe80::1]:50052"
+        );
+    }
+
+    #[test]
+    fn test_sync_root_relative_path() {
+        let root = SyncRootRow {
+            id: 1,
+            path: "/home/user/docs".to_string(),
+        };
+
+        assert_eq!(
+            root.relative_path("/home/user/docs/a/b.txt"),
+            Some(PathBuf::from("a/b.txt"))
+        );
+        assert_eq!(root.relative_path("/home/user/docs"), None);
+        assert_eq!(root.relative_path("/home/user/docs2/b.txt"), None);
+        assert_eq!(root.relative_path("/home/user/b.txt"), None);
+    }
+
     fn assert_common_fields_match_host_info_row(
         host_row: &HostsRow,
         host_info: &HostInfo,
@@ -85,7 +364,8 @@ mod test {
             assert_eq!(host_row.is_remote, is_remote);
         }
 
-        assert_eq!(host_row.ipv4_addr, host_info.address);
+        assert_eq!(host_row.address, host_info.address);
+        assert_eq!(host_row.port, host_info.port as i32);
         if let Some(discovery_time) = discovery_time {
             assert_eq!(host_row.discovery_time, discovery_time);
         } else {
@@ -42,8 +42,109 @@ pub enum DeleteLocalGroupError {
     Other(anyhow::Error),
 }
 
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum AccessControlError {
+    #[error("Group `{0}` does not exist")]
+    NoSuchGroup(String),
+
+    #[error("File `{0}` is not tracked")]
+    NoSuchFile(String),
+
+    #[error("Sync root {0} does not exist")]
+    NoSuchRoot(i32),
+
+    #[error("Other database error: `{0}`")]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum HostAliasError {
+    #[error("Alias `{0}` is already taken")]
+    AlreadyTaken(String),
+
+    #[error("Other database error: `{0}`")]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum FileSyncError {
+    #[error("File {remote_id} of host {peer_uuid} is already synced with local file {local_id}")]
+    AlreadySynced {
+        peer_uuid: String,
+        remote_id: i32,
+        local_id: i32,
+    },
+
+    #[error(
+        "Root {remote_root_id} of host {peer_uuid} is already synced with local root {root_id}"
+    )]
+    RootAlreadySynced {
+        peer_uuid: String,
+        remote_root_id: i32,
+        root_id: i32,
+    },
+
+    #[error("Other database error: `{0}`")]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum SyncRootError {
+    #[error("Directory `{0}` is already a sync root")]
+    AlreadyExists(String),
+
+    #[error("Directory `{path}` overlaps with sync root `{root_path}`")]
+    Overlapping { path: String, root_path: String },
+
+    #[error("Other database error: `{0}`")]
+    Other(#[from] anyhow::Error),
+}
+
 impl From<LocalServerBaseInfoError> for tonic::Status {
     fn from(value: LocalServerBaseInfoError) -> Self {
         tonic::Status::internal(format!("{}", value))
     }
 }
+
+impl From<AccessControlError> for tonic::Status {
+    fn from(value: AccessControlError) -> Self {
+        match value {
+            AccessControlError::NoSuchGroup(_)
+            | AccessControlError::NoSuchFile(_)
+            | AccessControlError::NoSuchRoot(_) => tonic::Status::not_found(value.to_string()),
+            AccessControlError::Other(_) => tonic::Status::internal(value.to_string()),
+        }
+    }
+}
+
+impl From<HostAliasError> for tonic::Status {
+    fn from(value: HostAliasError) -> Self {
+        match value {
+            HostAliasError::AlreadyTaken(_) => tonic::Status::already_exists(value.to_string()),
+            HostAliasError::Other(_) => tonic::Status::internal(value.to_string()),
+        }
+    }
+}
+
+impl From<FileSyncError> for tonic::Status {
+    fn from(value: FileSyncError) -> Self {
+        match value {
+            FileSyncError::AlreadySynced { .. } | FileSyncError::RootAlreadySynced { .. } => {
+                tonic::Status::already_exists(value.to_string())
+            }
+            FileSyncError::Other(_) => tonic::Status::internal(value.to_string()),
+        }
+    }
+}
+
+impl From<SyncRootError> for tonic::Status {
+    fn from(value: SyncRootError) -> Self {
+        match value {
+            SyncRootError::AlreadyExists(_) => tonic::Status::already_exists(value.to_string()),
+            SyncRootError::Overlapping { .. } => {
+                tonic::Status::failed_precondition(value.to_string())
+            }
+            SyncRootError::Other(_) => tonic::Status::internal(value.to_string()),
+        }
+    }
+}
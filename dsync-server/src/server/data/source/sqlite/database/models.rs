@@ -7,6 +7,14 @@ pub struct FilesLocalRow {
     pub id: i32,
     pub file_path: String,
     pub hash_sha1: String,
+    pub mtime: i64,
+    pub size: i64,
+    pub inode: i64,
+    pub gone: bool,
+    /// Root the file has been found in, none for files added by the user.
+    pub root_id: Option<i32>,
+    /// Version vector as a JSON object, see [`Self::version_vector`].
+    pub version: String,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable)]
@@ -15,6 +23,24 @@ pub struct FilesLocalRow {
 pub struct FilesLocalFragmentInsert {
     pub file_path: String,
     pub hash_sha1: String,
+    pub mtime: i64,
+    pub size: i64,
+    pub inode: i64,
+    pub root_id: Option<i32>,
+    pub version: String,
+}
+
+/// Contents of the file as found on disk, which is also no longer gone.
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = super::schema::files_local)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct FilesLocalContentFragmentUpdate {
+    pub hash_sha1: String,
+    pub mtime: i64,
+    pub size: i64,
+    pub inode: i64,
+    pub gone: bool,
+    pub version: String,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable)]
@@ -24,6 +50,34 @@ pub struct FilesTrackedRow {
     pub local_id: i32,
     pub peer_uuid: String,
     pub remote_id: i32,
+    pub last_synced_hash: Option<String>,
+}
+
+/// Local file modified concurrently with the remote file it is synced with.
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::file_conflicts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct FileConflictRow {
+    pub file_id: i32,
+    pub peer_uuid: String,
+    pub remote_hash: String,
+    /// Version vector of the remote file as a JSON object.
+    pub remote_version: String,
+    /// Where the remote version is fetched to.
+    pub copy_path: String,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::file_tombstones)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct FileTombstoneRow {
+    pub file_id: i32,
+    /// Host the file has been deleted on first.
+    pub host_uuid: String,
+    /// Version vector of the deletion as a JSON object.
+    pub version: String,
+    pub deleted_at: i64,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable)]
@@ -32,6 +86,10 @@ pub struct FilesTrackedRow {
 pub struct GroupsLocalRow {
     pub id: i32,
     pub name: String,
+    /// See [`crate::server::conflict::ConflictStrategy`].
+    pub conflict_strategy: String,
+    /// Comma separated uuids of hosts, see [`Self::host_priority`].
+    pub host_priority: String,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable)]
@@ -49,6 +107,73 @@ pub struct GroupFilesLocalRow {
     pub group_id: i32,
 }
 
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::sync_roots)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SyncRootRow {
+    pub id: i32,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::root_access)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RootAccessRow {
+    pub root_id: i32,
+    pub host_uuid: String,
+    pub can_read: bool,
+    pub can_write: bool,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::roots_tracked)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RootsTrackedRow {
+    pub root_id: i32,
+    pub peer_uuid: String,
+    pub remote_root_id: i32,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::group_access)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct GroupAccessRow {
+    pub group_id: i32,
+    pub host_uuid: String,
+    pub can_read: bool,
+    pub can_write: bool,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::file_access)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct FileAccessRow {
+    pub file_id: i32,
+    pub host_uuid: String,
+    pub can_read: bool,
+    pub can_write: bool,
+}
+
+/// Access of a remote host to a local file, granted directly or through groups of the file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Access {
+    pub read: bool,
+    pub write: bool,
+}
+
+impl Access {
+    pub fn is_none(&self) -> bool {
+        !self.read && !self.write
+    }
+
+    pub fn union(self, other: Access) -> Access {
+        Access {
+            read: self.read || other.read,
+            write: self.write || other.write,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Queryable, Selectable, Insertable)]
 #[diesel(table_name = super::schema::hosts)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -57,6 +182,80 @@ pub struct HostsRow {
     pub name: String,
     pub hostname: String,
     pub is_remote: bool,
-    pub ipv4_addr: String,
+    pub address: String,
     pub discovery_time: i64,
+    pub port: i32,
+    pub protocol_version: i32,
+    pub build_version: String,
+    /// Comma separated list.
+    pub capabilities: String,
+    /// Hex encoded ed25519 key of the host's certificate, pinned on the first contact.
+    /// None for hosts which have not been contacted over TLS yet.
+    pub public_key: Option<String>,
+    /// Whether the user has verified the key with `dsync-cli host pair`.
+    pub paired: bool,
+    /// Stable id the user refers to the host by, 0 for the local host.
+    /// Assigned on insert, the value of a row to be inserted is ignored.
+    pub local_id: i32,
+    /// Free form notes of the user.
+    pub notes: String,
+}
+
+/// User editable information on a host. Unset fields are left untouched.
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[diesel(table_name = super::schema::hosts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostsMetadataFragmentUpdate {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::host_aliases)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostAliasRow {
+    pub alias: String,
+    pub host_uuid: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::host_tags)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostTagRow {
+    pub host_uuid: String,
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::host_blocklist)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostBlocklistRow {
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = super::schema::hosts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostsVersionFragmentUpdate {
+    pub protocol_version: i32,
+    pub build_version: String,
+    pub capabilities: String,
+}
+
+#[derive(Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::local_identity)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct LocalIdentityRow {
+    pub id: i32,
+    pub secret_key: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = super::schema::host_addresses)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct HostAddressRow {
+    pub host_uuid: String,
+    pub address: String,
+    pub port: i32,
+    pub last_seen: i64,
 }
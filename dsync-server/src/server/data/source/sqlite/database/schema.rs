@@ -1,18 +1,63 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    file_tombstones (file_id) {
+        file_id -> Integer,
+        host_uuid -> Text,
+        version -> Text,
+        deleted_at -> BigInt,
+    }
+}
+
 diesel::table! {
     files_local (id) {
         id -> Integer,
         file_path -> Text,
         hash_sha1 -> Text,
+        mtime -> BigInt,
+        size -> BigInt,
+        inode -> BigInt,
+        gone -> Bool,
+        root_id -> Nullable<Integer>,
+        version -> Text,
+    }
+}
+
+diesel::table! {
+    file_conflicts (file_id, peer_uuid) {
+        file_id -> Integer,
+        peer_uuid -> Text,
+        remote_hash -> Text,
+        remote_version -> Text,
+        copy_path -> Text,
+        detected_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    file_access (file_id, host_uuid) {
+        file_id -> Integer,
+        host_uuid -> Text,
+        can_read -> Bool,
+        can_write -> Bool,
     }
 }
 
 diesel::table! {
-    files_tracked (local_id) {
+    files_tracked (local_id, peer_uuid) {
         local_id -> Integer,
         peer_uuid -> Text,
         remote_id -> Integer,
+        last_synced_hash -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    group_access (group_id, host_uuid) {
+        group_id -> Integer,
+        host_uuid -> Text,
+        can_read -> Bool,
+        can_write -> Bool,
     }
 }
 
@@ -27,6 +72,37 @@ diesel::table! {
     groups_local (id) {
         id -> Integer,
         name -> Text,
+        conflict_strategy -> Text,
+        host_priority -> Text,
+    }
+}
+
+diesel::table! {
+    host_addresses (host_uuid, address, port) {
+        host_uuid -> Text,
+        address -> Text,
+        port -> Integer,
+        last_seen -> BigInt,
+    }
+}
+
+diesel::table! {
+    host_aliases (alias) {
+        alias -> Text,
+        host_uuid -> Text,
+    }
+}
+
+diesel::table! {
+    host_blocklist (rule) {
+        rule -> Text,
+    }
+}
+
+diesel::table! {
+    host_tags (host_uuid, tag) {
+        host_uuid -> Text,
+        tag -> Text,
     }
 }
 
@@ -36,19 +112,94 @@ diesel::table! {
         name -> Text,
         hostname -> Text,
         is_remote -> Bool,
-        ipv4_addr -> Text,
+        address -> Text,
         discovery_time -> BigInt,
+        port -> Integer,
+        protocol_version -> Integer,
+        build_version -> Text,
+        capabilities -> Text,
+        public_key -> Nullable<Text>,
+        paired -> Bool,
+        local_id -> Integer,
+        notes -> Text,
+    }
+}
+
+diesel::table! {
+    host_local_id_sequence (id) {
+        id -> Integer,
+        next_local_id -> Integer,
+    }
+}
+
+diesel::table! {
+    local_identity (id) {
+        id -> Integer,
+        secret_key -> Binary,
+    }
+}
+
+diesel::table! {
+    root_access (root_id, host_uuid) {
+        root_id -> Integer,
+        host_uuid -> Text,
+        can_read -> Bool,
+        can_write -> Bool,
+    }
+}
+
+diesel::table! {
+    roots_tracked (root_id, peer_uuid) {
+        root_id -> Integer,
+        peer_uuid -> Text,
+        remote_root_id -> Integer,
+    }
+}
+
+diesel::table! {
+    sync_roots (id) {
+        id -> Integer,
+        path -> Text,
     }
 }
 
+diesel::joinable!(file_access -> files_local (file_id));
+diesel::joinable!(file_conflicts -> files_local (file_id));
+diesel::joinable!(file_conflicts -> hosts (peer_uuid));
+diesel::joinable!(file_access -> hosts (host_uuid));
+diesel::joinable!(file_tombstones -> files_local (file_id));
 diesel::joinable!(files_tracked -> files_local (local_id));
+diesel::joinable!(files_tracked -> hosts (peer_uuid));
+diesel::joinable!(group_access -> groups_local (group_id));
+diesel::joinable!(group_access -> hosts (host_uuid));
 diesel::joinable!(group_files_local -> files_local (file_id));
 diesel::joinable!(group_files_local -> groups_local (group_id));
+diesel::joinable!(host_addresses -> hosts (host_uuid));
+diesel::joinable!(host_aliases -> hosts (host_uuid));
+diesel::joinable!(host_tags -> hosts (host_uuid));
+diesel::joinable!(files_local -> sync_roots (root_id));
+diesel::joinable!(root_access -> hosts (host_uuid));
+diesel::joinable!(root_access -> sync_roots (root_id));
+diesel::joinable!(roots_tracked -> hosts (peer_uuid));
+diesel::joinable!(roots_tracked -> sync_roots (root_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    file_access,
+    file_conflicts,
+    file_tombstones,
     files_local,
     files_tracked,
+    group_access,
     group_files_local,
     groups_local,
+    host_addresses,
+    host_aliases,
+    host_blocklist,
+    host_local_id_sequence,
+    host_tags,
     hosts,
+    local_identity,
+    root_access,
+    roots_tracked,
+    sync_roots,
 );
@@ -0,0 +1,103 @@
+//! Long-term keypair of this server, presented to peers in its TLS certificate.
+//!
+//! Only the key is persisted. The self-signed certificate is derived from it on every start,
+//! since peers pin the key, not the certificate.
+
+use anyhow::Context;
+use dsync_shared::conn::tls::{PublicKey, TlsIdentity};
+use ed25519_dalek::{SECRET_KEY_LENGTH, SigningKey, VerifyingKey, pkcs8::EncodePrivateKey};
+use rand_core::OsRng;
+
+use crate::server::data::repo::DataRepository;
+
+pub(crate) struct Identity {
+    signing_key: SigningKey,
+    tls: TlsIdentity,
+}
+
+impl Identity {
+    pub(crate) fn generate() -> anyhow::Result<Self> {
+        Self::from_signing_key(SigningKey::generate(&mut OsRng))
+    }
+
+    /// Load the keypair of this server, generating it on the first run.
+    pub(crate) async fn load_or_generate(repo: &dyn DataRepository) -> anyhow::Result<Self> {
+        if let Some(secret_key) = repo.fetch_local_secret_key().await? {
+            let secret_key: [u8; SECRET_KEY_LENGTH] =
+                secret_key.as_slice().try_into().map_err(|_| {
+                    anyhow::anyhow!("Stored secret key has invalid length: {}", secret_key.len())
+                })?;
+            return Self::from_signing_key(SigningKey::from_bytes(&secret_key));
+        }
+
+        log::info!("Generating keypair of this server");
+        let identity = Self::generate()?;
+        repo.save_local_secret_key(identity.signing_key.as_bytes())
+            .await?;
+        Ok(identity)
+    }
+
+    fn from_signing_key(signing_key: SigningKey) -> anyhow::Result<Self> {
+        let private_key = signing_key
+            .to_pkcs8_der()
+            .context("Failed to encode private key")?;
+        let key_pair = rcgen::KeyPair::try_from(private_key.as_bytes())
+            .context("Failed to load private key")?;
+        let certificate =
+            rcgen::CertificateParams::new(vec![dsync_shared::conn::tls::SERVER_NAME.to_owned()])
+                .context("Invalid certificate parameters")?
+                .self_signed(&key_pair)
+                .context("Failed to create certificate")?;
+
+        Ok(Self {
+            tls: TlsIdentity {
+                certificate: certificate.der().to_vec(),
+                private_key: private_key.as_bytes().to_vec(),
+            },
+            signing_key,
+        })
+    }
+
+    pub(crate) fn public_key(&self) -> PublicKey {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    pub(crate) fn public_key_hex(&self) -> String {
+        hex::encode(self.public_key())
+    }
+
+    pub(crate) fn tls(&self) -> &TlsIdentity {
+        &self.tls
+    }
+}
+
+/// Validate public key received from a peer.
+pub(crate) fn parse_public_key(public_key: &[u8]) -> anyhow::Result<VerifyingKey> {
+    let public_key: &PublicKey = public_key
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid public key length: {}", public_key.len()))?;
+    VerifyingKey::from_bytes(public_key).context("Invalid public key")
+}
+
+/// Decode hex encoded public key, as stored in the hosts table.
+pub(crate) fn decode_public_key(public_key: &str) -> anyhow::Result<PublicKey> {
+    let public_key = hex::decode(public_key).context("Malformed public key")?;
+    Ok(parse_public_key(&public_key)?.to_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use dsync_shared::conn::tls::certificate_public_key;
+
+    use super::*;
+
+    #[test]
+    fn test_certificate_carries_identity_key() {
+        let identity = Identity::generate().unwrap();
+
+        assert_eq!(
+            certificate_public_key(&identity.tls().certificate),
+            Some(identity.public_key())
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Ignore patterns of sync roots.
+//!
+//! Files of sync roots matching the patterns are not indexed, not watched & not transferred by
+//! the root sync. Patterns use the gitignore syntax & come from `.dsyncignore` files anywhere
+//! in the root - like with git, the deeper the file, the higher the precedence - & from
+//! `ignore_patterns` of the server config, which apply to every root with the lowest precedence.
+//! Files tracked one by one (`dsync-cli file add`) are not subject to any patterns.
+//! Files being received (see [`PARTIAL_FILE_SUFFIX`]) are always ignored.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use ignore::{
+    Match,
+    gitignore::{Gitignore, GitignoreBuilder, Glob},
+};
+
+pub(crate) const IGNORE_FILE_NAME: &str = ".dsyncignore";
+/// Files are received under a temporary name ending with the suffix, next to the destination.
+pub(crate) const PARTIAL_FILE_SUFFIX: &str = ".dsync-part";
+
+/// Pattern which has decided about a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IgnoreRule {
+    /// The pattern as written, including the leading `!` of negated patterns.
+    pub pattern: String,
+    /// `.dsyncignore` file the pattern comes from, none for patterns of the server config.
+    pub source: Option<PathBuf>,
+    /// The pattern re-includes the path, which has been ignored by a lower precedence pattern.
+    pub negated: bool,
+}
+
+impl IgnoreRule {
+    fn from_match(matched: Match<&Glob>) -> Option<Self> {
+        let (glob, negated) = match matched {
+            Match::None => return None,
+            Match::Ignore(glob) => (glob, false),
+            Match::Whitelist(glob) => (glob, true),
+        };
+        Some(Self {
+            pattern: glob.original().to_owned(),
+            source: glob.from().map(Path::to_path_buf),
+            negated,
+        })
+    }
+}
+
+/// Patterns applying to paths of a single root. `.dsyncignore` files are read as they are needed
+/// & cached, so a matcher should be used for a single pass over the root only, in order to
+/// notice changes of the files.
+pub(crate) struct RootIgnore {
+    root: PathBuf,
+    global: Gitignore,
+    /// Patterns of the `.dsyncignore` file of every directory looked at so far.
+    dir_patterns: HashMap<PathBuf, Gitignore>,
+}
+
+impl RootIgnore {
+    pub(crate) fn new(root: impl Into<PathBuf>, global_patterns: &[String]) -> Self {
+        let root = root.into();
+
+        let mut builder = GitignoreBuilder::new(&root);
+        let partial_pattern = format!("*{PARTIAL_FILE_SUFFIX}");
+        for pattern in std::iter::once(&partial_pattern).chain(global_patterns) {
+            if let Err(err) = builder.add_line(None, pattern) {
+                log::warn!(target: "pslog", "Skipping invalid ignore pattern {pattern:?}: {err}");
+            }
+        }
+        let global = builder.build().unwrap_or_else(|err| {
+            log::warn!(target: "pslog", "Failed to build ignore patterns of the config: {err}");
+            Gitignore::empty()
+        });
+
+        Self {
+            root,
+            global,
+            dir_patterns: HashMap::new(),
+        }
+    }
+
+    /// Pattern deciding about the path, none when no pattern matches or the path is not
+    /// in the root. Patterns matching any of the parent directories apply to the path as well.
+    pub(crate) fn matched(&mut self, path: &Path, is_dir: bool) -> Option<IgnoreRule> {
+        if !path.starts_with(&self.root) || path == self.root {
+            return None;
+        }
+
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+            let patterns = self
+                .dir_patterns
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| read_ignore_file(dir));
+            if let Some(rule) =
+                IgnoreRule::from_match(patterns.matched_path_or_any_parents(path, is_dir))
+            {
+                return Some(rule);
+            }
+        }
+
+        IgnoreRule::from_match(self.global.matched_path_or_any_parents(path, is_dir))
+    }
+
+    pub(crate) fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        self.matched(path, is_dir).is_some_and(|rule| !rule.negated)
+    }
+}
+
+/// Patterns of the `.dsyncignore` file in the directory, empty when there is none.
+fn read_ignore_file(dir: &Path) -> Gitignore {
+    let ignore_file = dir.join(IGNORE_FILE_NAME);
+    if !ignore_file.is_file() {
+        return Gitignore::empty();
+    }
+
+    let mut builder = GitignoreBuilder::new(dir);
+    // Invalid lines are reported, the valid ones are in use anyway.
+    if let Some(err) = builder.add(&ignore_file) {
+        log::warn!(target: "pslog", "Invalid patterns in {ignore_file:?}: {err}");
+    }
+    builder.build().unwrap_or_else(|err| {
+        log::warn!(target: "pslog", "Failed to read {ignore_file:?}: {err}");
+        Gitignore::empty()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_root_ignore_precedence() {
+        let root = std::env::temp_dir().join(format!("dsync-ignore-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("app/src")).unwrap();
+        std::fs::write(root.join(IGNORE_FILE_NAME), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join("app").join(IGNORE_FILE_NAME), "!keep.log\n").unwrap();
+
+        let mut ignore = RootIgnore::new(&root, &["*.swp".to_owned()]);
+
+        assert!(ignore.is_ignored(&root.join("target"), true));
+        assert!(ignore.is_ignored(&root.join("app/target/debug/app"), false));
+        assert!(ignore.is_ignored(&root.join("app/debug.log"), false));
+        assert!(ignore.is_ignored(&root.join("app/src/.main.rs.swp"), false));
+        assert!(!ignore.is_ignored(&root.join("app/src/main.rs"), false));
+        assert!(!ignore.is_ignored(&root.join(IGNORE_FILE_NAME), false));
+        assert!(ignore.is_ignored(&root.join("app/.main.rs.dsync-part"), false));
+
+        // The deeper file re-includes what the root one ignores.
+        let rule = ignore.matched(&root.join("app/keep.log"), false).unwrap();
+        assert!(rule.negated);
+        assert_eq!(rule.pattern, "!keep.log");
+        assert_eq!(rule.source, Some(root.join("app").join(IGNORE_FILE_NAME)));
+
+        // Patterns of the config have no source file.
+        let rule = ignore.matched(&root.join("a.swp"), false).unwrap();
+        assert_eq!(rule.source, None);
+
+        // Paths outside of the root are none of its business.
+        assert_eq!(ignore.matched(Path::new("/elsewhere/a.log"), false), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
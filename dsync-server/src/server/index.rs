@@ -0,0 +1,495 @@
+//! Keeping `files_local` in line with the files on disk.
+//!
+//! The watcher catches changes as they happen, the rescan catches whatever it has missed
+//! (changes made while the server was down, lost events, directories which were not there
+//! to be watched). Files are compared by size, modification time & inode first, only those
+//! which differ are rehashed. Modifications bump the counter of this host in the version
+//! vector of the file (see `version_vector`), files received from peers take the version of
+//! the sender instead.
+//!
+//! Files which disappear leave a tombstone behind: the deletion counts as a modification &
+//! takes over the version of the file, so that peers learn about it (see `sync`) instead of
+//! sending the file back. The file stays tracked until it shows up again - which counts as
+//! a modification as well - or the tombstone expires after `tombstone_retention`.
+//!
+//! Files of sync roots are indexed as they show up - by the rescan, which walks every root,
+//! & by the watcher, which watches every directory of the roots. Ignored paths (see
+//! `ignore_rules`) are skipped & files which have become ignored are not tracked anymore.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::time::MissedTickBehavior;
+
+use crate::server::{
+    context::ServerContext,
+    data::source::sqlite::database::models::{
+        FileTombstoneRow, FilesLocalContentFragmentUpdate, FilesLocalFragmentInsert, FilesLocalRow,
+        SyncRootRow,
+    },
+    ignore_rules::RootIgnore,
+    service::tools::{self, file::DirTree},
+    version_vector::VersionVector,
+    watcher::FileEvent,
+};
+
+/// State of a tracked file after comparing it with the disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LocalFileState {
+    Unchanged,
+    Modified {
+        hash_sha1: String,
+        version: VersionVector,
+    },
+    /// Not on disk anymore, the deletion has just been noticed & its tombstone recorded.
+    Removed,
+    /// Not on disk, as already known.
+    Gone,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RescanSummary {
+    pub scanned: u32,
+    pub added: u32,
+    pub modified: u32,
+    pub gone: u32,
+}
+
+/// Compare the tracked file with the disk & store whatever has changed.
+/// Changes are published as file events.
+pub(crate) async fn refresh_local_file(
+    ctx: &ServerContext,
+    file: &FilesLocalRow,
+) -> anyhow::Result<LocalFileState> {
+    let metadata = match tokio::fs::metadata(&file.file_path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => anyhow::bail!("Not a regular file anymore"),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            if file.gone {
+                return Ok(LocalFileState::Gone);
+            }
+
+            let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+            ctx.repo
+                .mark_local_file_gone(&FileTombstoneRow {
+                    file_id: file.id,
+                    version: file.version_vector().bumped(&local_uuid).to_string(),
+                    host_uuid: local_uuid,
+                    deleted_at: tools::time::get_current_timestamp(),
+                })
+                .await?;
+            log::info!(target: "pslog", "Tracked file {} has been removed", file.file_path);
+            ctx.watcher.publish(FileEvent::Removed {
+                file_id: file.id,
+                file_path: file.file_path.clone(),
+            });
+            return Ok(LocalFileState::Removed);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    if file.matches_metadata(&metadata) {
+        return Ok(LocalFileState::Unchanged);
+    }
+
+    let hash_sha1 = tools::file::compute_sha1_hash_from_file_async(&file.file_path, None).await?;
+    // Modified (or brought back) on this host, whoever else has modified the file before.
+    let version = if hash_sha1 == file.hash_sha1 && !file.gone {
+        file.version_vector()
+    } else {
+        let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+        file.version_vector().bumped(&local_uuid)
+    };
+    ctx.repo
+        .update_local_file_content(
+            file.id,
+            &FilesLocalContentFragmentUpdate::from_metadata(hash_sha1.clone(), &metadata, &version),
+        )
+        .await?;
+
+    if hash_sha1 == file.hash_sha1 && !file.gone {
+        return Ok(LocalFileState::Unchanged);
+    }
+
+    log::info!(target: "pslog", "Tracked file {} has changed", file.file_path);
+    ctx.watcher.publish(FileEvent::Modified {
+        file_id: file.id,
+        file_path: file.file_path.clone(),
+        hash_sha1: hash_sha1.clone(),
+    });
+    Ok(LocalFileState::Modified { hash_sha1, version })
+}
+
+/// Move a file received from a peer to its destination & record its contents along with the
+/// version of the sender, so that the change is not mistaken for a modification made on this
+/// host. Untracked files landing in a sync root are tracked right away.
+pub(crate) async fn store_received_file(
+    ctx: &ServerContext,
+    part_path: &Path,
+    file_path: &str,
+    hash_sha1: String,
+    version: VersionVector,
+) -> anyhow::Result<()> {
+    // Renaming keeps the metadata, the file on disk looks just like the stored one.
+    let metadata = tokio::fs::metadata(part_path).await?;
+    let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+    let local_files = ctx.repo.fetch_local_files().await?;
+
+    if let Some(file) = local_files.iter().find(|file| file.file_path == file_path) {
+        // Sent by hand, the file has been modified on this host.
+        let version = if version.is_empty() {
+            file.version_vector().bumped(&local_uuid)
+        } else {
+            file.version_vector().merged(&version)
+        };
+        ctx.repo
+            .update_local_file_content(
+                file.id,
+                &FilesLocalContentFragmentUpdate::from_metadata(
+                    hash_sha1.clone(),
+                    &metadata,
+                    &version,
+                ),
+            )
+            .await?;
+        tokio::fs::rename(part_path, file_path).await?;
+
+        if hash_sha1 != file.hash_sha1 || file.gone {
+            log::info!(target: "pslog", "Tracked file {file_path} has been received");
+            ctx.watcher.publish(FileEvent::Modified {
+                file_id: file.id,
+                file_path: file_path.to_owned(),
+                hash_sha1,
+            });
+        }
+        return Ok(());
+    }
+
+    let roots = ctx.repo.fetch_sync_roots().await?;
+    let Some(root) = find_root(&roots, Path::new(file_path)).filter(|root| {
+        !RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns)
+            .is_ignored(Path::new(file_path), false)
+    }) else {
+        tokio::fs::rename(part_path, file_path).await?;
+        return Ok(());
+    };
+
+    let version = if version.is_empty() {
+        VersionVector::initial(&local_uuid)
+    } else {
+        version
+    };
+    ctx.repo
+        .save_local_files(&[FilesLocalFragmentInsert::from_metadata(
+            file_path.to_owned(),
+            hash_sha1,
+            &metadata,
+            Some(root.id),
+            &version,
+        )])
+        .await?;
+    tokio::fs::rename(part_path, file_path).await?;
+
+    if let Some(file) = ctx
+        .repo
+        .fetch_local_files()
+        .await?
+        .into_iter()
+        .find(|file| file.file_path == file_path)
+    {
+        log::info!(target: "pslog", "File {file_path} has been received to sync root {}", root.id);
+        ctx.watcher.publish(FileEvent::Added {
+            file_id: file.id,
+            file_path: file.file_path,
+        });
+    }
+
+    Ok(())
+}
+
+/// Delete the file, which has been deleted by a peer after all modifications seen by this host.
+/// The tombstone is recorded before the file is removed, so that the removal is not taken for
+/// a deletion made on this host.
+pub(crate) async fn apply_tombstone(
+    ctx: &ServerContext,
+    file: &FilesLocalRow,
+    tombstone: &FileTombstoneRow,
+) -> anyhow::Result<()> {
+    ctx.repo.mark_local_file_gone(tombstone).await?;
+    match tokio::fs::remove_file(&file.file_path).await {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    log::info!(
+        target: "pslog",
+        "Tracked file {} has been deleted by host {}",
+        file.file_path,
+        tombstone.host_uuid
+    );
+    ctx.watcher.publish(FileEvent::Removed {
+        file_id: file.id,
+        file_path: file.file_path.clone(),
+    });
+    Ok(())
+}
+
+/// Stop tracking deleted files whose tombstones are older than `tombstone_retention`.
+/// Returns how many files have been forgotten.
+pub(crate) async fn collect_tombstones(ctx: &ServerContext) -> anyhow::Result<u32> {
+    let retention = i64::try_from(ctx.cfg.tombstone_retention.as_secs()).unwrap_or(i64::MAX);
+    let expired_before = tools::time::get_current_timestamp().saturating_sub(retention);
+
+    let tombstones = ctx.repo.fetch_file_tombstones().await?;
+    let local_files = ctx.repo.fetch_local_files().await?;
+    let mut collected = 0;
+
+    for tombstone in tombstones
+        .iter()
+        .filter(|tombstone| tombstone.deleted_at < expired_before)
+    {
+        let Some(file) = local_files
+            .iter()
+            .find(|file| file.id == tombstone.file_id && file.gone)
+        else {
+            continue;
+        };
+        // Pairings, grants & the tombstone go along with it.
+        ctx.repo.delete_local_file(&file.file_path).await?;
+        log::info!(target: "pslog", "Tombstone of {} has expired", file.file_path);
+        collected += 1;
+    }
+
+    Ok(collected)
+}
+
+/// Start tracking files of the sync root. Returns how many files have been added.
+async fn add_root_files(
+    ctx: &ServerContext,
+    root_id: i32,
+    paths: Vec<PathBuf>,
+) -> anyhow::Result<u32> {
+    let version = VersionVector::initial(&ctx.repo.fetch_local_server_info().await?.uuid);
+    let mut fragments = Vec::with_capacity(paths.len());
+    for path in paths {
+        let Some(file_path) = path.to_str() else {
+            log::debug!(target: "pslog", "Skipping non UTF-8 path {path:?}");
+            continue;
+        };
+        let metadata = match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => metadata,
+            // Gone already or not a file.
+            _ => continue,
+        };
+        let hash_sha1 = match tools::file::compute_sha1_hash_from_file_async(&path, None).await {
+            Ok(hash_sha1) => hash_sha1,
+            Err(err) => {
+                log::warn!(target: "pslog", "Failed to hash {file_path}: {err}");
+                continue;
+            }
+        };
+        fragments.push(FilesLocalFragmentInsert::from_metadata(
+            file_path.to_owned(),
+            hash_sha1,
+            &metadata,
+            Some(root_id),
+            &version,
+        ));
+    }
+
+    if fragments.is_empty() {
+        return Ok(0);
+    }
+
+    ctx.repo.save_local_files(&fragments).await?;
+
+    let added_paths: HashSet<&str> = fragments
+        .iter()
+        .map(|fragment| fragment.file_path.as_str())
+        .collect();
+    let mut added = 0;
+    for file in ctx.repo.fetch_local_files().await? {
+        if !added_paths.contains(file.file_path.as_str()) {
+            continue;
+        }
+        log::info!(target: "pslog", "File {} has been added to sync root {root_id}", file.file_path);
+        ctx.watcher.publish(FileEvent::Added {
+            file_id: file.id,
+            file_path: file.file_path,
+        });
+        added += 1;
+    }
+
+    Ok(added)
+}
+
+/// Walk the directory of the sync root, skipping ignored paths.
+pub(crate) async fn walk_root(
+    ctx: &ServerContext,
+    root: &SyncRootRow,
+    dir: PathBuf,
+) -> anyhow::Result<DirTree> {
+    let mut ignore = RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns);
+    let tree = tokio::task::spawn_blocking(move || {
+        tools::file::walk_dir(dir, |path, is_dir| ignore.is_ignored(path, is_dir))
+    })
+    .await??;
+    Ok(tree)
+}
+
+/// Start tracking all files of the sync root, which are not tracked yet, & stop tracking those
+/// which have become ignored. Returns how many files have been added.
+pub(crate) async fn index_root(ctx: &ServerContext, root: &SyncRootRow) -> anyhow::Result<u32> {
+    let tree = walk_root(ctx, root, PathBuf::from(&root.path)).await?;
+    untrack_ignored(ctx, root).await?;
+    add_untracked_files(ctx, root, tree.files).await
+}
+
+/// Start tracking a path which has shown up in the sync root - a file, or a whole directory.
+/// Returns how many files have been added.
+pub(crate) async fn index_root_path(
+    ctx: &ServerContext,
+    root: &SyncRootRow,
+    path: PathBuf,
+) -> anyhow::Result<u32> {
+    let is_dir = match tokio::fs::symlink_metadata(&path).await {
+        Ok(metadata) if metadata.is_dir() || metadata.is_file() => metadata.is_dir(),
+        // Gone already, a symlink or something special.
+        _ => return Ok(0),
+    };
+    if RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns).is_ignored(&path, is_dir) {
+        return Ok(0);
+    }
+
+    let paths = if is_dir {
+        walk_root(ctx, root, path).await?.files
+    } else {
+        vec![path]
+    };
+    add_untracked_files(ctx, root, paths).await
+}
+
+/// Start tracking those of the paths, which are not tracked yet.
+async fn add_untracked_files(
+    ctx: &ServerContext,
+    root: &SyncRootRow,
+    paths: Vec<PathBuf>,
+) -> anyhow::Result<u32> {
+    let tracked: HashSet<PathBuf> = ctx
+        .repo
+        .fetch_local_files()
+        .await?
+        .into_iter()
+        .map(|file| PathBuf::from(file.file_path))
+        .collect();
+
+    let untracked = paths
+        .into_iter()
+        .filter(|path| !tracked.contains(path) && root.relative_path(path).is_some())
+        .collect();
+    add_root_files(ctx, root.id, untracked).await
+}
+
+/// Stop tracking files of the sync root, which match its ignore patterns.
+async fn untrack_ignored(ctx: &ServerContext, root: &SyncRootRow) -> anyhow::Result<()> {
+    let mut ignore = RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns);
+
+    for file in ctx.repo.fetch_local_files().await? {
+        if file.root_id != Some(root.id) || !ignore.is_ignored(Path::new(&file.file_path), false) {
+            continue;
+        }
+        ctx.repo.delete_local_file(&file.file_path).await?;
+        log::info!(target: "pslog", "File {} is ignored & not tracked anymore", file.file_path);
+    }
+
+    Ok(())
+}
+
+/// The sync root the path belongs to, if any.
+pub(crate) fn find_root<'a>(roots: &'a [SyncRootRow], path: &Path) -> Option<&'a SyncRootRow> {
+    roots.iter().find(|root| root.relative_path(path).is_some())
+}
+
+/// Compare all tracked files with the disk & index files new to sync roots.
+pub(crate) async fn rescan(ctx: &ServerContext) -> anyhow::Result<RescanSummary> {
+    let mut summary = RescanSummary::default();
+
+    for root in ctx.repo.fetch_sync_roots().await? {
+        match index_root(ctx, &root).await {
+            Ok(added) => summary.added += added,
+            Err(err) => {
+                log::warn!(target: "pslog", "Failed to index sync root {}: {err}", root.path)
+            }
+        }
+    }
+
+    let local_files = ctx.repo.fetch_local_files().await?;
+
+    for file in &local_files {
+        summary.scanned += 1;
+        match refresh_local_file(ctx, file).await {
+            Ok(LocalFileState::Unchanged | LocalFileState::Gone) => {}
+            Ok(LocalFileState::Modified { .. }) => summary.modified += 1,
+            Ok(LocalFileState::Removed) => summary.gone += 1,
+            Err(err) => {
+                log::warn!(target: "pslog", "Failed to rescan {}: {err}", file.file_path);
+            }
+        }
+    }
+
+    // Directories might have shown up since they were last watched.
+    ctx.watcher.refresh();
+
+    Ok(summary)
+}
+
+/// Rescan tracked files on start & every `rescan_interval`, collecting expired tombstones
+/// along the way.
+pub(crate) fn spawn_rescan(ctx: Arc<ServerContext>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ctx.cfg.rescan_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match rescan(&ctx).await {
+                Ok(summary) => {
+                    log::debug!(target: "pslog", "Rescan of tracked files done: {summary:?}")
+                }
+                Err(err) => log::warn!(target: "pslog", "Rescan of tracked files failed: {err}"),
+            }
+            if let Err(err) = collect_tombstones(&ctx).await {
+                log::warn!(target: "pslog", "Failed to collect expired tombstones: {err}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::test_utils;
+
+    #[tokio::test]
+    async fn test_rescan_reports_deletions_once() {
+        let dir = test_utils::temp_dir();
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let file_path = dir.join("file.txt");
+        test_utils::track_file(&ctx, &file_path, "contents", &VersionVector::default()).await;
+
+        let mut events = ctx.watcher.subscribe();
+
+        std::fs::remove_file(&file_path).unwrap();
+        assert_eq!(rescan(&ctx).await.unwrap().gone, 1);
+        assert!(matches!(events.try_recv(), Ok(FileEvent::Removed { .. })));
+        assert_eq!(rescan(&ctx).await.unwrap().gone, 0);
+        assert!(events.try_recv().is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
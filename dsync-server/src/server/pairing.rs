@@ -0,0 +1,316 @@
+//! Pairings in progress & derivation of verification codes.
+//!
+//! Pairing is a commit & reveal exchange of nonces next to the public keys. The initiator
+//! commits to its nonce before seeing the responder's one, so that none of the parties
+//! (or anyone in between) can steer the resulting code. Both users compare the codes
+//! shown on their hosts; keys are trusted only after they match.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use dsync_proto::model::server::HostInfo;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+pub(crate) const NONCE_LENGTH: usize = 32;
+
+/// How long a pairing might wait for the user's confirmation.
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Upper bound on pairings in progress, so that peers can not exhaust our memory.
+const MAX_PENDING_PAIRINGS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum PairingError {
+    #[error("no pairing with host {uuid} is in progress")]
+    NotInProgress { uuid: String },
+    #[error("revealed nonce does not match the commitment")]
+    CommitmentMismatch,
+    #[error("revealing peer does not hold the key being paired")]
+    KeyMismatch,
+    #[error("too many pairings in progress")]
+    TooManyPending,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum PairingStage {
+    /// Responder side, initiator has not revealed its nonce yet.
+    AwaitingReveal {
+        commitment: Vec<u8>,
+        nonce: [u8; NONCE_LENGTH],
+    },
+    /// Keys are exchanged, the user has to compare the codes.
+    AwaitingConfirmation { code: String },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PendingPairing {
+    pub host_info: HostInfo,
+    pub public_key: Vec<u8>,
+    /// Whether the peer started the pairing.
+    pub incoming: bool,
+    pub stage: PairingStage,
+    started: Instant,
+}
+
+/// In-memory registry of pairings in progress, keyed by peer uuid.
+/// Nothing is persisted - pairings interrupted by restart have to be started again.
+pub(crate) struct PairingRegistry {
+    pending: Mutex<HashMap<String, PendingPairing>>,
+}
+
+impl PairingRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Responder side: peer started pairing. Returns our nonce.
+    pub(crate) fn start_incoming(
+        &self,
+        host_info: HostInfo,
+        public_key: Vec<u8>,
+        commitment: Vec<u8>,
+    ) -> Result<[u8; NONCE_LENGTH], PairingError> {
+        let nonce = random_nonce();
+        self.insert(PendingPairing {
+            host_info,
+            public_key,
+            incoming: true,
+            stage: PairingStage::AwaitingReveal { commitment, nonce },
+            started: Instant::now(),
+        })?;
+        Ok(nonce)
+    }
+
+    /// Responder side: peer revealed its nonce. Returns the verification code.
+    ///
+    /// The nonce has to come from the peer which started the pairing, others can not
+    /// abort it.
+    pub(crate) fn reveal(
+        &self,
+        uuid: &str,
+        peer_public_key: &[u8],
+        peer_nonce: &[u8],
+        local_public_key: &[u8],
+    ) -> Result<String, PairingError> {
+        let mut pending = self.lock();
+        let not_in_progress = || PairingError::NotInProgress {
+            uuid: uuid.to_owned(),
+        };
+
+        let pairing = pending.get_mut(uuid).ok_or_else(not_in_progress)?;
+        let PairingStage::AwaitingReveal { commitment, nonce } = &pairing.stage else {
+            return Err(not_in_progress());
+        };
+
+        if pairing.public_key != peer_public_key {
+            return Err(PairingError::KeyMismatch);
+        }
+
+        if commit(&pairing.public_key, peer_nonce) != *commitment {
+            pending.remove(uuid);
+            return Err(PairingError::CommitmentMismatch);
+        }
+
+        let code = verification_code(&pairing.public_key, local_public_key, peer_nonce, nonce);
+        pairing.stage = PairingStage::AwaitingConfirmation { code: code.clone() };
+        Ok(code)
+    }
+
+    /// Initiator side: keys are exchanged.
+    pub(crate) fn insert_outgoing(
+        &self,
+        host_info: HostInfo,
+        public_key: Vec<u8>,
+        code: String,
+    ) -> Result<(), PairingError> {
+        self.insert(PendingPairing {
+            host_info,
+            public_key,
+            incoming: false,
+            stage: PairingStage::AwaitingConfirmation { code },
+            started: Instant::now(),
+        })
+    }
+
+    /// Pairings started by peers, which wait for the user to compare codes.
+    pub(crate) fn incoming_awaiting_confirmation(&self) -> Vec<PendingPairing> {
+        self.lock()
+            .values()
+            .filter(|pairing| {
+                pairing.incoming
+                    && matches!(pairing.stage, PairingStage::AwaitingConfirmation { .. })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Remove the pairing, once the user decided on it.
+    pub(crate) fn take_awaiting_confirmation(
+        &self,
+        uuid: &str,
+    ) -> Result<PendingPairing, PairingError> {
+        let mut pending = self.lock();
+        match pending.get(uuid) {
+            Some(PendingPairing {
+                stage: PairingStage::AwaitingConfirmation { .. },
+                ..
+            }) => Ok(pending.remove(uuid).expect("Presence checked above")),
+            _ => Err(PairingError::NotInProgress {
+                uuid: uuid.to_owned(),
+            }),
+        }
+    }
+
+    fn insert(&self, pairing: PendingPairing) -> Result<(), PairingError> {
+        let mut pending = self.lock();
+        let uuid = pairing.host_info.uuid.clone();
+        if !pending.contains_key(&uuid) && pending.len() >= MAX_PENDING_PAIRINGS {
+            return Err(PairingError::TooManyPending);
+        }
+        // Restarted pairing replaces the previous attempt.
+        pending.insert(uuid, pairing);
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, PendingPairing>> {
+        let mut pending = self.pending.lock().expect("Pairing registry lock poisoned");
+        pending.retain(|_, pairing| pairing.started.elapsed() < PAIRING_TIMEOUT);
+        pending
+    }
+}
+
+pub(crate) fn random_nonce() -> [u8; NONCE_LENGTH] {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// Commitment of the initiator to its nonce.
+pub(crate) fn commit(public_key: &[u8], nonce: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain_update(b"dsync-pair-commit")
+        .chain_update(public_key)
+        .chain_update(nonce)
+        .finalize()
+        .to_vec()
+}
+
+/// Six digit code, the same on both sides as long as they have seen the same keys & nonces.
+pub(crate) fn verification_code(
+    initiator_key: &[u8],
+    responder_key: &[u8],
+    initiator_nonce: &[u8],
+    responder_nonce: &[u8],
+) -> String {
+    let digest = Sha256::new()
+        .chain_update(b"dsync-pair-code")
+        .chain_update(initiator_key)
+        .chain_update(responder_key)
+        .chain_update(initiator_nonce)
+        .chain_update(responder_nonce)
+        .finalize();
+    let value =
+        u32::from_be_bytes(digest[..4].try_into().expect("Digest is long enough")) % 1_000_000;
+    format!("{:03} {:03}", value / 1000, value % 1000)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn host_info(uuid: &str) -> HostInfo {
+        HostInfo {
+            uuid: uuid.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_both_sides_derive_the_same_code() {
+        let registry = PairingRegistry::new();
+        let (initiator_key, responder_key) = (vec![1u8; 32], vec![2u8; 32]);
+        let initiator_nonce = random_nonce();
+
+        let responder_nonce = registry
+            .start_incoming(
+                host_info("initiator"),
+                initiator_key.clone(),
+                commit(&initiator_key, &initiator_nonce),
+            )
+            .unwrap();
+        let responder_code = registry
+            .reveal(
+                "initiator",
+                &initiator_key,
+                &initiator_nonce,
+                &responder_key,
+            )
+            .unwrap();
+        let initiator_code = verification_code(
+            &initiator_key,
+            &responder_key,
+            &initiator_nonce,
+            &responder_nonce,
+        );
+
+        assert_eq!(responder_code, initiator_code);
+        assert_eq!(responder_code.len(), 7);
+        assert_eq!(registry.incoming_awaiting_confirmation().len(), 1);
+        assert!(registry.take_awaiting_confirmation("initiator").is_ok());
+        assert!(registry.take_awaiting_confirmation("initiator").is_err());
+    }
+
+    #[test]
+    fn test_reveal_not_matching_commitment_aborts_pairing() {
+        let registry = PairingRegistry::new();
+        let initiator_key = vec![1u8; 32];
+
+        registry
+            .start_incoming(
+                host_info("initiator"),
+                initiator_key.clone(),
+                commit(&initiator_key, &random_nonce()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            registry.reveal("initiator", &initiator_key, &random_nonce(), &[2u8; 32]),
+            Err(PairingError::CommitmentMismatch)
+        );
+        assert!(registry.take_awaiting_confirmation("initiator").is_err());
+    }
+
+    #[test]
+    fn test_reveal_by_other_peer_keeps_pairing() {
+        let registry = PairingRegistry::new();
+        let (initiator_key, responder_key) = (vec![1u8; 32], vec![2u8; 32]);
+        let initiator_nonce = random_nonce();
+
+        registry
+            .start_incoming(
+                host_info("initiator"),
+                initiator_key.clone(),
+                commit(&initiator_key, &initiator_nonce),
+            )
+            .unwrap();
+
+        assert_eq!(
+            registry.reveal("initiator", &[3u8; 32], &random_nonce(), &responder_key),
+            Err(PairingError::KeyMismatch)
+        );
+        registry
+            .reveal(
+                "initiator",
+                &initiator_key,
+                &initiator_nonce,
+                &responder_key,
+            )
+            .unwrap();
+    }
+}
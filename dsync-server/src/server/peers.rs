@@ -0,0 +1,155 @@
+//! Liveness of known peers, as observed by the heartbeat.
+
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+
+use dsync_proto::model::server::HostInfo;
+use tokio::sync::broadcast;
+
+/// How many events might be buffered for a slow subscriber before it starts losing them.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PeerStatus {
+    pub online: bool,
+    /// Unix timestamp (seconds) of the last successful heartbeat.
+    pub last_seen: Option<i64>,
+    /// Round-trip time of the last heartbeat, none when it failed.
+    pub latency: Option<Duration>,
+}
+
+/// Changes of peer liveness. Work waiting for a peer should subscribe & start on `Online`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PeerEvent {
+    Online { uuid: String },
+    Offline { uuid: String },
+}
+
+/// In-memory registry of peer statuses. Nothing is persisted - after restart all peers
+/// are considered offline until the first heartbeat.
+pub(crate) struct PeerRegistry {
+    statuses: RwLock<HashMap<String, PeerStatus>>,
+    events: broadcast::Sender<PeerEvent>,
+}
+
+impl PeerRegistry {
+    pub(crate) fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            statuses: RwLock::new(HashMap::new()),
+            events,
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<PeerEvent> {
+        self.events.subscribe()
+    }
+
+    pub(crate) fn status(&self, uuid: &str) -> PeerStatus {
+        self.statuses
+            .read()
+            .expect("Peer registry lock poisoned")
+            .get(uuid)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Peer answered the heartbeat.
+    pub(crate) fn record_success(&self, uuid: &str, latency: Duration, seen_time: i64) {
+        let was_online = {
+            let mut statuses = self.statuses.write().expect("Peer registry lock poisoned");
+            let status = statuses.entry(uuid.to_owned()).or_default();
+            let was_online = status.online;
+            *status = PeerStatus {
+                online: true,
+                last_seen: Some(seen_time),
+                latency: Some(latency),
+            };
+            was_online
+        };
+
+        if !was_online {
+            log::info!(target: "pslog", "Peer {uuid} is online");
+            // No subscribers is fine.
+            let _ = self.events.send(PeerEvent::Online {
+                uuid: uuid.to_owned(),
+            });
+        }
+    }
+
+    /// Peer could not be reached.
+    pub(crate) fn record_failure(&self, uuid: &str) {
+        let was_online = {
+            let mut statuses = self.statuses.write().expect("Peer registry lock poisoned");
+            let status = statuses.entry(uuid.to_owned()).or_default();
+            status.latency = None;
+            std::mem::replace(&mut status.online, false)
+        };
+
+        if was_online {
+            log::info!(target: "pslog", "Peer {uuid} is offline");
+            let _ = self.events.send(PeerEvent::Offline {
+                uuid: uuid.to_owned(),
+            });
+        }
+    }
+
+    pub(crate) fn forget(&self, uuid: &str) {
+        self.statuses
+            .write()
+            .expect("Peer registry lock poisoned")
+            .remove(uuid);
+    }
+
+    /// Fill liveness fields of the host info.
+    pub(crate) fn fill_status(&self, host_info: &mut HostInfo) {
+        let status = self.status(&host_info.uuid);
+        host_info.online = status.online;
+        host_info.last_seen = status.last_seen.unwrap_or_default();
+        host_info.latency_ms = status
+            .latency
+            .map(|latency| latency.as_millis().try_into().unwrap_or(u32::MAX));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_events_emitted_on_state_change_only() {
+        let registry = PeerRegistry::new();
+        let mut events = registry.subscribe();
+
+        registry.record_failure("peer");
+        registry.record_success("peer", Duration::from_millis(3), 10);
+        registry.record_success("peer", Duration::from_millis(4), 20);
+        registry.record_failure("peer");
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            PeerEvent::Online {
+                uuid: "peer".to_owned()
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            PeerEvent::Offline {
+                uuid: "peer".to_owned()
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_last_seen_survives_going_offline() {
+        let registry = PeerRegistry::new();
+
+        registry.record_success("peer", Duration::from_millis(3), 10);
+        registry.record_failure("peer");
+
+        let status = registry.status("peer");
+        assert!(!status.online);
+        assert_eq!(status.last_seen, Some(10));
+        assert_eq!(status.latency, None);
+    }
+}
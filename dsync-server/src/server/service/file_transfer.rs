@@ -5,7 +5,7 @@ pub(crate) mod session_factory;
 pub(crate) mod session_registry;
 
 use std::{
-    net::{Ipv4Addr, SocketAddrV4},
+    collections::HashMap,
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -13,13 +13,17 @@ use std::{
 };
 
 use async_stream::stream;
+use dsync_proto::model::common::{
+    LocalFileDescription, RootFileDescription, SyncRootDescription, Tombstone,
+};
 use dsync_proto::services::file_transfer::{
+    FileDeleteRequest, FileDeleteResponse, FileDescribeRequest, FileDescribeResponse,
+    RootDescribeRequest, RootDescribeResponse, RootListRequest, RootListResponse,
     TransferChunkRequest, TransferChunkResponse, TransferInitRequest, TransferInitResponse,
     TransferSubmitRequest, TransferSubmitResponse,
     file_transfer_service_client::FileTransferServiceClient,
     file_transfer_service_server::FileTransferService,
 };
-use dsync_shared::conn::{ChannelFactory, create_server_uri};
 use tokio::{
     fs::{File, OpenOptions, metadata},
     io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufWriter},
@@ -27,16 +31,25 @@ use tokio::{
 use tokio_stream::StreamExt;
 use tonic::{IntoRequest, transport::Channel};
 
+use crate::server::version_vector::{Causality, VersionVector};
 use crate::server::{
     config::defaults,
     context::ServerContext,
+    data::source::sqlite::database::models::{
+        Access, FileTombstoneRow, FilesLocalRow, HostsRow, SyncRootRow,
+    },
+    ignore_rules::{PARTIAL_FILE_SUFFIX, RootIgnore},
+    index::{self, LocalFileState},
     service::{
         file_transfer::{
             session::{FileTransferSession, SessionId},
             session_factory::FileTransferSessionFactory,
             session_registry::FileTransferSessionRegistry,
         },
-        tools,
+        tools::{
+            self,
+            net::{auth, peer},
+        },
     },
 };
 
@@ -48,6 +61,39 @@ pub struct FileTransferServiceImpl {
 }
 
 impl FileTransferServiceImpl {
+    /// Deleted files are described by their tombstones, so that peers delete them as well.
+    async fn describe_deleted_file(
+        &self,
+        local_file: FilesLocalRow,
+    ) -> Result<tonic::Response<FileDescribeResponse>, tonic::Status> {
+        let tombstone = self
+            .server_ctx
+            .repo
+            .fetch_file_tombstones()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .into_iter()
+            .find(|tombstone| tombstone.file_id == local_file.id)
+            .ok_or_else(|| tonic::Status::not_found(format!("file-gone: {}", local_file.id)))?;
+
+        Ok(tonic::Response::new(FileDescribeResponse {
+            file: Some(LocalFileDescription {
+                local_id: local_file.id.to_string(),
+                file_path: local_file.file_path,
+                hash_sha1: local_file.hash_sha1,
+                synced_with: Vec::new(),
+                gone: true,
+                version: HashMap::from(&tombstone.version_vector()),
+                conflicted: false,
+                mtime: local_file.mtime,
+                tombstone: Some(Tombstone {
+                    host_uuid: tombstone.host_uuid,
+                    deleted_at: tombstone.deleted_at,
+                }),
+            }),
+        }))
+    }
+
     pub fn new(ctx: Arc<ServerContext>) -> Self {
         Self {
             server_ctx: ctx,
@@ -64,6 +110,8 @@ impl FileTransferService for FileTransferServiceImpl {
         request: tonic::Request<TransferSubmitRequest>,
     ) -> Result<tonic::Response<TransferSubmitResponse>, tonic::Status> {
         log::trace!("Received TransferSubmitRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
+
         // Here we received request to transfer a file from this server, to another one pointed
         // by the request.
         // We need to:
@@ -74,46 +122,21 @@ impl FileTransferService for FileTransferServiceImpl {
 
         let request_inner = request.into_inner();
 
-        // Step 1
-        // Verify that the file path is absolute & the file exists.
-
-        let file_path_src: PathBuf = PathBuf::from_str(&request_inner.file_path_src)
-            .expect("Path conversion should never fail");
-
-        if !file_path_src.is_absolute() {
-            return Err(tonic::Status::invalid_argument("src-path-not-absolute"));
+        // The local user might copy whatever they want.
+        if caller.is_remote {
+            ensure_not_ignored(&self.server_ctx, &request_inner.file_path_src).await?;
         }
+        auth::authorize_file_access(
+            &self.server_ctx,
+            &caller,
+            &request_inner.file_path_src,
+            Access {
+                read: true,
+                write: false,
+            },
+        )
+        .await?;
 
-        // TODO: Support not only files, but also directories
-        if !file_path_src.is_file() {
-            return Err(tonic::Status::invalid_argument("src-path-not-a-file"));
-        }
-
-        // Step 2
-        // Extract necessary file information
-
-        let Ok(file_metadata) = file_path_src.metadata() else {
-            return Err(tonic::Status::internal("src-path-failed-metadata-fetch"));
-        };
-
-        let Ok(file_size_bytes) = i64::try_from(file_metadata.len()) else {
-            return Err(tonic::Status::internal("file-size-conversion-fail"));
-        };
-
-        let Ok(file_sha1) =
-            tools::file::compute_sha1_hash_from_file_async(&file_path_src, None).await
-        else {
-            return Err(tonic::Status::internal("file-sh1-comput-fail"));
-        };
-
-        log::debug!(
-            "File info - size: {} bytes, sha1: {}",
-            file_size_bytes,
-            file_sha1
-        );
-
-        // Step 3
-        // Send init message to destination host
         let Ok(host_data) = self
             .server_ctx
             .repo
@@ -122,47 +145,21 @@ impl FileTransferService for FileTransferServiceImpl {
         else {
             return Err(tonic::Status::internal("host-dst-addr-missing"));
         };
+        authorize_transfer_destination(
+            &self.server_ctx,
+            &caller,
+            &host_data,
+            &request_inner.file_path_dst,
+        )
+        .await?;
 
-        let fts_uri = create_server_uri(SocketAddrV4::new(
-            Ipv4Addr::from_str(host_data.ipv4_addr.as_str()).expect("Failed to convert to ipv4"),
-            defaults::SERVER_PORT,
-        ));
-
-        let connection = ChannelFactory::channel_with_timeout(fts_uri, Duration::from_secs(5))
-            .await
-            .map_err(|err| {
-                tonic::Status::failed_precondition(format!("fts-connection-fail: {err}"))
-            })?;
-
-        let mut fts_client = FileTransferServiceClient::new(connection);
-
-        let transfer_init_request = TransferInitRequest {
-            file_path_src: request_inner.file_path_src,
-            file_path_dst: request_inner.file_path_dst,
-            file_sha1,
-            file_size_bytes,
-            chunk_size: 1024 * 8,
-        };
-
-        let result = fts_client
-            .transfer_init(transfer_init_request.clone())
-            .await;
-
-        let transfer_init_response = match result {
-            Ok(response) => response.into_inner(),
-            Err(status) => {
-                // log::error!(format!("FTS at {} rejected transfer request: {}", &request_inner.host_dst_uuid, status));
-                return Err(tonic::Status::failed_precondition("fts-rejected"));
-            }
-        };
-
-        // Step 4
-        // Schedule data transfer
-        tokio::spawn(Self::transfer_file_impl(
-            fts_client,
-            transfer_init_request,
-            transfer_init_response,
-        ));
+        send_file(
+            &self.server_ctx,
+            &host_data,
+            request_inner.file_path_src,
+            request_inner.file_path_dst,
+        )
+        .await?;
 
         Ok(tonic::Response::new(TransferSubmitResponse {}))
     }
@@ -172,6 +169,7 @@ impl FileTransferService for FileTransferServiceImpl {
         request: tonic::Request<TransferInitRequest>,
     ) -> Result<tonic::Response<TransferInitResponse>, tonic::Status> {
         log::trace!("Received TransferInitRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
         // This message means that some other server (or the very same) wants to tranfser file
         // to us.
         // We need to either decline the request & provide a reason,
@@ -179,9 +177,28 @@ impl FileTransferService for FileTransferServiceImpl {
 
         let request_inner = request.into_inner();
 
+        let requested_locally = self
+            .server_ctx
+            .expected_transfers
+            .take(&caller.uuid, &request_inner.file_path_dst);
+        if !requested_locally {
+            ensure_not_ignored(&self.server_ctx, &request_inner.file_path_dst).await?;
+            ensure_newer_version(&self.server_ctx, &request_inner).await?;
+            auth::authorize_file_access(
+                &self.server_ctx,
+                &caller,
+                &request_inner.file_path_dst,
+                Access {
+                    read: false,
+                    write: true,
+                },
+            )
+            .await?;
+        }
+
         let session = {
             let mut sf_guard = self.session_factory.lock().await;
-            sf_guard.create_session(request_inner)
+            sf_guard.create_session(caller.uuid, request_inner)
         };
 
         let session_id = {
@@ -199,11 +216,17 @@ impl FileTransferService for FileTransferServiceImpl {
         request_stream: tonic::Request<tonic::Streaming<TransferChunkRequest>>,
     ) -> Result<tonic::Response<TransferChunkResponse>, tonic::Status> {
         log::trace!("Received TransferChunkRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request_stream).await?;
+
         let mut stream = request_stream.into_inner().peekable();
 
-        let first_chunk = stream.peek().await.unwrap().as_ref().unwrap();
+        let Some(Ok(first_chunk)) = stream.peek().await else {
+            return Err(tonic::Status::invalid_argument("missing-first-chunk"));
+        };
         let session_id = SessionId(first_chunk.session_id);
-        assert!(session_id.is_valid());
+        if !session_id.is_valid() {
+            return Err(tonic::Status::invalid_argument("invalid-session-id"));
+        }
 
         let session_opt = {
             let mut sreg = self.session_registry.lock().await;
@@ -215,15 +238,33 @@ impl FileTransferService for FileTransferServiceImpl {
                 "session-not-estabilished",
             ));
         };
+        if session.peer_uuid != caller.uuid {
+            return Err(tonic::Status::permission_denied("session-of-other-peer"));
+        }
+
+        // Files of sync roots might land in directories which do not exist yet.
+        if let Some(dir) = Path::new(&session.transfer_init_request.file_path_dst).parent()
+            && let Err(err) = tokio::fs::create_dir_all(dir).await
+        {
+            return Err(tonic::Status::internal(format!(
+                "dst-dir-create-fail: {err}"
+            )));
+        }
+
+        // The data is written aside & moved over the destination once complete, so that
+        // the destination is never seen half-written.
+        let Some(part_path) = partial_path(&session.transfer_init_request.file_path_dst) else {
+            return Err(tonic::Status::invalid_argument("dst-path-not-a-file"));
+        };
 
         // Prepare writer
         let file_handle = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
-            .open(&session.transfer_init_request.file_path_dst) // TODO: Sanitize path
+            .open(&part_path)
             .await
-            .unwrap();
+            .map_err(|err| tonic::Status::internal(format!("dst-open-fail: {err}")))?;
 
         let mut writer = BufWriter::new(file_handle);
         let mut hasher = sha1_smol::Sha1::new();
@@ -266,11 +307,482 @@ impl FileTransferService for FileTransferServiceImpl {
                 session.transfer_init_request.file_sha1,
                 file_hash
             );
+            let _ = tokio::fs::remove_file(&part_path).await;
             return Err(tonic::Status::invalid_argument("file-hash-mismatch"));
         }
 
+        let version = VersionVector::from(session.transfer_init_request.version.clone());
+        if let Err(err) = index::store_received_file(
+            &self.server_ctx,
+            &part_path,
+            &session.transfer_init_request.file_path_dst,
+            file_hash,
+            version,
+        )
+        .await
+        {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(tonic::Status::internal(format!(
+                "dst-file-store-fail: {err}"
+            )));
+        }
+
         Ok(tonic::Response::new(TransferChunkResponse {}))
     }
+
+    async fn file_describe(
+        &self,
+        request: tonic::Request<FileDescribeRequest>,
+    ) -> Result<tonic::Response<FileDescribeResponse>, tonic::Status> {
+        log::trace!("Received FileDescribeRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
+        let file_id = request.into_inner().file_id;
+
+        let local_file = self
+            .server_ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .into_iter()
+            .find(|file| file.id == file_id)
+            .ok_or_else(|| tonic::Status::not_found(format!("no-such-file: {file_id}")))?;
+
+        auth::authorize_file_access(
+            &self.server_ctx,
+            &caller,
+            &local_file.file_path,
+            Access {
+                read: true,
+                write: false,
+            },
+        )
+        .await?;
+
+        // The watcher might not have caught up with the latest change yet.
+        let (hash_sha1, version) =
+            match index::refresh_local_file(&self.server_ctx, &local_file).await {
+                Ok(LocalFileState::Unchanged) => {
+                    (local_file.hash_sha1.clone(), local_file.version_vector())
+                }
+                Ok(LocalFileState::Modified { hash_sha1, version }) => (hash_sha1, version),
+                Ok(LocalFileState::Removed | LocalFileState::Gone) => {
+                    return self.describe_deleted_file(local_file).await;
+                }
+                Err(err) => {
+                    return Err(tonic::Status::internal(format!(
+                        "file-sh1-comput-fail: {err}"
+                    )));
+                }
+            };
+
+        let conflicted = self
+            .server_ctx
+            .repo
+            .fetch_file_conflicts()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .iter()
+            .any(|conflict| conflict.file_id == local_file.id);
+        let mtime = tokio::fs::metadata(&local_file.file_path)
+            .await
+            .map_or(local_file.mtime, |metadata| {
+                tools::file::modification_time(&metadata)
+            });
+
+        Ok(tonic::Response::new(FileDescribeResponse {
+            file: Some(LocalFileDescription {
+                local_id: local_file.id.to_string(),
+                file_path: local_file.file_path,
+                hash_sha1,
+                synced_with: Vec::new(),
+                gone: false,
+                version: HashMap::from(&version),
+                conflicted,
+                mtime,
+                tombstone: None,
+            }),
+        }))
+    }
+
+    async fn file_delete(
+        &self,
+        request: tonic::Request<FileDeleteRequest>,
+    ) -> Result<tonic::Response<FileDeleteResponse>, tonic::Status> {
+        log::trace!("Received FileDeleteRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
+        let payload = request.into_inner();
+        let tombstone = payload
+            .tombstone
+            .ok_or_else(|| tonic::Status::invalid_argument("missing-tombstone"))?;
+
+        let local_file = self
+            .server_ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .into_iter()
+            .find(|file| file.id == payload.file_id)
+            .ok_or_else(|| {
+                tonic::Status::not_found(format!("no-such-file: {}", payload.file_id))
+            })?;
+
+        auth::authorize_file_access(
+            &self.server_ctx,
+            &caller,
+            &local_file.file_path,
+            Access {
+                read: false,
+                write: true,
+            },
+        )
+        .await?;
+
+        // The file might have been modified since the caller has looked at it.
+        let local_version = match index::refresh_local_file(&self.server_ctx, &local_file).await {
+            Ok(LocalFileState::Unchanged) => local_file.version_vector(),
+            Ok(LocalFileState::Modified { version, .. }) => version,
+            // Deleted here as well.
+            Ok(LocalFileState::Removed | LocalFileState::Gone) => {
+                return Ok(tonic::Response::new(FileDeleteResponse {}));
+            }
+            Err(err) => {
+                return Err(tonic::Status::internal(format!(
+                    "file-sh1-comput-fail: {err}"
+                )));
+            }
+        };
+        let version = VersionVector::from(payload.version);
+        if !matches!(
+            local_version.compare(&version),
+            Causality::Before | Causality::Equal
+        ) {
+            return Err(tonic::Status::failed_precondition("version-not-newer"));
+        }
+
+        index::apply_tombstone(
+            &self.server_ctx,
+            &local_file,
+            &FileTombstoneRow {
+                file_id: local_file.id,
+                host_uuid: tombstone.host_uuid,
+                version: version.to_string(),
+                deleted_at: tombstone.deleted_at,
+            },
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("file-delete-fail: {err}")))?;
+
+        Ok(tonic::Response::new(FileDeleteResponse {}))
+    }
+
+    async fn root_list(
+        &self,
+        request: tonic::Request<RootListRequest>,
+    ) -> Result<tonic::Response<RootListResponse>, tonic::Status> {
+        log::trace!("Received RootListRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
+
+        let all_roots = self
+            .server_ctx
+            .repo
+            .fetch_sync_roots()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?;
+
+        let mut roots = Vec::new();
+        for root in all_roots {
+            if authorize_root_read(&self.server_ctx, &caller, root.id)
+                .await
+                .is_err()
+            {
+                continue;
+            }
+            roots.push(SyncRootDescription {
+                root_id: root.id,
+                path: root.path,
+                synced_with: Vec::new(),
+            });
+        }
+
+        Ok(tonic::Response::new(RootListResponse { roots }))
+    }
+
+    async fn root_describe(
+        &self,
+        request: tonic::Request<RootDescribeRequest>,
+    ) -> Result<tonic::Response<RootDescribeResponse>, tonic::Status> {
+        log::trace!("Received RootDescribeRequest");
+        let caller = auth::authenticate_peer(&self.server_ctx, &request).await?;
+        let root_id = request.into_inner().root_id;
+
+        let root = self
+            .server_ctx
+            .repo
+            .fetch_sync_roots()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .into_iter()
+            .find(|root| root.id == root_id)
+            .ok_or_else(|| tonic::Status::not_found(format!("no-such-root: {root_id}")))?;
+
+        authorize_root_read(&self.server_ctx, &caller, root.id).await?;
+
+        let files = self
+            .server_ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?
+            .into_iter()
+            .filter(|file| file.root_id == Some(root.id))
+            .filter_map(|file| {
+                let relative_path = root_relative_path(&root, &file.file_path)?;
+                Some(RootFileDescription {
+                    file_id: file.id,
+                    relative_path,
+                    hash_sha1: file.hash_sha1,
+                    gone: file.gone,
+                })
+            })
+            .collect();
+
+        Ok(tonic::Response::new(RootDescribeResponse {
+            root: Some(SyncRootDescription {
+                root_id: root.id,
+                path: root.path,
+                synced_with: Vec::new(),
+            }),
+            files,
+        }))
+    }
+}
+
+/// Make sure the caller may have the file sent to the destination. Callers have files sent to
+/// themselves as they wish, the local host (a file sent to itself is taken as requested locally)
+/// is off limits for remote callers & other hosts only receive files the caller may write.
+async fn authorize_transfer_destination(
+    ctx: &ServerContext,
+    caller: &HostsRow,
+    host_dst: &HostsRow,
+    file_path_dst: &str,
+) -> Result<(), tonic::Status> {
+    if !caller.is_remote || host_dst.uuid == caller.uuid {
+        return Ok(());
+    }
+    if !host_dst.is_remote {
+        return Err(tonic::Status::permission_denied("local-destination"));
+    }
+
+    auth::authorize_file_access(
+        ctx,
+        caller,
+        file_path_dst,
+        Access {
+            read: false,
+            write: true,
+        },
+    )
+    .await
+}
+
+/// Paths ignored by their sync root are neither read nor written by peers.
+async fn ensure_not_ignored(ctx: &ServerContext, file_path: &str) -> Result<(), tonic::Status> {
+    let roots = ctx
+        .repo
+        .fetch_sync_roots()
+        .await
+        .map_err(|err| tonic::Status::internal(err.to_string()))?;
+    let file_path = Path::new(file_path);
+
+    if let Some(root) = index::find_root(&roots, file_path)
+        && RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns).is_ignored(file_path, false)
+    {
+        return Err(tonic::Status::failed_precondition("path-ignored"));
+    }
+
+    Ok(())
+}
+
+/// Refuse pushes to tracked files, which would discard modifications the sender has not seen.
+/// The sync of the sender is going to find the files conflicted instead.
+async fn ensure_newer_version(
+    ctx: &ServerContext,
+    request: &TransferInitRequest,
+) -> Result<(), tonic::Status> {
+    // Files sent by hand replace whatever is there.
+    if request.version.is_empty() {
+        return Ok(());
+    }
+
+    let local_files = ctx
+        .repo
+        .fetch_local_files()
+        .await
+        .map_err(|err| tonic::Status::internal(err.to_string()))?;
+    let Some(local_file) = local_files
+        .iter()
+        .find(|file| file.file_path == request.file_path_dst)
+    else {
+        return Ok(());
+    };
+
+    let version = VersionVector::from(request.version.clone());
+    match version.compare(&local_file.version_vector()) {
+        Causality::After | Causality::Equal => Ok(()),
+        _ if local_file.hash_sha1 == request.file_sha1 => Ok(()),
+        Causality::Before | Causality::Concurrent => {
+            Err(tonic::Status::failed_precondition("version-not-newer"))
+        }
+    }
+}
+
+/// Temporary path the file is received to, see [`PARTIAL_FILE_SUFFIX`].
+pub(crate) fn partial_path(file_path_dst: &str) -> Option<PathBuf> {
+    let file_path_dst = Path::new(file_path_dst);
+    let file_name = file_path_dst.file_name()?.to_str()?;
+    Some(file_path_dst.with_file_name(format!(".{file_name}{PARTIAL_FILE_SUFFIX}")))
+}
+
+/// Local host may read any root, remote hosts those shared with them.
+async fn authorize_root_read(
+    ctx: &ServerContext,
+    host: &HostsRow,
+    root_id: i32,
+) -> Result<(), tonic::Status> {
+    if !host.is_remote {
+        return Ok(());
+    }
+
+    let granted = ctx
+        .repo
+        .fetch_root_access(root_id, &host.uuid)
+        .await
+        .map_err(|err| tonic::Status::internal(err.to_string()))?;
+    if !granted.read {
+        return Err(tonic::Status::permission_denied("no-read-access"));
+    }
+
+    Ok(())
+}
+
+/// Path of the file relative to the root, with `/` separators regardless of the platform.
+fn root_relative_path(root: &SyncRootRow, file_path: &str) -> Option<String> {
+    let relative_path = root.relative_path(file_path)?;
+    let components = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(components.join("/"))
+}
+
+/// Send a local file to `host_dst`. Returns once the destination has accepted the transfer,
+/// the data is sent in the background.
+pub(crate) async fn send_file(
+    ctx: &ServerContext,
+    host_dst: &HostsRow,
+    file_path_src: String,
+    file_path_dst: String,
+) -> Result<(), tonic::Status> {
+    // Step 1
+    // Verify that the file path is absolute & the file exists.
+
+    let file_path_src_buf: PathBuf =
+        PathBuf::from_str(&file_path_src).expect("Path conversion should never fail");
+
+    if !file_path_src_buf.is_absolute() {
+        return Err(tonic::Status::invalid_argument("src-path-not-absolute"));
+    }
+
+    // TODO: Support not only files, but also directories
+    if !file_path_src_buf.is_file() {
+        return Err(tonic::Status::invalid_argument("src-path-not-a-file"));
+    }
+
+    // Step 2
+    // Extract necessary file information
+
+    let Ok(file_metadata) = file_path_src_buf.metadata() else {
+        return Err(tonic::Status::internal("src-path-failed-metadata-fetch"));
+    };
+
+    let Ok(file_size_bytes) = i64::try_from(file_metadata.len()) else {
+        return Err(tonic::Status::internal("file-size-conversion-fail"));
+    };
+
+    let Ok(file_sha1) =
+        tools::file::compute_sha1_hash_from_file_async(&file_path_src_buf, None).await
+    else {
+        return Err(tonic::Status::internal("file-sh1-comput-fail"));
+    };
+
+    log::debug!(
+        "File info - size: {} bytes, sha1: {}",
+        file_size_bytes,
+        file_sha1
+    );
+
+    // Tracked files carry their version, see `version_vector`.
+    let version = source_version(ctx, &file_path_src)
+        .await
+        .map_err(|err| tonic::Status::internal(format!("src-version-fetch-fail: {err}")))?;
+
+    // Step 3
+    // Send init message to destination host
+    let connection = peer::connect_to_host(ctx, host_dst, Duration::from_secs(5))
+        .await
+        .map_err(|err| tonic::Status::failed_precondition(format!("fts-connection-fail: {err}")))?;
+
+    let mut fts_client = FileTransferServiceClient::new(connection);
+
+    let transfer_init_request = TransferInitRequest {
+        file_path_src,
+        file_path_dst,
+        file_sha1,
+        file_size_bytes,
+        chunk_size: 1024 * 8,
+        version: HashMap::from(&version),
+    };
+
+    let result = fts_client
+        .transfer_init(transfer_init_request.clone())
+        .await;
+
+    let transfer_init_response = match result {
+        Ok(response) => response.into_inner(),
+        Err(status) => {
+            // log::error!(format!("FTS at {} rejected transfer request: {}", &request_inner.host_dst_uuid, status));
+            return Err(tonic::Status::failed_precondition(format!(
+                "fts-rejected: {}",
+                status.message()
+            )));
+        }
+    };
+
+    // Step 4
+    // Schedule data transfer
+    tokio::spawn(FileTransferServiceImpl::transfer_file_impl(
+        fts_client,
+        transfer_init_request,
+        transfer_init_response,
+    ));
+
+    Ok(())
+}
+
+/// Version of the file, empty for files which are not tracked.
+async fn source_version(ctx: &ServerContext, file_path: &str) -> anyhow::Result<VersionVector> {
+    let local_files = ctx.repo.fetch_local_files().await?;
+    let Some(local_file) = local_files.iter().find(|file| file.file_path == file_path) else {
+        return Ok(VersionVector::default());
+    };
+
+    // The watcher might not have caught up with the latest change yet.
+    match index::refresh_local_file(ctx, local_file).await? {
+        LocalFileState::Modified { version, .. } => Ok(version),
+        _ => Ok(local_file.version_vector()),
+    }
 }
 
 impl FileTransferServiceImpl {
@@ -332,3 +844,181 @@ impl FileTransferServiceImpl {
         client.transfer_chunk(stream).await;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::test_utils;
+
+    #[tokio::test]
+    async fn test_transfer_to_local_host_rejected_for_remote_callers() {
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let caller = test_utils::add_paired_host(&ctx, "remote", 50051, "aa").await;
+        let local_host = ctx
+            .repo
+            .fetch_host_by_uuid(test_utils::LOCAL_UUID)
+            .await
+            .unwrap();
+
+        let status = authorize_transfer_destination(&ctx, &caller, &local_host, "/etc/passwd")
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // The local host itself is trusted, e.g. with `file copy` from the CLI.
+        authorize_transfer_destination(&ctx, &local_host, &local_host, "/tmp/copy")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_other_hosts_requires_write_access() {
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let caller = test_utils::add_paired_host(&ctx, "remote", 50051, "aa").await;
+        let other = test_utils::add_paired_host(&ctx, "other", 50051, "bb").await;
+
+        // Pulls have the file sent to the caller.
+        authorize_transfer_destination(&ctx, &caller, &caller, "/tmp/pulled")
+            .await
+            .unwrap();
+
+        let status = authorize_transfer_destination(&ctx, &caller, &other, "/tmp/pushed")
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_of_ignored_paths_refused() {
+        let dir = test_utils::temp_dir();
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let mut peer_cfg = test_utils::test_config();
+        peer_cfg.ignore_patterns = vec![".env".to_owned()];
+        let peer_ctx = test_utils::host_context("peer", peer_cfg).await;
+
+        let local_port = test_utils::serve_peer_services(ctx.clone()).await;
+        let peer_port = test_utils::serve_peer_services(peer_ctx.clone()).await;
+        let peer = test_utils::add_paired_host(
+            &ctx,
+            "peer",
+            peer_port,
+            &peer_ctx.identity.public_key_hex(),
+        )
+        .await;
+        test_utils::add_paired_host(
+            &peer_ctx,
+            test_utils::LOCAL_UUID,
+            local_port,
+            &ctx.identity.public_key_hex(),
+        )
+        .await;
+
+        let root_dir = dir.join("root");
+        std::fs::create_dir(&root_dir).unwrap();
+        std::fs::write(root_dir.join(".env"), "SECRET=1").unwrap();
+        std::fs::write(root_dir.join("notes.txt"), "notes").unwrap();
+        let root_id = peer_ctx
+            .repo
+            .save_sync_root(root_dir.to_str().unwrap())
+            .await
+            .unwrap();
+        peer_ctx
+            .repo
+            .save_root_access(
+                root_id,
+                test_utils::LOCAL_UUID,
+                Access {
+                    read: true,
+                    write: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        let channel = peer::connect_to_host(&ctx, &peer, Duration::from_secs(5))
+            .await
+            .unwrap();
+        let mut client = FileTransferServiceClient::new(channel);
+        let mut submit = async |file_name: &str| {
+            let file_path_dst = dir.join(file_name).to_str().unwrap().to_owned();
+            ctx.expected_transfers.expect("peer", &file_path_dst);
+            client
+                .transfer_submit(TransferSubmitRequest {
+                    host_org_uuid: test_utils::LOCAL_UUID.to_owned(),
+                    host_dst_uuid: test_utils::LOCAL_UUID.to_owned(),
+                    file_path_src: root_dir.join(file_name).to_str().unwrap().to_owned(),
+                    file_path_dst,
+                })
+                .await
+        };
+
+        submit("notes.txt").await.unwrap();
+        let status = submit(".env").await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert_eq!(status.message(), "path-ignored");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_session_refused_to_other_peers() {
+        let dir = test_utils::temp_dir();
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let local_port = test_utils::serve_peer_services(ctx.clone()).await;
+
+        let mut clients = Vec::new();
+        for uuid in ["peer", "intruder"] {
+            let peer_ctx = test_utils::host_context(uuid, test_utils::test_config()).await;
+            test_utils::add_paired_host(&ctx, uuid, 50051, &peer_ctx.identity.public_key_hex())
+                .await;
+            let local = test_utils::add_paired_host(
+                &peer_ctx,
+                test_utils::LOCAL_UUID,
+                local_port,
+                &ctx.identity.public_key_hex(),
+            )
+            .await;
+            let channel = peer::connect_to_host(&peer_ctx, &local, Duration::from_secs(5))
+                .await
+                .unwrap();
+            clients.push(FileTransferServiceClient::new(channel));
+        }
+        let [mut peer_client, mut intruder_client] = clients.try_into().unwrap();
+
+        let data = b"data".to_vec();
+        let file_path_dst = dir.join("pushed.txt").to_str().unwrap().to_owned();
+        ctx.expected_transfers.expect("peer", &file_path_dst);
+        let session_id = peer_client
+            .transfer_init(TransferInitRequest {
+                file_path_src: "/tmp/pushed.txt".to_owned(),
+                file_path_dst: file_path_dst.clone(),
+                file_sha1: sha1_smol::Sha1::from(&data).digest().to_string(),
+                file_size_bytes: data.len() as i64,
+                chunk_size: 1024,
+                version: HashMap::new(),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+        let chunks = |data_buffer: &[u8]| {
+            tokio_stream::iter(vec![TransferChunkRequest {
+                session_id,
+                chunk_id: 0,
+                data_buffer: data_buffer.to_vec(),
+            }])
+        };
+
+        let status = intruder_client
+            .transfer_chunk(chunks(b"evil"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // The session stays usable by the peer which opened it.
+        peer_client.transfer_chunk(chunks(&data)).await.unwrap();
+        assert_eq!(std::fs::read(&file_path_dst).unwrap(), data);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -20,5 +20,7 @@ impl From<SessionId> for i32 {
 #[derive(Debug, Clone)]
 pub(crate) struct FileTransferSession {
     pub session_id: SessionId,
+    /// Uuid of the peer which opened the session; only it may stream the data.
+    pub peer_uuid: String,
     pub transfer_init_request: TransferInitRequest,
 }
@@ -17,10 +17,12 @@ impl FileTransferSessionFactory {
 impl FileTransferSessionFactory {
     pub(crate) fn create_session(
         &mut self,
+        peer_uuid: String,
         transfer_init_request: TransferInitRequest,
     ) -> FileTransferSession {
         let new_session = FileTransferSession {
             session_id: self.next_session_id,
+            peer_uuid,
             transfer_init_request,
         };
 
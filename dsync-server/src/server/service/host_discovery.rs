@@ -1,12 +1,18 @@
 use dsync_proto::model::server::HostInfo;
 use dsync_proto::services::host_discovery::host_discovery_service_server::HostDiscoveryService;
-use dsync_proto::services::host_discovery::{GeneralKenobiResponse, HelloThereRequest};
+use dsync_proto::services::host_discovery::{
+    GeneralKenobiResponse, HelloThereRequest, PairInitRequest, PairInitResponse, PairRevealRequest,
+    PairRevealResponse, PingRequest, PingResponse,
+};
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use crate::server::context::ServerContext;
 use crate::server::data::source::sqlite::database::models::HostsRow;
+use crate::server::pairing::PairingError;
 use crate::server::service::tools;
+use crate::server::service::tools::net::{addr, auth, handshake};
+use crate::server::{blocklist, identity, pairing};
 
 // #[derive(Debug)]
 pub struct HostDiscoveryServiceImpl {
@@ -34,6 +40,8 @@ impl HostDiscoveryService for HostDiscoveryServiceImpl {
         let peer_addr = request.remote_addr().ok_or_else(|| {
             Status::invalid_argument("Can not determine address of connecting peer")
         })?;
+        let public_key = auth::peer_public_key(&request)
+            .ok_or_else(|| Status::unauthenticated("missing-client-certificate"))?;
 
         let Some(peer_info) = request.into_inner().host_info else {
             log::trace!(target: "pslog", "Rejecting request due to missing peer info");
@@ -42,6 +50,34 @@ impl HostDiscoveryService for HostDiscoveryServiceImpl {
 
         log::debug!(target: "pslog", "Connectinng peer: {}", peer_info.hostname);
 
+        match blocklist::find_rule(&self.ctx, Some(&peer_info.uuid), Some(peer_addr.ip())).await {
+            Ok(None) => {}
+            Ok(Some(rule)) => {
+                log::info!(target: "pslog", "Refusing blocked peer {} at {peer_addr}, rule {rule}", peer_info.uuid);
+                return Err(tonic::Status::permission_denied("blocked-host"));
+            }
+            Err(err) => {
+                return Err(tonic::Status::internal(format!(
+                    "Failed to check the blocklist: {err}"
+                )));
+            }
+        }
+
+        if let Err(err) = handshake::check_compatibility(peer_info.version.as_ref()) {
+            log::warn!(target: "pslog", "Refusing incompatible peer {}: {err}", peer_info.hostname);
+            return Err(tonic::Status::failed_precondition(format!(
+                "incompatible-peer: {err}"
+            )));
+        }
+
+        if let Err(err) = handshake::check_pinned_key(&self.ctx, &peer_info.uuid, &public_key).await
+        {
+            log::warn!(target: "pslog", "Refusing peer {}: {err}", peer_info.hostname);
+            return Err(tonic::Status::permission_denied(format!(
+                "pinned-key-mismatch: {err}"
+            )));
+        }
+
         let server_info = match self.retrieve_local_server_info().await {
             Ok(data) => data,
             Err(err) => {
@@ -52,18 +88,19 @@ impl HostDiscoveryService for HostDiscoveryServiceImpl {
             }
         };
 
-        // Can not use HostsRow::from_host_info due to ipv4_addr field not coming from the peer_info
-        let host_row = HostsRow {
-            uuid: peer_info.uuid,
-            name: peer_info.name,
-            hostname: peer_info.hostname,
-            is_remote: true,
-            ipv4_addr: peer_addr.ip().to_string(),
-            discovery_time: tools::time::get_current_timestamp(),
+        // Peers never send their own address, we know it from the connection though.
+        // The port is the one peer listens on, not the source port of this connection.
+        let peer_info = HostInfo {
+            address: addr::format_ip(&addr::canonical_socket_addr(peer_addr)),
+            ..peer_info
         };
-
-        // FIXME: We need to handle the result of the insert operation
-        let _ = self.ctx.repo.insert_hosts(&[host_row]).await;
+        // Known peers might be connecting from a new address (e.g. after changing networks).
+        let seen_time = tools::time::get_current_timestamp();
+        if let Err(err) =
+            handshake::remember_host(&self.ctx, &peer_info, &public_key, seen_time).await
+        {
+            log::warn!(target: "pslog", "Failed to save connecting peer: {err}");
+        }
 
         Ok(Response::new(GeneralKenobiResponse {
             host_info: Some(HostInfo {
@@ -71,7 +108,122 @@ impl HostDiscoveryService for HostDiscoveryServiceImpl {
                 name: server_info.name,
                 hostname: server_info.hostname,
                 address: "".to_string(),
+                port: self.ctx.cfg.port.into(),
+                version: Some(handshake::local_daemon_version()),
+                ..Default::default()
             }),
         }))
     }
+
+    async fn ping(&self, _request: Request<PingRequest>) -> Result<Response<PingResponse>, Status> {
+        let server_info = self.retrieve_local_server_info().await.map_err(|err| {
+            tonic::Status::internal(format!("Failed to fetch local server info: {err}"))
+        })?;
+
+        Ok(Response::new(PingResponse {
+            uuid: server_info.uuid,
+        }))
+    }
+
+    async fn pair_init(
+        &self,
+        request: Request<PairInitRequest>,
+    ) -> Result<Response<PairInitResponse>, Status> {
+        log::info!(target: "pslog", "Received pair_init rpc");
+
+        let peer_addr = request.remote_addr().ok_or_else(|| {
+            Status::invalid_argument("Can not determine address of connecting peer")
+        })?;
+        let tls_public_key = auth::peer_public_key(&request)
+            .ok_or_else(|| Status::unauthenticated("missing-client-certificate"))?;
+        let payload = request.into_inner();
+
+        let Some(peer_info) = payload.host_info else {
+            return Err(Status::invalid_argument("Missing peer info"));
+        };
+
+        if let Err(err) = handshake::check_compatibility(peer_info.version.as_ref()) {
+            return Err(Status::failed_precondition(format!(
+                "incompatible-peer: {err}"
+            )));
+        }
+
+        if let Err(err) = identity::parse_public_key(&payload.public_key) {
+            return Err(Status::invalid_argument(format!(
+                "invalid-public-key: {err}"
+            )));
+        }
+
+        // The key being paired has to be the one the peer proves to hold.
+        if payload.public_key != tls_public_key {
+            return Err(Status::permission_denied("public-key-mismatch"));
+        }
+
+        if let Err(err) =
+            handshake::check_pinned_key(&self.ctx, &peer_info.uuid, &tls_public_key).await
+        {
+            return Err(Status::permission_denied(format!(
+                "pinned-key-mismatch: {err}"
+            )));
+        }
+
+        let server_info = self
+            .retrieve_local_server_info()
+            .await
+            .map_err(|err| Status::internal(format!("Failed to fetch local server info: {err}")))?;
+
+        if peer_info.uuid == server_info.uuid {
+            return Err(Status::invalid_argument("Can not pair with itself"));
+        }
+
+        // Saved only once the user confirms the pairing.
+        let peer_info = HostInfo {
+            address: addr::format_ip(&addr::canonical_socket_addr(peer_addr)),
+            ..peer_info
+        };
+
+        let nonce = self
+            .ctx
+            .pairings
+            .start_incoming(peer_info, payload.public_key, payload.commitment)
+            .map_err(|err| Status::resource_exhausted(err.to_string()))?;
+
+        Ok(Response::new(PairInitResponse {
+            uuid: server_info.uuid,
+            public_key: self.ctx.identity.public_key().to_vec(),
+            nonce: nonce.to_vec(),
+        }))
+    }
+
+    async fn pair_reveal(
+        &self,
+        request: Request<PairRevealRequest>,
+    ) -> Result<Response<PairRevealResponse>, Status> {
+        log::info!(target: "pslog", "Received pair_reveal rpc");
+
+        let tls_public_key = auth::peer_public_key(&request)
+            .ok_or_else(|| Status::unauthenticated("missing-client-certificate"))?;
+        let payload = request.into_inner();
+        if payload.nonce.len() != pairing::NONCE_LENGTH {
+            return Err(Status::invalid_argument("invalid-nonce-length"));
+        }
+
+        match self.ctx.pairings.reveal(
+            &payload.uuid,
+            &tls_public_key,
+            &payload.nonce,
+            &self.ctx.identity.public_key(),
+        ) {
+            Ok(_) => {
+                log::info!(target: "pslog", "Host {} requested pairing, run `dsync-cli host pair` to verify it", payload.uuid);
+                Ok(Response::new(PairRevealResponse {}))
+            }
+            Err(err @ PairingError::CommitmentMismatch) => {
+                log::warn!(target: "pslog", "Aborting pairing with host {}: {err}", payload.uuid);
+                Err(Status::permission_denied(err.to_string()))
+            }
+            Err(err @ PairingError::KeyMismatch) => Err(Status::permission_denied(err.to_string())),
+            Err(err) => Err(Status::failed_precondition(err.to_string())),
+        }
+    }
 }
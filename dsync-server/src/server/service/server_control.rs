@@ -16,7 +16,7 @@ pub struct ServerControlServiceImpl {
 impl ServerControlServiceImpl {
     pub fn new(ctx: Arc<ServerContext>, shutdown_sig: oneshot::Sender<()>) -> Self {
         Self {
-            ctx: ctx,
+            ctx,
             shutdown_sig: Mutex::new(Some(shutdown_sig)),
         }
     }
@@ -1,4 +1,8 @@
-use std::{io::Read, path::Path};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
 
 use tokio::io::AsyncReadExt;
 
@@ -66,6 +70,64 @@ pub async fn compute_sha1_hash_from_file_async(
     anyhow::Ok(sha1_instance.digest().to_string())
 }
 
+/// Modification time of the file, as unix timestamp in nanoseconds (0 when unavailable).
+pub fn modification_time(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .and_then(|duration| duration.as_nanos().try_into().ok())
+        .unwrap_or_default()
+}
+
+/// Regular files & directories of a directory tree.
+#[derive(Debug, Default)]
+pub struct DirTree {
+    pub files: Vec<PathBuf>,
+    /// Includes the directory the walk started in.
+    pub dirs: Vec<PathBuf>,
+}
+
+/// Walk the directory & all its subdirectories. Symlinks are not followed, subdirectories which
+/// can not be read are skipped. Entries for which `is_excluded(path, is_dir)` holds are skipped,
+/// excluded directories are not descended into.
+pub fn walk_dir(
+    dir: impl AsRef<Path>,
+    mut is_excluded: impl FnMut(&Path, bool) -> bool,
+) -> std::io::Result<DirTree> {
+    let mut tree = DirTree::default();
+    let mut pending_dirs = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(current_dir) = pending_dirs.pop() {
+        let entries = match std::fs::read_dir(&current_dir) {
+            Ok(entries) => entries,
+            Err(err) if current_dir != dir.as_ref() => {
+                log::debug!("Skipping unreadable directory {current_dir:?}: {err}");
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        tree.dirs.push(current_dir);
+
+        for entry in entries {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if (file_type.is_dir() || file_type.is_file()) && is_excluded(&path, file_type.is_dir())
+            {
+                continue;
+            }
+            if file_type.is_dir() {
+                pending_dirs.push(path);
+            } else if file_type.is_file() {
+                tree.files.push(path);
+            }
+        }
+    }
+
+    Ok(tree)
+}
+
 /// Basically tries to call `which ${binary_name}` & reports the command status.
 /// Returns false if the check has failed for some other reason!
 pub fn check_binary_exists(binary_name: &str) -> bool {
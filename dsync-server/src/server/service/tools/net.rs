@@ -1 +1,11 @@
+pub mod addr;
 pub mod addr_discovery;
+pub mod auth;
+pub mod beacon;
+pub mod handshake;
+pub mod heartbeat;
+pub mod pairing;
+pub mod peer;
+pub mod static_peers;
+pub mod sweep;
+pub mod tls;
@@ -0,0 +1,78 @@
+//! Helpers for handling peer addresses of both IP families.
+
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+/// Turn IPv4-mapped IPv6 addresses (seen on dual-stack listeners) back into plain IPv4.
+pub(crate) fn canonical_socket_addr(addr: SocketAddr) -> SocketAddr {
+    match addr {
+        SocketAddr::V6(v6) => match v6.ip().to_ipv4_mapped() {
+            Some(v4) => SocketAddr::new(v4.into(), v6.port()),
+            None => addr,
+        },
+        SocketAddr::V4(_) => addr,
+    }
+}
+
+/// Render the IP part of the address, keeping the zone id of scoped IPv6 addresses
+/// (e.g. `fe80::1%2`), as link-local addresses are useless without it.
+pub(crate) fn format_ip(addr: &SocketAddr) -> String {
+    match addr {
+        SocketAddr::V6(v6) if v6.scope_id() != 0 => format!("{}%{}", v6.ip(), v6.scope_id()),
+        _ => addr.ip().to_string(),
+    }
+}
+
+/// Inverse of [`format_ip`]. Accepts plain IPv4 / IPv6 addresses & IPv6 addresses
+/// with a numeric zone id.
+pub(crate) fn parse_socket_addr(ip: &str, port: u16) -> anyhow::Result<SocketAddr> {
+    let ip = ip.trim_start_matches('[').trim_end_matches(']');
+
+    if let Some((ip, scope_id)) = ip.split_once('%') {
+        let ip: Ipv6Addr = ip.parse()?;
+        let scope_id: u32 = scope_id.parse()?;
+        return Ok(SocketAddrV6::new(ip, port, 0, scope_id).into());
+    }
+
+    Ok(SocketAddr::new(ip.parse::<IpAddr>()?, port))
+}
+
+/// Rank of the address when the same peer is reachable through several of them.
+/// Higher is better: global IPv6, then link-local IPv6, then IPv4.
+pub(crate) fn preference(addr: &SocketAddr) -> u8 {
+    match addr.ip() {
+        IpAddr::V6(ip) if ip.is_unicast_link_local() => 1,
+        IpAddr::V6(ip) if ip.to_ipv4_mapped().is_none() => 2,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_canonical_socket_addr_unmaps_ipv4() {
+        let mapped: SocketAddr = "[::ffff:192.168.1.2]:50051".parse().unwrap();
+        assert_eq!(
+            canonical_socket_addr(mapped),
+            "192.168.1.2:50051".parse::<SocketAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_parse_roundtrip_keeps_scope() {
+        let addr: SocketAddr = SocketAddrV6::new("fe80::1".parse().unwrap(), 50051, 0, 3).into();
+        let formatted = format_ip(&addr);
+        assert_eq!(formatted, "fe80::1%3");
+        assert_eq!(parse_socket_addr(&formatted, 50051).unwrap(), addr);
+    }
+
+    #[test]
+    fn test_preference_favours_ipv6() {
+        let v4: SocketAddr = "192.168.1.2:50051".parse().unwrap();
+        let link_local: SocketAddr = "[fe80::1]:50051".parse().unwrap();
+        let global: SocketAddr = "[2001:db8::1]:50051".parse().unwrap();
+        assert!(preference(&global) > preference(&link_local));
+        assert!(preference(&link_local) > preference(&v4));
+    }
+}
@@ -1,37 +1,72 @@
-use std::{net::Ipv4Addr, str::FromStr};
-
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV6, ToSocketAddrs},
+    str::FromStr,
+    sync::Arc,
+};
+
+use ipnet::IpNet;
 use serde::Deserialize;
 
-pub(crate) fn discover_hosts_in_local_network() -> Option<Vec<Ipv4Addr>> {
-    let nmap_result = std::process::Command::new("nmap")
-        .arg("-sP")
-        .arg("192.168.100.1/24")
-        .status();
-
-    if let Err(err) = nmap_result {
-        log::error!("nmap run failed with error: {err}");
-        return None;
+use crate::server::{
+    config::{Config, DiscoveryBackendKind, StaticPeer},
+    context::ServerContext,
+    service::tools::net::{addr, beacon::BroadcastBeaconBackend, sweep::TcpSweepDiscoveryBackend},
+};
+
+/// Source of candidate peer addresses used by host discovery. Addresses returned
+/// by the backend are not verified - each one still has to pass the `HelloThere` handshake.
+#[async_trait::async_trait]
+pub(crate) trait DiscoveryBackend: Send + Sync {
+    /// Spawn any background work the backend needs. Called once, on server start.
+    async fn start(&self, _ctx: Arc<ServerContext>) -> anyhow::Result<()> {
+        Ok(())
     }
 
-    // Lines for existing & up hosts.
-    let ipv4_list: Vec<Ipv4Addr> = CandidateAddressProviderFactory::new()
-        .make_provider()
-        .unwrap()
-        .compute_list()
-        .unwrap();
+    /// Compute the list of addresses potential peers might listen on.
+    async fn discover(&self) -> anyhow::Result<Vec<SocketAddr>>;
+}
+
+/// Reads addresses from system neighbour tables. Assumes that all peers listen on the same port.
+#[derive(Debug, Clone)]
+pub(crate) struct NeighboursDiscoveryBackend {
+    port: u16,
+}
+
+#[async_trait::async_trait]
+impl DiscoveryBackend for NeighboursDiscoveryBackend {
+    async fn discover(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        let Some(provider) = CandidateAddressProviderFactory::new().make_provider() else {
+            anyhow::bail!("Neither `arp` nor `ip` binary is available");
+        };
+
+        provider.compute_list(self.port)
+    }
+}
 
-    Some(ipv4_list)
+pub(crate) fn make_discovery_backend(cfg: &Config) -> Arc<dyn DiscoveryBackend> {
+    match cfg.discovery.backend {
+        DiscoveryBackendKind::Sweep => {
+            Arc::new(TcpSweepDiscoveryBackend::new(cfg.discovery.clone()))
+        }
+        DiscoveryBackendKind::Neighbours => Arc::new(NeighboursDiscoveryBackend {
+            port: cfg.discovery.scan_port,
+        }),
+        DiscoveryBackendKind::Broadcast => {
+            Arc::new(BroadcastBeaconBackend::new(cfg.discovery.clone()))
+        }
+    }
 }
 
 pub trait CandidateAddressProvider {
-    fn compute_list(&self) -> anyhow::Result<Vec<Ipv4Addr>>;
+    fn compute_list(&self, port: u16) -> anyhow::Result<Vec<SocketAddr>>;
 }
 
 #[derive(Debug, Clone)]
 pub struct ArpAddressProvider;
 
 impl CandidateAddressProvider for ArpAddressProvider {
-    fn compute_list(&self) -> anyhow::Result<Vec<Ipv4Addr>> {
+    fn compute_list(&self, port: u16) -> anyhow::Result<Vec<SocketAddr>> {
         let arp_result = std::process::Command::new("arp").arg("-a").output();
 
         if let Err(err) = arp_result {
@@ -49,8 +84,8 @@ impl CandidateAddressProvider for ArpAddressProvider {
             }
         };
 
-        // Lines for existing & up hosts.
-        let ipv4_list: Vec<Ipv4Addr> = output_string
+        // Lines for existing & up hosts. `arp` knows IPv4 neighbours only.
+        let ipv4_list: Vec<SocketAddr> = output_string
             .lines()
             .filter(|&line| !line.contains("incomplete") && !line.contains("_gateway"))
             .filter_map(|line| {
@@ -77,6 +112,7 @@ impl CandidateAddressProvider for ArpAddressProvider {
                 })
             })
             .filter_map(Result::ok)
+            .map(|addr| SocketAddr::new(addr.into(), port))
             .collect();
 
         Ok(ipv4_list)
@@ -87,7 +123,7 @@ impl CandidateAddressProvider for ArpAddressProvider {
 pub struct IpNeighAddressProvider;
 
 impl CandidateAddressProvider for IpNeighAddressProvider {
-    fn compute_list(&self) -> anyhow::Result<Vec<Ipv4Addr>> {
+    fn compute_list(&self, port: u16) -> anyhow::Result<Vec<SocketAddr>> {
         let cmd_output = match std::process::Command::new("ip")
             .arg("-json")
             .arg("neighbour")
@@ -108,20 +144,128 @@ impl CandidateAddressProvider for IpNeighAddressProvider {
 
         let neigh_objects: Vec<IpNeighObject> = serde_json::from_str(&output_string)?;
 
-        let ipv4_list = neigh_objects
+        let interface_indices = interface_indices();
+
+        let addr_list = neigh_objects
             .into_iter()
             .filter(|neigh| {
-                !neigh.dst.contains(":")
-                    && !["FAILED", "INCOMPLETE"].contains(&neigh.state.first().unwrap().as_str())
+                !["FAILED", "INCOMPLETE"].contains(&neigh.state.first().unwrap().as_str())
             })
-            .map(|neigh| Ipv4Addr::from_str(&neigh.dst))
-            .filter_map(Result::ok)
-            .collect::<Vec<Ipv4Addr>>();
+            .filter_map(|neigh| {
+                let ip_addr = IpAddr::from_str(&neigh.dst).ok()?;
+                match ip_addr {
+                    IpAddr::V6(ip) if ip.is_unicast_link_local() => {
+                        // Link-local addresses are ambiguous w/o the interface they are reachable through.
+                        let scope_id = *interface_indices.get(&neigh.dev)?;
+                        Some(SocketAddrV6::new(ip, port, 0, scope_id).into())
+                    }
+                    _ => Some(SocketAddr::new(ip_addr, port)),
+                }
+            })
+            .collect::<Vec<SocketAddr>>();
 
-        Ok(ipv4_list)
+        Ok(addr_list)
     }
 }
 
+/// Peers listed in the configuration. Domain names are resolved on every call (blocking),
+/// so that peers with dynamic addresses stay reachable.
+#[derive(Debug, Clone)]
+pub struct StaticPeersAddressProvider {
+    peers: Vec<StaticPeer>,
+}
+
+impl StaticPeersAddressProvider {
+    pub fn new(peers: Vec<StaticPeer>) -> Self {
+        Self { peers }
+    }
+
+    /// Addresses of every peer. `port` is used for peers which do not specify one.
+    /// Peers which fail to resolve are skipped.
+    pub fn resolve_peers(&self, port: u16) -> Vec<(StaticPeer, Vec<SocketAddr>)> {
+        self.peers
+            .iter()
+            .filter_map(|peer| {
+                let port = peer.port.unwrap_or(port);
+                let addrs = match addr::parse_socket_addr(&peer.address, port) {
+                    Ok(addr) => vec![addr],
+                    Err(_) => (peer.address.as_str(), port)
+                        .to_socket_addrs()
+                        .inspect_err(|err| {
+                            log::warn!(target: "pslog", "Failed to resolve static peer {}: {err}", peer.address);
+                        })
+                        .ok()?
+                        .collect(),
+                };
+                Some((peer.clone(), addrs))
+            })
+            .collect()
+    }
+}
+
+impl CandidateAddressProvider for StaticPeersAddressProvider {
+    fn compute_list(&self, port: u16) -> anyhow::Result<Vec<SocketAddr>> {
+        Ok(self
+            .resolve_peers(port)
+            .into_iter()
+            .flat_map(|(_, addrs)| addrs)
+            .collect())
+    }
+}
+
+/// Keep candidates reachable through one of `interfaces`, all candidates are kept when it is empty.
+/// Scoped IPv6 addresses are matched by their zone id, other addresses by the networks
+/// the interfaces are attached to.
+pub(crate) fn retain_on_interfaces(
+    candidates: Vec<SocketAddr>,
+    interfaces: &[String],
+) -> anyhow::Result<Vec<SocketAddr>> {
+    if interfaces.is_empty() {
+        return Ok(candidates);
+    }
+
+    let allowed: Vec<if_addrs::Interface> = if_addrs::get_if_addrs()?
+        .into_iter()
+        .filter(|iface| interfaces.contains(&iface.name))
+        .collect();
+
+    Ok(candidates
+        .into_iter()
+        .filter(|candidate| is_on_interfaces(candidate, &allowed))
+        .collect())
+}
+
+fn is_on_interfaces(candidate: &SocketAddr, interfaces: &[if_addrs::Interface]) -> bool {
+    if let SocketAddr::V6(v6) = candidate
+        && v6.scope_id() != 0
+    {
+        return interfaces
+            .iter()
+            .any(|iface| iface.index == Some(v6.scope_id()));
+    }
+
+    let ip = addr::canonical_socket_addr(*candidate).ip();
+    interfaces.iter().any(|iface| {
+        let (local_ip, prefix_len) = match &iface.addr {
+            if_addrs::IfAddr::V4(addr) => (IpAddr::V4(addr.ip), addr.prefixlen),
+            if_addrs::IfAddr::V6(addr) => (IpAddr::V6(addr.ip), addr.prefixlen),
+        };
+        IpNet::new(local_ip, prefix_len).is_ok_and(|network| network.contains(&ip))
+    })
+}
+
+/// Map interface names to their indices, which serve as IPv6 zone ids.
+fn interface_indices() -> HashMap<String, u32> {
+    let Ok(interfaces) = if_addrs::get_if_addrs() else {
+        return HashMap::new();
+    };
+
+    interfaces
+        .into_iter()
+        .filter_map(|iface| Some((iface.name, iface.index?)))
+        .collect()
+}
+
 pub(crate) struct CandidateAddressProviderFactory;
 
 impl CandidateAddressProviderFactory {
@@ -129,15 +273,16 @@ impl CandidateAddressProviderFactory {
         Self
     }
 
+    /// Prefers `ip neigh`, as it lists IPv6 neighbours too.
     pub fn make_provider(&self) -> Option<Box<dyn CandidateAddressProvider>> {
-        if let Some(arp_provider) = self.make_arp_provider() {
-            return Some(arp_provider);
-        }
-
         if let Some(ip_neigh_provider) = self.make_ip_neigh_provider() {
             return Some(ip_neigh_provider);
         }
 
+        if let Some(arp_provider) = self.make_arp_provider() {
+            return Some(arp_provider);
+        }
+
         None
     }
 
@@ -161,7 +306,6 @@ impl CandidateAddressProviderFactory {
 #[derive(Deserialize)]
 struct IpNeighObject {
     pub dst: String,
-    #[allow(unused)]
     pub dev: String,
     pub state: Vec<String>,
 }
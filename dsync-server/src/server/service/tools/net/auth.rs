@@ -0,0 +1,173 @@
+//! Authentication of requests.
+//!
+//! Daemons present their certificates on every connection (mutual TLS), so the caller is
+//! recognized by the key of its client certificate. The handshake proves possession of the key.
+//!
+//! Control services (used by the CLI) are served to local callers only.
+//!
+//! Paired hosts are further limited to the files shared with them (see `authorize_file_access`).
+
+use std::path::{Component, Path};
+
+use dsync_shared::conn::tls::{self, PublicKey};
+use tonic::{service::Interceptor, transport::server::UdsConnectInfo};
+
+use crate::server::{
+    context::ServerContext,
+    data::source::sqlite::database::models::{Access, HostsRow},
+    service::tools::net::addr,
+};
+
+/// Interceptor of the control services, rejecting callers from other hosts.
+/// Callers connected over the Unix socket are local by definition.
+///
+/// Control services listen on loopback already, this guards against them being exposed by mistake.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LocalOnly;
+
+impl Interceptor for LocalOnly {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        if request.extensions().get::<UdsConnectInfo>().is_some() {
+            return Ok(request);
+        }
+
+        match request.remote_addr() {
+            Some(remote_addr) if addr::canonical_socket_addr(remote_addr).ip().is_loopback() => {
+                Ok(request)
+            }
+            _ => Err(tonic::Status::permission_denied("local-callers-only")),
+        }
+    }
+}
+
+/// Key of the certificate the caller has presented, none if it did not present any.
+pub(crate) fn peer_public_key<T>(request: &tonic::Request<T>) -> Option<PublicKey> {
+    let certificates = request.peer_certs()?;
+    tls::certificate_public_key(certificates.first()?)
+}
+
+/// Make sure the request comes from a paired host. Returns the calling host.
+///
+/// The key is read up front, so that the returned future does not borrow the request
+/// (streaming requests are not `Sync`).
+pub(crate) fn authenticate_peer<'a, T>(
+    ctx: &'a ServerContext,
+    request: &tonic::Request<T>,
+) -> impl Future<Output = Result<HostsRow, tonic::Status>> + Send + 'a {
+    let public_key = peer_public_key(request);
+    authenticate_public_key(ctx, public_key)
+}
+
+async fn authenticate_public_key(
+    ctx: &ServerContext,
+    public_key: Option<PublicKey>,
+) -> Result<HostsRow, tonic::Status> {
+    let public_key =
+        public_key.ok_or_else(|| tonic::Status::unauthenticated("missing-client-certificate"))?;
+
+    let host = ctx
+        .repo
+        .fetch_host_by_public_key(&hex::encode(public_key))
+        .await
+        .map_err(|err| tonic::Status::internal(err.to_string()))?
+        .ok_or_else(|| tonic::Status::permission_denied("unknown-host"))?;
+    if !host.paired {
+        return Err(tonic::Status::permission_denied("host-not-paired"));
+    }
+
+    Ok(host)
+}
+
+/// Make sure the host has been granted `required` access to the local file, directly, through
+/// a group or through a sync root. The local host has access to all files.
+pub(crate) async fn authorize_file_access(
+    ctx: &ServerContext,
+    host: &HostsRow,
+    file_path: &str,
+    required: Access,
+) -> Result<(), tonic::Status> {
+    if !host.is_remote {
+        return Ok(());
+    }
+
+    // Access is granted to paths under shared roots as well, `..` must not lead out of them.
+    let is_normalized = Path::new(file_path)
+        .components()
+        .all(|component| matches!(component, Component::RootDir | Component::Normal(_)));
+    if !Path::new(file_path).is_absolute() || !is_normalized {
+        return Err(tonic::Status::invalid_argument("path-not-normalized"));
+    }
+
+    let granted = ctx
+        .repo
+        .fetch_file_access(file_path, &host.uuid)
+        .await
+        .map_err(|err| tonic::Status::internal(err.to_string()))?;
+
+    if required.read && !granted.read {
+        return Err(tonic::Status::permission_denied("no-read-access"));
+    }
+    if required.write && !granted.write {
+        return Err(tonic::Status::permission_denied("no-write-access"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+
+    use tonic::transport::server::TcpConnectInfo;
+
+    use super::*;
+
+    fn request_from(remote_addr: Option<&str>) -> tonic::Request<()> {
+        let mut request = tonic::Request::new(());
+        request.extensions_mut().insert(TcpConnectInfo {
+            local_addr: None,
+            remote_addr: remote_addr.map(|addr| addr.parse::<SocketAddr>().unwrap()),
+        });
+        request
+    }
+
+    #[test]
+    fn test_local_only_accepts_loopback_callers() {
+        assert!(
+            LocalOnly
+                .call(request_from(Some("127.0.0.1:40000")))
+                .is_ok()
+        );
+        assert!(LocalOnly.call(request_from(Some("[::1]:40000"))).is_ok());
+        assert!(
+            LocalOnly
+                .call(request_from(Some("[::ffff:127.0.0.1]:40000")))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_local_only_accepts_unix_socket_callers() {
+        let mut request = tonic::Request::new(());
+        request.extensions_mut().insert(UdsConnectInfo {
+            peer_addr: None,
+            peer_cred: None,
+        });
+        assert!(LocalOnly.call(request).is_ok());
+    }
+
+    #[test]
+    fn test_local_only_rejects_remote_callers() {
+        assert!(
+            LocalOnly
+                .call(request_from(Some("192.168.1.10:40000")))
+                .is_err()
+        );
+        assert!(
+            LocalOnly
+                .call(request_from(Some("[::ffff:192.168.1.10]:40000")))
+                .is_err()
+        );
+        assert!(LocalOnly.call(request_from(None)).is_err());
+    }
+}
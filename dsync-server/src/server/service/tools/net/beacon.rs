@@ -0,0 +1,378 @@
+//! Host discovery based on UDP broadcast beacons.
+//!
+//! Every daemon periodically broadcasts a small, signed datagram announcing its identity
+//! & the port its gRPC server listens on. Daemons hearing a beacon from an unknown peer
+//! run the regular `HelloThere` handshake against it. This works in networks filtering
+//! multicast & does not depend on any external binaries. Beacons are IPv4 only, as there is no
+//! broadcast in IPv6; peers found this way can still be reached over IPv6 through other backends.
+//!
+//! Wire format: `MAGIC (4B) | HMAC-SHA256 tag (32B) | JSON payload`.
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::{net::UdpSocket, sync::Mutex};
+
+use crate::server::{
+    config::DiscoveryConfig,
+    context::ServerContext,
+    service::tools::{
+        self,
+        net::{
+            addr_discovery::{DiscoveryBackend, retain_on_interfaces},
+            handshake::{HELLO_TIMEOUT, remember_host, say_hello},
+        },
+    },
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const BEACON_MAGIC: &[u8; 4] = b"DSBC";
+const TAG_LEN: usize = 32;
+const MAX_BEACON_LEN: usize = 1024;
+
+/// How long `discover` waits for answers after broadcasting a query.
+const QUERY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Peers which have not been heard of for this many beacon intervals are considered gone.
+const EXPIRY_INTERVALS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BeaconKind {
+    /// Periodic "I'm here" message.
+    Announce,
+    /// Asks all daemons to announce themselves immediately.
+    Query,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Beacon {
+    pub kind: BeaconKind,
+    pub uuid: String,
+    pub name: String,
+    /// Port the gRPC server of the sender listens on.
+    pub port: u16,
+    pub protocol_version: u32,
+}
+
+impl Beacon {
+    pub(crate) fn encode(&self, secret: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let payload = serde_json::to_vec(self)?;
+
+        let mut mac = HmacSha256::new_from_slice(secret)?;
+        mac.update(&payload);
+        let tag = mac.finalize().into_bytes();
+
+        let mut packet = Vec::with_capacity(BEACON_MAGIC.len() + TAG_LEN + payload.len());
+        packet.extend_from_slice(BEACON_MAGIC);
+        packet.extend_from_slice(&tag);
+        packet.extend_from_slice(&payload);
+        Ok(packet)
+    }
+
+    pub(crate) fn decode(packet: &[u8], secret: &[u8]) -> anyhow::Result<Self> {
+        let Some(rest) = packet.strip_prefix(BEACON_MAGIC) else {
+            anyhow::bail!("Not a beacon - magic mismatch");
+        };
+
+        if rest.len() <= TAG_LEN {
+            anyhow::bail!("Beacon too short");
+        }
+
+        let (tag, payload) = rest.split_at(TAG_LEN);
+
+        let mut mac = HmacSha256::new_from_slice(secret)?;
+        mac.update(payload);
+        mac.verify_slice(tag)
+            .map_err(|_| anyhow::anyhow!("Beacon signature mismatch"))?;
+
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SeenPeer {
+    addr: SocketAddr,
+    last_seen: Instant,
+}
+
+/// Identity of this server, as announced in beacons.
+#[derive(Debug, Clone)]
+struct LocalIdentity {
+    uuid: String,
+    name: String,
+    port: u16,
+}
+
+impl LocalIdentity {
+    fn beacon(&self, kind: BeaconKind) -> Beacon {
+        Beacon {
+            kind,
+            uuid: self.uuid.clone(),
+            name: self.name.clone(),
+            port: self.port,
+            protocol_version: dsync_shared::PROTOCOL_VERSION,
+        }
+    }
+}
+
+pub(crate) struct BroadcastBeaconBackend {
+    cfg: DiscoveryConfig,
+    socket: OnceLock<Arc<UdpSocket>>,
+    identity: OnceLock<LocalIdentity>,
+    seen: Arc<Mutex<HashMap<String, SeenPeer>>>,
+}
+
+impl BroadcastBeaconBackend {
+    pub(crate) fn new(cfg: DiscoveryConfig) -> Self {
+        Self {
+            cfg,
+            socket: OnceLock::new(),
+            identity: OnceLock::new(),
+            seen: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn broadcast_addr(&self) -> SocketAddr {
+        SocketAddrV4::new(Ipv4Addr::BROADCAST, self.cfg.beacon_port).into()
+    }
+
+    fn bind_socket(&self) -> anyhow::Result<UdpSocket> {
+        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, self.cfg.beacon_port);
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+        // Allows several daemons on single machine to receive the broadcasts.
+        socket.set_reuse_address(true)?;
+        socket.set_broadcast(true)?;
+        socket.set_nonblocking(true)?;
+        socket.bind(&bind_addr.into())?;
+        Ok(UdpSocket::from_std(socket.into())?)
+    }
+
+    async fn send_beacon(
+        socket: &UdpSocket,
+        beacon: &Beacon,
+        secret: &[u8],
+        target: SocketAddr,
+    ) -> anyhow::Result<()> {
+        let packet = beacon.encode(secret)?;
+        socket.send_to(&packet, target).await?;
+        Ok(())
+    }
+
+    async fn announce_loop(
+        socket: Arc<UdpSocket>,
+        identity: LocalIdentity,
+        cfg: DiscoveryConfig,
+        target: SocketAddr,
+    ) {
+        let beacon = identity.beacon(BeaconKind::Announce);
+        let mut interval = tokio::time::interval(cfg.beacon_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(err) =
+                Self::send_beacon(&socket, &beacon, cfg.beacon_secret.as_bytes(), target).await
+            {
+                log::warn!(target: "pslog", "Failed to broadcast beacon: {err}");
+            }
+        }
+    }
+
+    async fn receive_loop(
+        socket: Arc<UdpSocket>,
+        identity: LocalIdentity,
+        cfg: DiscoveryConfig,
+        seen: Arc<Mutex<HashMap<String, SeenPeer>>>,
+        ctx: Arc<ServerContext>,
+    ) {
+        let mut buffer = vec![0u8; MAX_BEACON_LEN];
+
+        loop {
+            let (len, src) = match socket.recv_from(&mut buffer).await {
+                Ok(result) => result,
+                Err(err) => {
+                    log::warn!(target: "pslog", "Failed to receive beacon: {err}");
+                    continue;
+                }
+            };
+
+            let beacon = match Beacon::decode(&buffer[..len], cfg.beacon_secret.as_bytes()) {
+                Ok(beacon) => beacon,
+                Err(err) => {
+                    log::trace!(target: "pslog", "Dropping datagram from {src}: {err}");
+                    continue;
+                }
+            };
+
+            if beacon.uuid == identity.uuid {
+                continue;
+            }
+
+            log::trace!(target: "pslog", "Received beacon {beacon:?} from {src}");
+
+            if beacon.kind == BeaconKind::Query {
+                let answer = identity.beacon(BeaconKind::Announce);
+                if let Err(err) =
+                    Self::send_beacon(&socket, &answer, cfg.beacon_secret.as_bytes(), src).await
+                {
+                    log::warn!(target: "pslog", "Failed to answer beacon query from {src}: {err}");
+                }
+            }
+
+            let peer_addr = SocketAddr::new(src.ip(), beacon.port);
+            match retain_on_interfaces(vec![peer_addr], &cfg.interfaces) {
+                Ok(kept) if kept.is_empty() => {
+                    log::trace!(target: "pslog", "Ignoring beacon from {src}, not on discovery interfaces");
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log::warn!(target: "pslog", "Failed to list network interfaces: {err}");
+                    continue;
+                }
+            }
+            let is_new_addr = {
+                let mut seen_guard = seen.lock().await;
+                let previous = seen_guard.insert(
+                    beacon.uuid.clone(),
+                    SeenPeer {
+                        addr: peer_addr,
+                        last_seen: Instant::now(),
+                    },
+                );
+                previous.is_none_or(|peer| peer.addr != peer_addr)
+            };
+
+            if is_new_addr {
+                tokio::spawn(Self::handshake_if_changed(ctx.clone(), beacon, peer_addr));
+            }
+        }
+    }
+
+    /// Handshake with the beacon sender, unless it is a known host at its current address.
+    /// Known hosts are verified again on address change, before the address is updated.
+    async fn handshake_if_changed(ctx: Arc<ServerContext>, beacon: Beacon, addr: SocketAddr) {
+        match ctx.repo.fetch_host_by_uuid(&beacon.uuid).await {
+            Ok(host) if host.socket_addr().is_ok_and(|known| known == addr) => return,
+            Ok(_) => {
+                log::info!(target: "pslog", "Beacon from known peer {} at new address {addr}", beacon.name)
+            }
+            Err(_) => {
+                log::info!(target: "pslog", "Beacon from unknown peer {} at {addr}", beacon.name)
+            }
+        }
+
+        match say_hello(&ctx, addr, HELLO_TIMEOUT).await {
+            Ok((host_info, public_key)) => {
+                let seen_time = tools::time::get_current_timestamp();
+                if let Err(err) = remember_host(&ctx, &host_info, &public_key, seen_time).await {
+                    log::error!(target: "pslog", "Failed to save host discovered by beacon: {err}");
+                }
+            }
+            Err(err) => {
+                log::warn!(target: "pslog", "Handshake with beacon sender at {addr} failed: {err}");
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DiscoveryBackend for BroadcastBeaconBackend {
+    async fn start(&self, ctx: Arc<ServerContext>) -> anyhow::Result<()> {
+        let local_info = ctx.repo.fetch_local_server_info().await?;
+        let identity = LocalIdentity {
+            uuid: local_info.uuid,
+            name: local_info.name,
+            port: ctx.cfg.port,
+        };
+
+        let socket = Arc::new(self.bind_socket()?);
+        log::info!(target: "pslog", "Beacon discovery listening on {:?}", socket.local_addr());
+
+        let _ = self.socket.set(socket.clone());
+        let _ = self.identity.set(identity.clone());
+
+        tokio::spawn(Self::announce_loop(
+            socket.clone(),
+            identity.clone(),
+            self.cfg.clone(),
+            self.broadcast_addr(),
+        ));
+        tokio::spawn(Self::receive_loop(
+            socket,
+            identity,
+            self.cfg.clone(),
+            self.seen.clone(),
+            ctx,
+        ));
+
+        Ok(())
+    }
+
+    async fn discover(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        let (Some(socket), Some(identity)) = (self.socket.get(), self.identity.get()) else {
+            anyhow::bail!("Beacon discovery has not been started");
+        };
+
+        Self::send_beacon(
+            socket,
+            &identity.beacon(BeaconKind::Query),
+            self.cfg.beacon_secret.as_bytes(),
+            self.broadcast_addr(),
+        )
+        .await?;
+
+        tokio::time::sleep(QUERY_WINDOW).await;
+
+        let max_age = self.cfg.beacon_interval * EXPIRY_INTERVALS;
+        let mut seen_guard = self.seen.lock().await;
+        seen_guard.retain(|_, peer| peer.last_seen.elapsed() <= max_age);
+
+        Ok(seen_guard.values().map(|peer| peer.addr).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_beacon() -> Beacon {
+        Beacon {
+            kind: BeaconKind::Announce,
+            uuid: "uuid".to_string(),
+            name: "name".to_string(),
+            port: 50051,
+            protocol_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_beacon_roundtrip() {
+        let beacon = sample_beacon();
+        let packet = beacon.encode(b"secret").unwrap();
+        assert_eq!(Beacon::decode(&packet, b"secret").unwrap(), beacon);
+    }
+
+    #[test]
+    fn test_beacon_rejects_wrong_secret() {
+        let packet = sample_beacon().encode(b"secret").unwrap();
+        assert!(Beacon::decode(&packet, b"other-secret").is_err());
+    }
+
+    #[test]
+    fn test_beacon_rejects_tampered_payload() {
+        let mut packet = sample_beacon().encode(b"secret").unwrap();
+        let last = packet.len() - 2;
+        packet[last] ^= 0x01;
+        assert!(Beacon::decode(&packet, b"secret").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use dsync_proto::{
+    model::server::{DaemonVersion, HostInfo},
+    services::host_discovery::{
+        HelloThereRequest, host_discovery_service_client::HostDiscoveryServiceClient,
+    },
+};
+use dsync_shared::{
+    conn::{
+        ChannelFactory,
+        tls::{PeerVerification, PublicKey},
+    },
+    protocol::{self, capability},
+};
+use tokio::{
+    sync::{Mutex, Semaphore, mpsc},
+    task::JoinSet,
+};
+
+use crate::server::{
+    blocklist,
+    context::ServerContext,
+    data::source::sqlite::database::models::{HostsRow, HostsVersionFragmentUpdate},
+    service::tools::{self, net::addr},
+};
+
+/// How long do we wait for a peer to accept the connection during handshake.
+pub(crate) const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reasons for refusing to talk to a peer.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum IncompatiblePeerError {
+    #[error(
+        "peer does not report its version, it predates protocol version negotiation; \
+         this daemon supports protocol versions {min}..={max}"
+    )]
+    Unversioned { min: u32, max: u32 },
+    #[error("peer speaks protocol version {peer}, this daemon supports versions {min}..={max}")]
+    ProtocolVersion { peer: u32, min: u32, max: u32 },
+    #[error("peer shares no hash algorithm with this daemon (peer: {peer:?}, ours: {ours:?})")]
+    NoCommonHash {
+        peer: Vec<String>,
+        ours: Vec<String>,
+    },
+}
+
+/// Version of this daemon, as announced during handshake.
+pub(crate) fn local_daemon_version() -> DaemonVersion {
+    DaemonVersion {
+        protocol_version: protocol::PROTOCOL_VERSION,
+        build_version: env!("CARGO_PKG_VERSION").to_owned(),
+        capabilities: protocol::CAPABILITIES
+            .iter()
+            .map(|capability| capability.to_string())
+            .collect(),
+    }
+}
+
+/// Check whether we can talk to a peer reporting `peer_version`.
+pub(crate) fn check_compatibility(
+    peer_version: Option<&DaemonVersion>,
+) -> Result<(), IncompatiblePeerError> {
+    let (min, max) = (
+        protocol::MIN_SUPPORTED_PROTOCOL_VERSION,
+        protocol::PROTOCOL_VERSION,
+    );
+
+    let Some(peer_version) = peer_version else {
+        return Err(IncompatiblePeerError::Unversioned { min, max });
+    };
+
+    if !(min..=max).contains(&peer_version.protocol_version) {
+        return Err(IncompatiblePeerError::ProtocolVersion {
+            peer: peer_version.protocol_version,
+            min,
+            max,
+        });
+    }
+
+    let is_hash = |capability: &&str| capability.starts_with(capability::HASH_PREFIX);
+    let ours: Vec<&str> = protocol::CAPABILITIES
+        .iter()
+        .copied()
+        .filter(is_hash)
+        .collect();
+    let peer: Vec<&str> = peer_version
+        .capabilities
+        .iter()
+        .map(String::as_str)
+        .filter(is_hash)
+        .collect();
+
+    if !peer.iter().any(|hash| ours.contains(hash)) {
+        return Err(IncompatiblePeerError::NoCommonHash {
+            peer: peer.into_iter().map(str::to_owned).collect(),
+            ours: ours.into_iter().map(str::to_owned).collect(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Run `HelloThere` handshake against a daemon listening at `remote_addr`.
+///
+/// On success returns host info of the remote, with the address filled in
+/// (peers never send their own address), along with the key the remote has presented.
+/// The key is checked against the pinned one, if the remote is known already.
+pub(crate) async fn say_hello(
+    ctx: &ServerContext,
+    remote_addr: SocketAddr,
+    timeout: Duration,
+) -> anyhow::Result<(HostInfo, PublicKey)> {
+    blocklist::ensure_not_blocked(ctx, None, Some(remote_addr.ip())).await?;
+
+    let (channel, public_key) = ChannelFactory::peer_channel(
+        remote_addr,
+        ctx.identity.tls(),
+        PeerVerification::TrustOnFirstUse,
+        timeout,
+    )
+    .await?;
+    let mut client_conn = HostDiscoveryServiceClient::new(channel);
+
+    let server_info = ctx.repo.fetch_local_server_info().await?;
+
+    let request = tonic::Request::new(HelloThereRequest {
+        host_info: Some(HostInfo {
+            uuid: server_info.uuid,
+            name: server_info.name,
+            hostname: server_info.hostname,
+            address: "".to_owned(),
+            port: ctx.cfg.port.into(),
+            version: Some(local_daemon_version()),
+            ..Default::default()
+        }),
+    });
+
+    let response = client_conn.hello_there(request).await?.into_inner();
+
+    let Some(mut remote_server_info) = response.host_info else {
+        anyhow::bail!("Invalid response from peer at {remote_addr}, host info must not be none");
+    };
+
+    if !remote_server_info.address.is_empty() {
+        anyhow::bail!("Unexpected payload from peer at {remote_addr}, expected empty address");
+    }
+
+    if let Err(err) = check_compatibility(remote_server_info.version.as_ref()) {
+        anyhow::bail!("Refusing incompatible peer at {remote_addr}: {err}");
+    }
+
+    blocklist::ensure_not_blocked(ctx, Some(&remote_server_info.uuid), None).await?;
+    check_pinned_key(ctx, &remote_server_info.uuid, &public_key).await?;
+
+    // Fill up the address, because we actually have this information here
+    remote_server_info.address = addr::format_ip(&remote_addr);
+    remote_server_info.port = remote_addr.port().into();
+
+    Ok((remote_server_info, public_key))
+}
+
+/// Make sure a known host presents the key pinned on the first contact.
+/// Unknown hosts & hosts with no key pinned yet pass.
+pub(crate) async fn check_pinned_key(
+    ctx: &ServerContext,
+    host_uuid: &str,
+    public_key: &PublicKey,
+) -> anyhow::Result<()> {
+    let Some(host) = ctx.repo.find_host_by_uuid(host_uuid).await? else {
+        return Ok(());
+    };
+
+    match host.public_key {
+        Some(pinned_key) if pinned_key != hex::encode(public_key) => anyhow::bail!(
+            "Host {host_uuid} presented key {}, but {pinned_key} is pinned; \
+             remove the host with `dsync-cli host remove` to accept the new key",
+            hex::encode(public_key)
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Save the host, if it is not known yet, & record the address it has just been seen at
+/// as its current one. The key of the host gets pinned, unless some key is pinned already.
+pub(crate) async fn remember_host(
+    ctx: &ServerContext,
+    host_info: &HostInfo,
+    public_key: &PublicKey,
+    seen_time: i64,
+) -> anyhow::Result<()> {
+    let host_row = HostsRow::from_host_info(host_info, true, Some(seen_time));
+    let port = u16::try_from(host_row.port)?;
+
+    ctx.repo.insert_hosts(&[host_row]).await?;
+    ctx.repo
+        .pin_host_public_key(&host_info.uuid, &hex::encode(public_key))
+        .await?;
+    ctx.repo
+        .update_host_addr(&host_info.uuid, &host_info.address, port, seen_time)
+        .await?;
+
+    // Peer might have been upgraded since we have last seen it.
+    if let Some(version) = &host_info.version {
+        ctx.repo
+            .update_host_version(
+                &host_info.uuid,
+                &HostsVersionFragmentUpdate::from_daemon_version(version),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Run handshakes with all `candidates`, at most `concurrency` of them at once.
+///
+/// Every host which answers is cached in the repository & then sent through the returned
+/// channel, as soon as its handshake completes. The channel is closed once all handshakes finish.
+///
+/// Hosts answering on several addresses are reported once. The cached address is switched
+/// to the preferred one (see [`addr::preference`]) as more handshakes complete.
+pub(crate) fn say_hello_all(
+    ctx: Arc<ServerContext>,
+    candidates: Vec<SocketAddr>,
+    concurrency: usize,
+) -> mpsc::Receiver<HostInfo> {
+    let concurrency = concurrency.max(1);
+    let (tx, rx) = mpsc::channel(concurrency);
+
+    tokio::spawn(async move {
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let discovery_time = tools::time::get_current_timestamp();
+        let answered: Arc<Mutex<HashMap<String, SocketAddr>>> = Arc::default();
+        let mut handshakes = JoinSet::new();
+
+        for addr in candidates {
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed");
+            let ctx = ctx.clone();
+            let tx = tx.clone();
+            let answered = answered.clone();
+
+            handshakes.spawn(async move {
+                let _permit = permit;
+                let (host_info, public_key) = match say_hello(&ctx, addr, HELLO_TIMEOUT).await {
+                    Ok(answer) => answer,
+                    Err(err) => {
+                        log::trace!(target: "pslog", "Have not found deamon at {addr}: {err}");
+                        return;
+                    }
+                };
+
+                let mut answered_guard = answered.lock().await;
+                if let Some(known_addr) = answered_guard.get(&host_info.uuid) {
+                    if addr::preference(&addr) > addr::preference(known_addr) {
+                        answered_guard.insert(host_info.uuid.clone(), addr);
+                        if let Err(err) = ctx
+                            .repo
+                            .update_host_addr(
+                                &host_info.uuid,
+                                &host_info.address,
+                                addr.port(),
+                                discovery_time,
+                            )
+                            .await
+                        {
+                            log::warn!(target: "pslog", "Failed to update host address: {err}");
+                        }
+                    }
+                    return;
+                }
+                answered_guard.insert(host_info.uuid.clone(), addr);
+
+                if let Err(err) = remember_host(&ctx, &host_info, &public_key, discovery_time).await
+                {
+                    log::warn!(target: "pslog", "Failed to cache discovered host: {err}");
+                }
+                drop(answered_guard);
+
+                // Receiver might be gone (e.g. client disconnected), the host is cached anyway.
+                let _ = tx.send(host_info).await;
+            });
+        }
+
+        while handshakes.join_next().await.is_some() {}
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compatibility_with_self() {
+        assert_eq!(check_compatibility(Some(&local_daemon_version())), Ok(()));
+    }
+
+    #[test]
+    fn test_compatibility_refuses_unversioned_peer() {
+        assert!(matches!(
+            check_compatibility(None),
+            Err(IncompatiblePeerError::Unversioned { .. })
+        ));
+    }
+
+    #[test]
+    fn test_compatibility_refuses_newer_protocol() {
+        let version = DaemonVersion {
+            protocol_version: protocol::PROTOCOL_VERSION + 1,
+            ..local_daemon_version()
+        };
+        assert!(matches!(
+            check_compatibility(Some(&version)),
+            Err(IncompatiblePeerError::ProtocolVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_compatibility_requires_common_hash() {
+        let version = DaemonVersion {
+            capabilities: vec!["hash:blake3".to_owned(), "delta-transfer".to_owned()],
+            ..local_daemon_version()
+        };
+        assert!(matches!(
+            check_compatibility(Some(&version)),
+            Err(IncompatiblePeerError::NoCommonHash { .. })
+        ));
+    }
+}
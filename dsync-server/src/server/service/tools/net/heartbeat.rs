@@ -0,0 +1,140 @@
+//! Periodic liveness check of known peers.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use dsync_proto::{
+    model::server::HostInfo,
+    services::host_discovery::{
+        PingRequest, host_discovery_service_client::HostDiscoveryServiceClient,
+    },
+};
+use tokio::{task::JoinSet, time::MissedTickBehavior};
+
+use crate::server::{
+    context::ServerContext,
+    service::tools::{self, net::peer},
+};
+
+/// How long a single ping (connection included) might take before the peer is considered offline.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ping all known peers every `heartbeat_interval`, recording results in the peer registry.
+pub(crate) fn spawn_heartbeat(ctx: Arc<ServerContext>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ctx.cfg.heartbeat_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if let Err(err) = beat(&ctx).await {
+                log::warn!(target: "pslog", "Heartbeat failed: {err}");
+            }
+        }
+    });
+}
+
+async fn beat(ctx: &Arc<ServerContext>) -> anyhow::Result<()> {
+    let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+    let peers: Vec<HostInfo> = ctx
+        .repo
+        .fetch_hosts()
+        .await?
+        .into_iter()
+        .filter(|host| host.uuid != local_uuid)
+        .collect();
+
+    let mut pings = JoinSet::new();
+    for host in peers {
+        let ctx = ctx.clone();
+        let local_uuid = local_uuid.clone();
+
+        pings.spawn(async move {
+            match ping(&ctx, &host, local_uuid).await {
+                Ok(latency) => {
+                    ctx.peers.record_success(
+                        &host.uuid,
+                        latency,
+                        tools::time::get_current_timestamp(),
+                    );
+                }
+                Err(err) => {
+                    log::trace!(target: "pslog", "Peer {} did not answer the heartbeat: {err}", host.uuid);
+                    ctx.peers.record_failure(&host.uuid);
+                }
+            }
+        });
+    }
+
+    while pings.join_next().await.is_some() {}
+    Ok(())
+}
+
+/// Returns round-trip time of the ping, connection establishment excluded.
+async fn ping(
+    ctx: &ServerContext,
+    host: &HostInfo,
+    local_uuid: String,
+) -> anyhow::Result<Duration> {
+    // The stored row carries the pinned key, the host has to present it.
+    let host_row = ctx.repo.fetch_host_by_uuid(&host.uuid).await?;
+    let channel = peer::connect_to_host(ctx, &host_row, PING_TIMEOUT).await?;
+    let mut client = HostDiscoveryServiceClient::new(channel);
+
+    let mut request = tonic::Request::new(PingRequest { uuid: local_uuid });
+    request.set_timeout(PING_TIMEOUT);
+
+    let started = Instant::now();
+    let response = client.ping(request).await?.into_inner();
+    let latency = started.elapsed();
+
+    // Another daemon might have taken over the address.
+    if response.uuid != host.uuid {
+        anyhow::bail!(
+            "Expected host {} at {}, but {} answered",
+            host.uuid,
+            host.address,
+            response.uuid
+        );
+    }
+
+    Ok(latency)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::{identity::Identity, test_utils};
+
+    #[tokio::test]
+    async fn test_ping_checks_pinned_key() {
+        let ctx = test_utils::test_context(test_utils::test_config()).await;
+        let peer_ctx = test_utils::host_context("peer", test_utils::test_config()).await;
+        let port = test_utils::serve_peer_services(peer_ctx.clone()).await;
+
+        let peer =
+            test_utils::add_paired_host(&ctx, "peer", port, &peer_ctx.identity.public_key_hex())
+                .await;
+        ping(
+            &ctx,
+            &peer.to_host_info(),
+            test_utils::LOCAL_UUID.to_owned(),
+        )
+        .await
+        .unwrap();
+
+        // Someone else answering at the address of the peer, with its uuid.
+        let impostor_key = Identity::generate().unwrap().public_key_hex();
+        let peer = test_utils::add_paired_host(&ctx, "peer", port, &impostor_key).await;
+        assert_eq!(peer.public_key, Some(impostor_key));
+        ping(
+            &ctx,
+            &peer.to_host_info(),
+            test_utils::LOCAL_UUID.to_owned(),
+        )
+        .await
+        .unwrap_err();
+    }
+}
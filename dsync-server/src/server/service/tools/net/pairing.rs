@@ -0,0 +1,83 @@
+//! Initiating side of the pairing. See `server::pairing` for the protocol.
+
+use std::time::Duration;
+
+use dsync_proto::{
+    model::server::HostInfo,
+    services::host_discovery::{
+        PairInitRequest, PairRevealRequest,
+        host_discovery_service_client::HostDiscoveryServiceClient,
+    },
+};
+
+use crate::server::{
+    context::ServerContext,
+    data::source::sqlite::database::models::HostsRow,
+    identity, pairing,
+    service::tools::net::{handshake, peer},
+};
+
+const PAIR_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exchange keys with a known host. Returns the verification code the user has to compare
+/// with the one shown on the host, before the pairing is confirmed.
+pub(crate) async fn pair_with_host(ctx: &ServerContext, host: &HostsRow) -> anyhow::Result<String> {
+    let (channel, tls_public_key) = peer::connect_to_host_with_key(ctx, host, PAIR_TIMEOUT).await?;
+    let mut client = HostDiscoveryServiceClient::new(channel);
+
+    let server_info = ctx.repo.fetch_local_server_info().await?;
+    let public_key = ctx.identity.public_key();
+    let nonce = pairing::random_nonce();
+
+    let response = client
+        .pair_init(PairInitRequest {
+            host_info: Some(HostInfo {
+                uuid: server_info.uuid.clone(),
+                name: server_info.name,
+                hostname: server_info.hostname,
+                address: "".to_owned(),
+                port: ctx.cfg.port.into(),
+                version: Some(handshake::local_daemon_version()),
+                ..Default::default()
+            }),
+            public_key: public_key.to_vec(),
+            commitment: pairing::commit(&public_key, &nonce),
+        })
+        .await?
+        .into_inner();
+
+    // Another daemon might have taken over the address.
+    if response.uuid != host.uuid {
+        anyhow::bail!(
+            "Expected host {} at {}, but {} answered",
+            host.uuid,
+            host.address,
+            response.uuid
+        );
+    }
+    identity::parse_public_key(&response.public_key)?;
+    // The key being paired has to be the one the host proves to hold.
+    if response.public_key != tls_public_key {
+        anyhow::bail!(
+            "Host {} sent a key other than its certificate one",
+            host.uuid
+        );
+    }
+    if response.nonce.len() != pairing::NONCE_LENGTH {
+        anyhow::bail!("Invalid nonce length: {}", response.nonce.len());
+    }
+
+    client
+        .pair_reveal(PairRevealRequest {
+            uuid: server_info.uuid,
+            nonce: nonce.to_vec(),
+        })
+        .await?;
+
+    let code =
+        pairing::verification_code(&public_key, &response.public_key, &nonce, &response.nonce);
+    ctx.pairings
+        .insert_outgoing(host.to_host_info(), response.public_key, code.clone())?;
+
+    Ok(code)
+}
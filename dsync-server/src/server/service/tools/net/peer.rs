@@ -0,0 +1,107 @@
+//! Connecting to already known peers.
+
+use std::{net::SocketAddr, time::Duration};
+
+use dsync_shared::conn::{
+    ChannelFactory,
+    tls::{PeerVerification, PublicKey},
+};
+use tonic::transport::Channel;
+
+use crate::server::{
+    blocklist,
+    context::ServerContext,
+    data::source::sqlite::database::models::HostsRow,
+    identity,
+    service::tools::{self, net::addr},
+};
+
+/// Open a channel to a known host. Its current address is tried first, then all other
+/// addresses the host has been seen at, most recently seen first.
+///
+/// The address which accepts the connection becomes the current address of the host.
+/// Blocked hosts & blocked addresses are never connected to.
+pub(crate) async fn connect_to_host(
+    ctx: &ServerContext,
+    host: &HostsRow,
+    timeout: Duration,
+) -> anyhow::Result<Channel> {
+    let (channel, _) = connect_to_host_with_key(ctx, host, timeout).await?;
+    Ok(channel)
+}
+
+/// Same as [`connect_to_host`], but returns the key the host has presented as well.
+///
+/// The key has to match the pinned one. Hosts with no key pinned yet are trusted on first use
+/// & the key they present gets pinned.
+pub(crate) async fn connect_to_host_with_key(
+    ctx: &ServerContext,
+    host: &HostsRow,
+    timeout: Duration,
+) -> anyhow::Result<(Channel, PublicKey)> {
+    blocklist::ensure_not_blocked(ctx, Some(&host.uuid), None).await?;
+
+    let verification = match host.public_key.as_deref() {
+        Some(public_key) => PeerVerification::Pinned(identity::decode_public_key(public_key)?),
+        None => PeerVerification::TrustOnFirstUse,
+    };
+
+    let mut candidates: Vec<SocketAddr> = host.socket_addr().into_iter().collect();
+    for row in ctx.repo.fetch_host_addresses(&host.uuid).await? {
+        let Ok(port) = u16::try_from(row.port) else {
+            continue;
+        };
+        match addr::parse_socket_addr(&row.address, port) {
+            Ok(candidate) if !candidates.contains(&candidate) => candidates.push(candidate),
+            Ok(_) => {}
+            Err(err) => {
+                log::warn!(target: "pslog", "Skipping invalid address {} of host {}: {err}", row.address, host.uuid);
+            }
+        }
+    }
+
+    let mut last_error = None;
+    for candidate in candidates {
+        if let Some(rule) = blocklist::find_rule(ctx, None, Some(candidate.ip())).await? {
+            log::debug!(target: "pslog", "Skipping address {candidate} of host {}, blocked by rule {rule}", host.uuid);
+            continue;
+        }
+
+        match ChannelFactory::peer_channel(candidate, ctx.identity.tls(), verification, timeout)
+            .await
+        {
+            Ok((channel, public_key)) => {
+                if verification == PeerVerification::TrustOnFirstUse {
+                    ctx.repo
+                        .pin_host_public_key(&host.uuid, &hex::encode(public_key))
+                        .await?;
+                }
+                if let Err(err) = ctx
+                    .repo
+                    .update_host_addr(
+                        &host.uuid,
+                        &addr::format_ip(&candidate),
+                        candidate.port(),
+                        tools::time::get_current_timestamp(),
+                    )
+                    .await
+                {
+                    log::warn!(target: "pslog", "Failed to refresh address of host {}: {err}", host.uuid);
+                }
+                return Ok((channel, public_key));
+            }
+            Err(err) => {
+                log::debug!(target: "pslog", "Host {} is not reachable at {candidate}: {err}", host.uuid);
+                last_error = Some(err);
+            }
+        }
+    }
+
+    match last_error {
+        Some(err) => anyhow::bail!(
+            "Host {} is not reachable at any known address, last error: {err}",
+            host.uuid
+        ),
+        None => anyhow::bail!("Host {} has no known address", host.uuid),
+    }
+}
@@ -0,0 +1,105 @@
+//! Periodic handshake with peers listed in the configuration. There is no discovery step,
+//! peers are contacted at the configured addresses directly.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::{task::JoinSet, time::MissedTickBehavior};
+
+use crate::server::{
+    config::StaticPeer,
+    context::ServerContext,
+    service::tools::{
+        self,
+        net::{
+            addr_discovery::StaticPeersAddressProvider,
+            handshake::{self, HELLO_TIMEOUT},
+        },
+    },
+};
+
+/// Say hello to all static peers on start & then every `static_peers_interval`.
+/// Does nothing when no static peers are configured.
+pub(crate) fn spawn_static_peers(ctx: Arc<ServerContext>) {
+    if ctx.cfg.discovery.static_peers.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let provider = StaticPeersAddressProvider::new(ctx.cfg.discovery.static_peers.clone());
+        let mut interval = tokio::time::interval(ctx.cfg.discovery.static_peers_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            contact_all(&ctx, &provider).await;
+        }
+    });
+}
+
+async fn contact_all(ctx: &Arc<ServerContext>, provider: &StaticPeersAddressProvider) {
+    let provider = provider.clone();
+    let port = ctx.cfg.discovery.scan_port;
+    // Resolving domain names blocks.
+    let peers = match tokio::task::spawn_blocking(move || provider.resolve_peers(port)).await {
+        Ok(peers) => peers,
+        Err(err) => {
+            log::warn!(target: "pslog", "Failed to resolve static peers: {err}");
+            return;
+        }
+    };
+
+    let mut handshakes = JoinSet::new();
+    for (peer, addrs) in peers {
+        let ctx = ctx.clone();
+        handshakes.spawn(async move {
+            if let Err(err) = contact(&ctx, &peer, &addrs, HELLO_TIMEOUT).await {
+                log::debug!(target: "pslog", "Static peer {} did not answer: {err}", peer.address);
+            }
+        });
+    }
+
+    while handshakes.join_next().await.is_some() {}
+}
+
+/// Handshake with the first address of the peer which answers & remember the peer.
+async fn contact(
+    ctx: &ServerContext,
+    peer: &StaticPeer,
+    addrs: &[std::net::SocketAddr],
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    let mut last_error = anyhow::anyhow!("No address resolved");
+
+    for addr in addrs {
+        let (host_info, public_key) = match handshake::say_hello(ctx, *addr, timeout).await {
+            Ok(answer) => answer,
+            Err(err) => {
+                last_error = err;
+                continue;
+            }
+        };
+
+        if let Some(expected_uuid) = &peer.uuid
+            && expected_uuid != &host_info.uuid
+        {
+            log::warn!(
+                target: "pslog",
+                "Static peer at {addr} answered as {}, but {expected_uuid} is expected; ignoring it",
+                host_info.uuid
+            );
+            anyhow::bail!("Unexpected uuid {} at {addr}", host_info.uuid);
+        }
+
+        handshake::remember_host(
+            ctx,
+            &host_info,
+            &public_key,
+            tools::time::get_current_timestamp(),
+        )
+        .await?;
+        log::debug!(target: "pslog", "Static peer {} answered at {addr}", host_info.uuid);
+        return Ok(());
+    }
+
+    Err(last_error)
+}
@@ -0,0 +1,185 @@
+//! In-process host discovery: enumerate subnets of local interfaces & probe every address
+//! in them with a TCP connect to the dsync port. IPv4 only: IPv6 subnets are far too large
+//! to enumerate, neighbour discovery should be used to find IPv6 peers.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
+    time::Duration,
+};
+
+use ipnet::Ipv4Net;
+use tokio::{net::TcpStream, sync::Semaphore, task::JoinSet};
+
+use crate::server::{
+    config::DiscoveryConfig, service::tools::net::addr_discovery::DiscoveryBackend,
+};
+
+/// Subnets with shorter prefix are narrowed down to /24 around the interface address,
+/// otherwise a single sweep could take hours.
+const MIN_SWEEP_PREFIX_LEN: u8 = 20;
+const NARROWED_PREFIX_LEN: u8 = 24;
+
+/// Network a local interface is attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocalNetwork {
+    pub interface: String,
+    pub local_addr: Ipv4Addr,
+    pub network: Ipv4Net,
+}
+
+/// List IPv4 networks of all non-loopback interfaces.
+pub(crate) fn local_networks() -> anyhow::Result<Vec<LocalNetwork>> {
+    let networks = if_addrs::get_if_addrs()?
+        .into_iter()
+        .filter(|iface| !iface.is_loopback())
+        .filter_map(|iface| match iface.addr {
+            if_addrs::IfAddr::V4(addr) => Some(LocalNetwork {
+                interface: iface.name,
+                local_addr: addr.ip,
+                network: Ipv4Net::new(addr.ip, addr.prefixlen).ok()?.trunc(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Ok(networks)
+}
+
+/// Limit the network to a reasonable size, keeping `local_addr` inside.
+fn narrow_network(local_addr: Ipv4Addr, network: Ipv4Net) -> Ipv4Net {
+    if network.prefix_len() >= MIN_SWEEP_PREFIX_LEN {
+        return network;
+    }
+
+    let narrowed = Ipv4Net::new(local_addr, NARROWED_PREFIX_LEN)
+        .expect("Prefix length is valid")
+        .trunc();
+    log::info!(target: "pslog", "Network {network} is too large to sweep, narrowing down to {narrowed}");
+    narrowed
+}
+
+/// Build the list of addresses to probe. Skips the addresses of this very host.
+fn sweep_targets(networks: &[Ipv4Net], own_addrs: &[Ipv4Addr], port: u16) -> Vec<SocketAddrV4> {
+    let mut targets: Vec<SocketAddrV4> = networks
+        .iter()
+        .flat_map(|network| network.hosts())
+        .filter(|addr| !own_addrs.contains(addr))
+        .map(|addr| SocketAddrV4::new(addr, port))
+        .collect();
+
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+/// Try to connect to every target, running at most `concurrency` probes at once.
+/// Returns targets which accepted the connection.
+pub(crate) async fn probe_tcp(
+    targets: Vec<SocketAddr>,
+    timeout: Duration,
+    concurrency: usize,
+) -> Vec<SocketAddr> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut probes = JoinSet::new();
+
+    for target in targets {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Semaphore is never closed");
+
+        probes.spawn(async move {
+            let _permit = permit;
+            match tokio::time::timeout(timeout, TcpStream::connect(target)).await {
+                Ok(Ok(_)) => Some(target),
+                _ => None,
+            }
+        });
+    }
+
+    let mut responsive = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(Some(target)) = result {
+            responsive.push(target);
+        }
+    }
+
+    responsive
+}
+
+/// Finds peers by connecting to the dsync port of every address in the local subnets.
+/// Does not depend on any external binaries.
+#[derive(Debug, Clone)]
+pub(crate) struct TcpSweepDiscoveryBackend {
+    cfg: DiscoveryConfig,
+}
+
+impl TcpSweepDiscoveryBackend {
+    pub(crate) fn new(cfg: DiscoveryConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+#[async_trait::async_trait]
+impl DiscoveryBackend for TcpSweepDiscoveryBackend {
+    async fn discover(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        let local_networks = local_networks()?;
+        let own_addrs: Vec<Ipv4Addr> = local_networks.iter().map(|net| net.local_addr).collect();
+
+        let networks: Vec<Ipv4Net> = if self.cfg.scan_subnets.is_empty() {
+            local_networks
+                .iter()
+                .filter(|net| {
+                    self.cfg.interfaces.is_empty() || self.cfg.interfaces.contains(&net.interface)
+                })
+                .map(|net| narrow_network(net.local_addr, net.network))
+                .collect()
+        } else {
+            self.cfg.scan_subnets.clone()
+        };
+
+        let targets: Vec<SocketAddr> = sweep_targets(&networks, &own_addrs, self.cfg.scan_port)
+            .into_iter()
+            .map(SocketAddr::V4)
+            .collect();
+        log::info!(target: "pslog", "Sweeping {} addresses in {networks:?}", targets.len());
+
+        Ok(probe_tcp(targets, self.cfg.scan_timeout, self.cfg.scan_concurrency).await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_narrow_network_keeps_small_networks() {
+        let network: Ipv4Net = "192.168.1.0/24".parse().unwrap();
+        assert_eq!(
+            narrow_network(Ipv4Addr::new(192, 168, 1, 10), network),
+            network
+        );
+    }
+
+    #[test]
+    fn test_narrow_network_shrinks_large_networks() {
+        let network: Ipv4Net = "10.0.0.0/8".parse().unwrap();
+        assert_eq!(
+            narrow_network(Ipv4Addr::new(10, 1, 2, 3), network),
+            "10.1.2.0/24".parse::<Ipv4Net>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sweep_targets_skips_own_addresses() {
+        let network: Ipv4Net = "192.168.1.0/30".parse().unwrap();
+        let own = Ipv4Addr::new(192, 168, 1, 1);
+        let targets = sweep_targets(&[network, network], &[own], 50051);
+        assert_eq!(
+            targets,
+            vec![SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 2), 50051)]
+        );
+    }
+}
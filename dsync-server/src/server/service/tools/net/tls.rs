@@ -0,0 +1,63 @@
+//! Accepting TLS connections for the gRPC server.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{TlsAcceptor, server::TlsStream};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// How long might a client take to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections accepted on `listener`, past the TLS handshake.
+///
+/// Handshakes run concurrently, so that a slow (or malicious) client does not hold up
+/// the others. Failed handshakes are logged & dropped.
+pub(crate) fn incoming(
+    listener: TcpListener,
+    config: rustls::ServerConfig,
+) -> ReceiverStream<std::io::Result<TlsStream<TcpStream>>> {
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    if tx.send(Err(err)).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            if tx.is_closed() {
+                break;
+            }
+
+            let acceptor = acceptor.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                if let Err(err) = stream.set_nodelay(true) {
+                    log::debug!(target: "pslog", "Failed to set TCP_NODELAY for {peer_addr}: {err}");
+                }
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => {
+                        let _ = tx.send(Ok(stream)).await;
+                    }
+                    Ok(Err(err)) => {
+                        log::debug!(target: "pslog", "TLS handshake with {peer_addr} failed: {err}");
+                    }
+                    Err(_) => {
+                        log::debug!(target: "pslog", "TLS handshake with {peer_addr} timed out");
+                    }
+                }
+            });
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
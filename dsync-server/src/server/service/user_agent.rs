@@ -1,50 +1,273 @@
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::str::FromStr;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::server::config::defaults;
 use crate::server::data::source::sqlite::database::error::{
     DeleteLocalGroupError, FileAddError, SaveLocalGroupError,
 };
-use crate::server::data::source::sqlite::database::models::{FilesLocalFragmentInsert, HostsRow};
+use crate::server::data::source::sqlite::database::models::{
+    Access, FileConflictRow, FilesLocalFragmentInsert, FilesLocalRow, FilesTrackedRow,
+    HostsMetadataFragmentUpdate, HostsRow, RootsTrackedRow, SyncRootRow,
+};
 use crate::server::service::tools;
+use crate::server::service::tools::net::{addr, addr_discovery, handshake, pairing, peer};
 
-use anyhow::Context;
-use dsync_proto::model::common::LocalFileDescription;
+use dsync_proto::model::common::{LocalFileDescription, SyncRootDescription};
 use dsync_proto::model::server::HostInfo;
 use dsync_proto::services::user_agent::{
-    HostAddRequest, HostAddResponse, HostRemoveRequest, HostRemoveResponse, HostSpec, host_spec,
+    ConflictListRequest, ConflictListResponse, ConflictResolveRequest, ConflictResolveResponse,
+    ConflictShowRequest, ConflictShowResponse, FileCheckIgnoreRequest, FileCheckIgnoreResponse,
+    FileConflict, FileRescanRequest, FileRescanResponse, FileShareRequest, FileShareResponse,
+    FileSyncRequest, FileSyncResponse, FileUnsyncRequest, FileUnsyncResponse, GroupShareRequest,
+    GroupShareResponse, GroupStrategyRequest, GroupStrategyResponse, HostAddRequest,
+    HostAddResponse, HostAliasRequest, HostAliasResponse, HostBlockRequest, HostBlockResponse,
+    HostBlocklistRequest, HostBlocklistResponse, HostPairConfirmRequest, HostPairConfirmResponse,
+    HostPairPendingRequest, HostPairPendingResponse, HostPairRequest, HostPairResponse,
+    HostRemoveRequest, HostRemoveResponse, HostSpec, HostUpdateRequest, HostUpdateResponse,
+    IgnoreRule, PendingPairing, RootAddRequest, RootAddResponse, RootRemoveRequest,
+    RootRemoveResponse, RootShareRequest, RootShareResponse, RootSyncRequest, RootSyncResponse,
+    RootUnsyncRequest, RootUnsyncResponse, host_spec,
 };
 use dsync_proto::services::{
     file_transfer::{
-        TransferSubmitRequest, file_transfer_service_client::FileTransferServiceClient,
-    },
-    host_discovery::{
-        HelloThereRequest, host_discovery_service_client::HostDiscoveryServiceClient,
+        FileDescribeRequest, RootDescribeRequest, TransferSubmitRequest,
+        file_transfer_service_client::FileTransferServiceClient,
     },
     user_agent::{
         FileAddRequest, FileAddResponse, FileCopyRequest, FileCopyResponse, FileListRequest,
         FileListResponse, FileRemoveRequest, FileRemoveResponse, GroupCreateRequest,
         GroupCreateResponse, GroupDeleteRequest, GroupDeleteResponse, GroupListRequest,
-        GroupListResponse, HostDiscoverRequest, HostDiscoverResponse, HostListRequest,
-        HostListResponse, user_agent_service_server::UserAgentService,
+        GroupListResponse, HostDiscoverRequest, HostDiscoverResponse, HostDiscoverStreamResponse,
+        HostListRequest, HostListResponse, user_agent_service_server::UserAgentService,
     },
 };
 use dsync_shared::DEFAULT_SERVER_PORT;
-use dsync_shared::conn::{ChannelFactory, create_server_uri};
-use dsync_shared::model::FileSourceWrapper;
-use tonic::transport::Uri;
+use dsync_shared::model::{FileSourceWrapper, validate_host_name};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status};
 
+use crate::server::config::BlockRule;
+use crate::server::conflict::{self, ConflictStrategy, Resolution};
 use crate::server::context::ServerContext;
+use crate::server::ignore_rules::RootIgnore;
+use crate::server::index;
+use crate::server::pairing::PairingStage;
+use crate::server::version_vector::VersionVector;
 
 pub struct UserAgentServiceImpl {
     ctx: Arc<ServerContext>,
 }
 
 impl UserAgentServiceImpl {
+    /// Remote files every local file is synced with, as `HOST@REMOTE_ID`, keyed by local file id.
+    async fn synced_with(&self) -> Result<HashMap<i32, Vec<String>>, Status> {
+        let tracked_files = self
+            .ctx
+            .repo
+            .fetch_tracked_files()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let hosts = self
+            .ctx
+            .repo
+            .fetch_hosts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let mut synced_with: HashMap<i32, Vec<String>> = HashMap::new();
+        for tracked in tracked_files {
+            let host_name = hosts
+                .iter()
+                .find(|host| host.uuid == tracked.peer_uuid)
+                .map_or(tracked.peer_uuid.as_str(), |host| host.name.as_str());
+            synced_with
+                .entry(tracked.local_id)
+                .or_default()
+                .push(format!("{host_name}@{}", tracked.remote_id));
+        }
+
+        Ok(synced_with)
+    }
+
+    /// Remote roots every local root is synced with, as `HOST@REMOTE_ROOT_ID`, keyed by root id.
+    async fn roots_synced_with(&self) -> Result<HashMap<i32, Vec<String>>, Status> {
+        let tracked_roots = self
+            .ctx
+            .repo
+            .fetch_tracked_roots()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let hosts = self
+            .ctx
+            .repo
+            .fetch_hosts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let mut synced_with: HashMap<i32, Vec<String>> = HashMap::new();
+        for tracked in tracked_roots {
+            let host_name = hosts
+                .iter()
+                .find(|host| host.uuid == tracked.peer_uuid)
+                .map_or(tracked.peer_uuid.as_str(), |host| host.name.as_str());
+            synced_with
+                .entry(tracked.root_id)
+                .or_default()
+                .push(format!("{host_name}@{}", tracked.remote_root_id));
+        }
+
+        Ok(synced_with)
+    }
+
+    /// Roots of a remote host, which it has shared with this host.
+    async fn remote_roots(&self, host_spec: &HostSpec) -> Result<Vec<SyncRootDescription>, Status> {
+        let host_info = self
+            .resolve_host_info_by_spec(host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+
+        let channel = peer::connect_to_host(&self.ctx, &host_info, Duration::from_secs(5))
+            .await
+            .map_err(|err| Status::unavailable(format!("Failed to connect to the host - {err}")))?;
+        let response = FileTransferServiceClient::new(channel)
+            .root_list(dsync_proto::services::file_transfer::RootListRequest {})
+            .await?;
+
+        Ok(response.into_inner().roots)
+    }
+
+    async fn ensure_root_exists(&self, root_id: i32) -> Result<(), Status> {
+        let roots = self
+            .ctx
+            .repo
+            .fetch_sync_roots()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if !roots.iter().any(|root| root.id == root_id) {
+            return Err(Status::not_found(format!("No sync root with id {root_id}")));
+        }
+        Ok(())
+    }
+
+    /// Conflict of the local file with the host, or with the only host it conflicts with.
+    async fn find_conflict(
+        &self,
+        local_id: i32,
+        host_spec: Option<HostSpec>,
+    ) -> Result<(FilesLocalRow, FileConflictRow), Status> {
+        let peer_uuid = match host_spec {
+            Some(host_spec) => Some(
+                self.resolve_host_info_by_spec(&host_spec)
+                    .await
+                    .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?
+                    .uuid,
+            ),
+            None => None,
+        };
+
+        let mut conflicts: Vec<FileConflictRow> = self
+            .ctx
+            .repo
+            .fetch_file_conflicts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .into_iter()
+            .filter(|conflict| conflict.file_id == local_id)
+            .filter(|conflict| {
+                peer_uuid
+                    .as_ref()
+                    .is_none_or(|peer_uuid| *peer_uuid == conflict.peer_uuid)
+            })
+            .collect();
+        if conflicts.len() > 1 {
+            return Err(Status::invalid_argument(format!(
+                "File {local_id} conflicts with {} hosts, pick one of them",
+                conflicts.len()
+            )));
+        }
+        let conflict = conflicts.pop().ok_or_else(|| {
+            Status::not_found(format!(
+                "File {local_id} has no conflicts with the given host(s)"
+            ))
+        })?;
+
+        let local_file = self
+            .ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .into_iter()
+            .find(|file| file.id == local_id)
+            .ok_or_else(|| Status::not_found(format!("No tracked file with id {local_id}")))?;
+
+        Ok((local_file, conflict))
+    }
+
+    async fn describe_conflicts(
+        &self,
+        conflicts: Vec<FileConflictRow>,
+    ) -> Result<Vec<FileConflict>, Status> {
+        let local_files = self
+            .ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let tracked_files = self
+            .ctx
+            .repo
+            .fetch_tracked_files()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let hosts = self
+            .ctx
+            .repo
+            .fetch_hosts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let mut descriptions = Vec::with_capacity(conflicts.len());
+        for conflict in conflicts {
+            let Some(local_file) = local_files.iter().find(|file| file.id == conflict.file_id)
+            else {
+                continue;
+            };
+            let host_name = hosts
+                .iter()
+                .find(|host| host.uuid == conflict.peer_uuid)
+                .map_or(conflict.peer_uuid.as_str(), |host| host.name.as_str());
+            let remote_id = tracked_files
+                .iter()
+                .find(|tracked| {
+                    tracked.local_id == conflict.file_id && tracked.peer_uuid == conflict.peer_uuid
+                })
+                .map_or(0, |tracked| tracked.remote_id);
+            let remote_version: VersionVector = conflict.remote_version.parse().unwrap_or_default();
+
+            descriptions.push(FileConflict {
+                local_id: local_file.id,
+                file_path: local_file.file_path.clone(),
+                host_name: host_name.to_owned(),
+                remote_id,
+                local_hash: local_file.hash_sha1.clone(),
+                local_version: HashMap::from(&local_file.version_vector()),
+                remote_version: HashMap::from(&remote_version),
+                copy_fetched: conflict::is_copy_fetched(&conflict).await,
+                host_uuid: conflict.peer_uuid,
+                remote_hash: conflict.remote_hash,
+                copy_path: conflict.copy_path,
+                detected_at: conflict.detected_at,
+            });
+        }
+
+        Ok(descriptions)
+    }
+
     pub fn new(ctx: Arc<ServerContext>) -> Self {
         Self { ctx }
     }
@@ -69,17 +292,13 @@ impl UserAgentService for UserAgentServiceImpl {
             let invalid_paths = req_payload
                 .file_paths
                 .iter()
-                .filter_map(|path_str| {
+                .filter(|path_str| {
                     let path = PathBuf::from(path_str);
-                    if !path.is_absolute() || !path.is_file() {
-                        Some(path_str)
-                    } else {
-                        None
-                    }
+                    !path.is_absolute() || !path.is_file()
                 })
                 .collect::<Vec<_>>();
 
-            if invalid_paths.len() > 0 {
+            if !invalid_paths.is_empty() {
                 return Err(Status::invalid_argument(format!(
                     "Some file paths are invalid (not absolute or not a file): {:?}",
                     invalid_paths
@@ -87,54 +306,70 @@ impl UserAgentService for UserAgentServiceImpl {
             }
         }
 
-        // 2 - compute file hash
-        // TODO: TBH - do we need these hashes? We don't use them anywhere yet + they're not kept
-        // up to date in case the file changes (we need to observe inodes).
-        // This could potentially be done in multithreaded manner in case of many paths.
+        let local_uuid = self
+            .ctx
+            .repo
+            .fetch_local_server_info()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .uuid;
+        let version = VersionVector::initial(&local_uuid);
 
-        let hashes: Vec<String> = req_payload
+        // 2 - compute file hash
+        // Size, mtime & inode are stored as well, so that rescans rehash changed files only.
+        // TODO: This could potentially be done in multithreaded manner in case of many paths.
+        let fragments: Vec<FilesLocalFragmentInsert> = req_payload
             .file_paths
             .iter()
-            .filter_map(
-                |path_str| match tools::file::compute_sha1_hash_from_file(path_str, None) {
-                    Ok(hash) => Some(hash),
-                    Err(_) => None,
-                },
-            )
+            .filter_map(|path_str| {
+                let hash = tools::file::compute_sha1_hash_from_file(path_str, None).ok()?;
+                let metadata = std::fs::metadata(path_str).ok()?;
+                Some(FilesLocalFragmentInsert::from_metadata(
+                    path_str.clone(),
+                    hash,
+                    &metadata,
+                    None,
+                    &version,
+                ))
+            })
             .collect();
 
-        if hashes.len() != req_payload.file_paths.len() {
+        if fragments.len() != req_payload.file_paths.len() {
             return Err(Status::internal("Failed to compute some hashes"));
         }
 
-        let fragments = req_payload
-            .file_paths
-            .into_iter()
-            .zip(hashes.into_iter())
-            .map(|(file_path, hash)| FilesLocalFragmentInsert {
-                file_path,
-                hash_sha1: hash,
-            });
-
         // 3 - save file to the db
-        let result = self
-            .ctx
-            .repo
-            .save_local_files(&fragments.collect::<Vec<FilesLocalFragmentInsert>>())
-            .await;
+        let result = self.ctx.repo.save_local_files(&fragments).await;
 
         match result {
-            Ok(_) => Ok(tonic::Response::new(FileAddResponse {})),
+            Ok(_) => {}
             Err(err) => match err {
-                FileAddError::AlreadyExists { file_name } => Err(Status::already_exists(format!(
-                    "File: {file_name} is already tracked"
-                ))),
-                FileAddError::OtherDatabaseError { kind } => Err(Status::failed_precondition(
-                    format!("Some other database error: {kind:?}"),
-                )),
-                FileAddError::Other(err) => Err(Status::unknown(format!("Unknown error: {err}"))),
+                FileAddError::AlreadyExists { file_name } => {
+                    return Err(Status::already_exists(format!(
+                        "File: {file_name} is already tracked"
+                    )));
+                }
+                FileAddError::OtherDatabaseError { kind } => {
+                    return Err(Status::failed_precondition(format!(
+                        "Some other database error: {kind:?}"
+                    )));
+                }
+                FileAddError::Other(err) => {
+                    return Err(Status::unknown(format!("Unknown error: {err}")));
+                }
             },
         }
+
+        if let Some(group_id) = req_payload.group_id {
+            self.ctx
+                .repo
+                .save_group_files(&group_id, &req_payload.file_paths)
+                .await?;
+        }
+
+        self.ctx.watcher.refresh();
+
+        Ok(tonic::Response::new(FileAddResponse {}))
     }
 
     async fn file_remove(
@@ -150,7 +385,10 @@ impl UserAgentService for UserAgentServiceImpl {
         }
 
         match self.ctx.repo.delete_local_file(&payload.file_path).await {
-            Ok(_) => Ok(Response::new(FileRemoveResponse {})),
+            Ok(_) => {
+                self.ctx.watcher.refresh();
+                Ok(Response::new(FileRemoveResponse {}))
+            }
             Err(err) => {
                 let message = format!("Error while attampting to remove a file: {err}");
                 log::warn!("{message}");
@@ -168,14 +406,31 @@ impl UserAgentService for UserAgentServiceImpl {
         log::info!("Received FileList");
         log::debug!("Payload: {request_payload:?}");
 
+        let synced_with = self.synced_with().await?;
+        let conflicted: HashSet<i32> = self
+            .ctx
+            .repo
+            .fetch_file_conflicts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .into_iter()
+            .map(|conflict| conflict.file_id)
+            .collect();
+
         match self.ctx.repo.fetch_local_files().await {
             Ok(local_files) => Ok(tonic::Response::new(FileListResponse {
                 file_list: local_files
                     .into_iter()
                     .map(|f| LocalFileDescription {
                         local_id: f.id.to_string(),
+                        synced_with: synced_with.get(&f.id).cloned().unwrap_or_default(),
+                        version: HashMap::from(&f.version_vector()),
+                        conflicted: conflicted.contains(&f.id),
+                        mtime: f.mtime,
+                        tombstone: None,
                         file_path: f.file_path,
                         hash_sha1: f.hash_sha1,
+                        gone: f.gone,
                     })
                     .collect(),
             })),
@@ -245,31 +500,38 @@ impl UserAgentService for UserAgentServiceImpl {
                 })?
         };
 
+        let file_path_dst = file_dst_spec.path_spec.into_direct_string();
+
+        // Pulling from a remote host - the local user decides what gets written here.
+        let is_pull = host_src_info.is_remote && !host_dst_info.is_remote;
+        if is_pull {
+            self.ctx
+                .expected_transfers
+                .expect(&host_src_info.uuid, &file_path_dst);
+        }
+
         let transfer_request = TransferSubmitRequest {
             file_path_src: file_src_spec.path_spec.into_direct_string(),
-            file_path_dst: file_dst_spec.path_spec.into_direct_string(),
+            file_path_dst: file_path_dst.clone(),
             host_org_uuid: host_local_info.uuid,
             host_dst_uuid: host_dst_info.uuid,
         };
 
-        let host_dst_ipv4_addr = Ipv4Addr::from_str(&host_src_info.ipv4_addr).map_err(|err| {
-            Status::failed_precondition(format!(
-                "failed-to-parse-dst-address: {} with error: {err}",
-                &host_src_info.ipv4_addr
-            ))
-        })?;
-        let host_dst_addr = SocketAddrV4::new(host_dst_ipv4_addr, defaults::SERVER_PORT);
-
-        let channel = ChannelFactory::channel_with_timeout(
-            create_server_uri(host_dst_addr),
-            Duration::from_secs(5),
-        )
-        .await?;
+        // Transfer is submitted to the Source Host.
+        let channel = peer::connect_to_host(&self.ctx, &host_src_info, Duration::from_secs(5))
+            .await
+            .map_err(|err| Status::unavailable(format!("failed-to-connect-src-host: {err}")))?;
         let mut transfer_client = FileTransferServiceClient::new(channel);
 
         let _transfer_response = match transfer_client.transfer_submit(transfer_request).await {
             Ok(response) => response.into_inner(),
             Err(status) => {
+                if is_pull {
+                    let _ = self
+                        .ctx
+                        .expected_transfers
+                        .take(&host_src_info.uuid, &file_path_dst);
+                }
                 log::warn!("Dest host rejected TransferInitRequest: {}", status);
                 // TODO: Handle this correctly, instead of forwarding the status
                 return Err(status);
@@ -279,140 +541,518 @@ impl UserAgentService for UserAgentServiceImpl {
         Ok(tonic::Response::new(FileCopyResponse {}))
     }
 
-    async fn host_list(
+    async fn file_share(
         &self,
-        _request: Request<HostListRequest>,
-    ) -> Result<Response<HostListResponse>, Status> {
-        log::info!("Received ListHostsRequest");
+        request: Request<FileShareRequest>,
+    ) -> Result<Response<FileShareResponse>, Status> {
+        let payload = request.into_inner();
+        let host_info = self.resolve_share_target(payload.host_spec).await?;
+        let access = access_from_proto(payload.access);
 
-        let servers_info = match self.ctx.repo.fetch_hosts().await {
-            Ok(data) => data,
+        self.ctx
+            .repo
+            .save_file_access(&payload.file_path, &host_info.uuid, access)
+            .await?;
+
+        log::info!(
+            "Access of host {} to file {} set to {access:?}",
+            host_info.uuid,
+            payload.file_path
+        );
+        Ok(Response::new(FileShareResponse {}))
+    }
+
+    async fn file_sync(
+        &self,
+        request: Request<FileSyncRequest>,
+    ) -> Result<Response<FileSyncResponse>, Status> {
+        let payload = request.into_inner();
+        let host_spec = payload
+            .host_spec
+            .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        let local_files = self
+            .ctx
+            .repo
+            .fetch_local_files()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if !local_files.iter().any(|file| file.id == payload.local_id) {
+            return Err(Status::not_found(format!(
+                "No tracked file with id {}",
+                payload.local_id
+            )));
+        }
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+        if !host_info.is_remote {
+            return Err(Status::invalid_argument(
+                "Can not sync with current server instance",
+            ));
+        }
+
+        // Catch typos & missing grants early. Hosts which are offline are synced with later.
+        match peer::connect_to_host(&self.ctx, &host_info, Duration::from_secs(5)).await {
+            Ok(channel) => {
+                FileTransferServiceClient::new(channel)
+                    .file_describe(FileDescribeRequest {
+                        file_id: payload.remote_id,
+                    })
+                    .await
+                    .map_err(|status| {
+                        Status::failed_precondition(format!(
+                            "remote-file-unavailable: {}",
+                            status.message()
+                        ))
+                    })?;
+            }
             Err(err) => {
-                log::error!("Error while fetching peer server info: {err}");
-                return Err(tonic::Status::internal(
-                    "Error while fetching peer server info: {err}",
-                ));
+                log::info!(
+                    "Host {} is not reachable, the file is going to be synced once it is: {err}",
+                    host_info.uuid
+                );
             }
+        }
+
+        self.ctx
+            .repo
+            .save_tracked_file(&FilesTrackedRow {
+                local_id: payload.local_id,
+                peer_uuid: host_info.uuid,
+                remote_id: payload.remote_id,
+                last_synced_hash: None,
+            })
+            .await?;
+        self.ctx.sync_requested.notify_one();
+
+        Ok(Response::new(FileSyncResponse {}))
+    }
+
+    async fn file_unsync(
+        &self,
+        request: Request<FileUnsyncRequest>,
+    ) -> Result<Response<FileUnsyncResponse>, Status> {
+        let payload = request.into_inner();
+
+        let peer_uuid = match payload.host_spec {
+            Some(host_spec) => Some(
+                self.resolve_host_info_by_spec(&host_spec)
+                    .await
+                    .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?
+                    .uuid,
+            ),
+            None => None,
         };
 
-        return Ok(Response::new(HostListResponse {
-            host_list: servers_info,
-        }));
+        let removed = self
+            .ctx
+            .repo
+            .delete_tracked_files(payload.local_id, peer_uuid.as_deref())
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if removed == 0 {
+            return Err(Status::not_found(format!(
+                "File {} is not synced with the given host(s)",
+                payload.local_id
+            )));
+        }
+
+        Ok(Response::new(FileUnsyncResponse {
+            removed: u32::try_from(removed).unwrap_or(u32::MAX),
+        }))
     }
 
-    async fn host_discover(
+    async fn file_rescan(
         &self,
-        _request: Request<HostDiscoverRequest>,
-    ) -> Result<Response<HostDiscoverResponse>, Status> {
-        log::info!("Received DiscoverHostsRequest");
+        _request: Request<FileRescanRequest>,
+    ) -> Result<Response<FileRescanResponse>, Status> {
+        log::info!("Received FileRescan");
 
-        let discovered_servers_info = self.host_discovery_impl().await.unwrap();
+        let summary = index::rescan(&self.ctx)
+            .await
+            .map_err(|err| Status::internal(format!("Failed to rescan tracked files: {err}")))?;
 
-        return Ok(Response::new(HostDiscoverResponse {
-            host_list: discovered_servers_info,
-        }));
+        Ok(Response::new(FileRescanResponse {
+            scanned: summary.scanned,
+            modified: summary.modified,
+            added: summary.added,
+            gone: summary.gone,
+        }))
     }
 
-    async fn host_add(
+    async fn file_check_ignore(
         &self,
-        request: Request<HostAddRequest>,
-    ) -> Result<Response<HostAddResponse>, Status> {
-        // FIXME: This function has few problems:
-        // 1. We do not check whether requested server is exactly the same one
-        // as instance serving the request.
-        // 2. We need to actually store the port together with the address...
-        // Even if autodiscovery is not able to find remotes running on different
-        // ports than default, now it is possible to manually add them.
+        request: Request<FileCheckIgnoreRequest>,
+    ) -> Result<Response<FileCheckIgnoreResponse>, Status> {
+        let path = PathBuf::from(request.into_inner().path);
+        if !path.is_absolute() {
+            return Err(Status::invalid_argument("Path is not absolute"));
+        }
+
+        let roots = self
+            .ctx
+            .repo
+            .fetch_sync_roots()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let Some(root) = index::find_root(&roots, &path) else {
+            return Ok(Response::new(FileCheckIgnoreResponse::default()));
+        };
+
+        let rule = RootIgnore::new(&root.path, &self.ctx.cfg.ignore_patterns)
+            .matched(&path, path.is_dir());
+
+        Ok(Response::new(FileCheckIgnoreResponse {
+            root_id: Some(root.id),
+            ignored: rule.as_ref().is_some_and(|rule| !rule.negated),
+            rule: rule.map(|rule| IgnoreRule {
+                pattern: rule.pattern,
+                source: rule
+                    .source
+                    .map(|source| source.to_string_lossy().into_owned()),
+                negated: rule.negated,
+            }),
+        }))
+    }
 
+    async fn root_add(
+        &self,
+        request: Request<RootAddRequest>,
+    ) -> Result<Response<RootAddResponse>, Status> {
         let payload = request.into_inner();
 
-        let host_ipv4 = match Ipv4Addr::from_str(&payload.ipv4_addr) {
-            Ok(addr) => addr,
-            Err(err) => {
-                return Err(Status::invalid_argument(format!(
-                    "failed-to-parse-host-ipv4: {err}"
-                )));
-            }
-        };
+        log::info!("Received RootAdd");
+        log::debug!("Payload: {payload:?}");
 
-        let port = if let Some(port) = payload.port {
-            match u16::try_from(port) {
-                Ok(port) => port,
-                Err(err) => {
-                    return Err(Status::invalid_argument(format!(
-                        "failed-to-parse-host-port: {err}"
-                    )));
-                }
-            }
-        } else {
-            DEFAULT_SERVER_PORT
+        let path = PathBuf::from(&payload.path);
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(Status::invalid_argument(format!(
+                "Path {} is not absolute or not a directory",
+                payload.path
+            )));
+        }
+        // Paths of the root's files are compared by prefix, so the root has to be canonical.
+        let path = path
+            .canonicalize()
+            .map_err(|err| Status::internal(format!("Failed to resolve the path - {err}")))?;
+        let path = path
+            .to_str()
+            .ok_or_else(|| Status::invalid_argument("Path is not valid UTF-8"))?;
+
+        let root_id = self.ctx.repo.save_sync_root(path).await?;
+        let root = SyncRootRow {
+            id: root_id,
+            path: path.to_owned(),
         };
 
-        let host_addr = SocketAddrV4::new(host_ipv4, port);
+        let files = index::index_root(&self.ctx, &root)
+            .await
+            .map_err(|err| Status::internal(format!("Failed to index the root - {err}")))?;
+        self.ctx.watcher.refresh();
 
-        let mut client = HostDiscoveryServiceClient::new(
-            ChannelFactory::channel_with_timeout(
-                create_server_uri(host_addr),
-                Duration::from_secs(5),
-            )
-            .await?,
-        );
+        log::info!("Sync root {path} added with {files} file(s)");
+        Ok(Response::new(RootAddResponse { root_id, files }))
+    }
 
-        let localhost_info = self.ctx.repo.fetch_local_server_info().await?;
+    async fn root_remove(
+        &self,
+        request: Request<RootRemoveRequest>,
+    ) -> Result<Response<RootRemoveResponse>, Status> {
+        let root_id = request.into_inner().root_id;
 
-        let result = client
-            .hello_there(HelloThereRequest {
-                host_info: Some(HostInfo {
-                    uuid: localhost_info.uuid,
-                    name: localhost_info.name,
-                    hostname: localhost_info.hostname,
-                    address: String::from(""),
-                }),
-            })
-            .await;
+        let removed = self
+            .ctx
+            .repo
+            .delete_sync_root(root_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if removed == 0 {
+            return Err(Status::not_found(format!("No sync root with id {root_id}")));
+        }
+        self.ctx.watcher.refresh();
 
-        let response_payload = match result {
-            Ok(response) => response.into_inner(),
-            Err(err) => {
-                return Err(Status::unavailable(format!(
-                    "received-error-response: {err}"
-                )));
+        Ok(Response::new(RootRemoveResponse {}))
+    }
+
+    async fn root_list(
+        &self,
+        request: Request<dsync_proto::services::user_agent::RootListRequest>,
+    ) -> Result<Response<dsync_proto::services::user_agent::RootListResponse>, Status> {
+        let payload = request.into_inner();
+
+        let roots = match payload.host_spec {
+            Some(host_spec) => self.remote_roots(&host_spec).await?,
+            None => {
+                let synced_with = self.roots_synced_with().await?;
+                self.ctx
+                    .repo
+                    .fetch_sync_roots()
+                    .await
+                    .map_err(|err| Status::internal(err.to_string()))?
+                    .into_iter()
+                    .map(|root| SyncRootDescription {
+                        root_id: root.id,
+                        synced_with: synced_with.get(&root.id).cloned().unwrap_or_default(),
+                        path: root.path,
+                    })
+                    .collect()
             }
         };
 
-        let host_info = response_payload.host_info.ok_or_else(|| {
-            Status::aborted("Remote host response had missing required field - host_info")
-        })?;
+        Ok(Response::new(
+            dsync_proto::services::user_agent::RootListResponse { roots },
+        ))
+    }
 
-        let host_row = HostsRow {
-            uuid: host_info.uuid.clone(),
-            name: host_info.name.clone(),
-            hostname: host_info.hostname.clone(),
-            is_remote: true,
-            ipv4_addr: host_addr.ip().to_string(),
-            discovery_time: tools::time::get_current_timestamp(),
-        };
+    async fn root_share(
+        &self,
+        request: Request<RootShareRequest>,
+    ) -> Result<Response<RootShareResponse>, Status> {
+        let payload = request.into_inner();
+        let host_info = self.resolve_share_target(payload.host_spec).await?;
+        let access = access_from_proto(payload.access);
 
-        let _ = self.ctx.repo.insert_hosts(&[host_row]).await;
-        Ok(Response::new(HostAddResponse {
-            host_info: Some(host_info),
-        }))
+        self.ctx
+            .repo
+            .save_root_access(payload.root_id, &host_info.uuid, access)
+            .await?;
+
+        log::info!(
+            "Access of host {} to root {} set to {access:?}",
+            host_info.uuid,
+            payload.root_id
+        );
+        Ok(Response::new(RootShareResponse {}))
     }
 
-    async fn host_remove(
+    async fn root_sync(
         &self,
-        request: Request<HostRemoveRequest>,
-    ) -> Result<Response<HostRemoveResponse>, Status> {
+        request: Request<RootSyncRequest>,
+    ) -> Result<Response<RootSyncResponse>, Status> {
         let payload = request.into_inner();
         let host_spec = payload
             .host_spec
             .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
 
+        self.ensure_root_exists(payload.root_id).await?;
+
         let host_info = self
             .resolve_host_info_by_spec(&host_spec)
             .await
-            .map_err(|err| {
-                Status::unknown(format!(
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+        if !host_info.is_remote {
+            return Err(Status::invalid_argument(
+                "Can not sync with current server instance",
+            ));
+        }
+
+        // Catch typos & missing grants early. Hosts which are offline are synced with later.
+        match peer::connect_to_host(&self.ctx, &host_info, Duration::from_secs(5)).await {
+            Ok(channel) => {
+                FileTransferServiceClient::new(channel)
+                    .root_describe(RootDescribeRequest {
+                        root_id: payload.remote_root_id,
+                    })
+                    .await
+                    .map_err(|status| {
+                        Status::failed_precondition(format!(
+                            "remote-root-unavailable: {}",
+                            status.message()
+                        ))
+                    })?;
+            }
+            Err(err) => {
+                log::info!(
+                    "Host {} is not reachable, the root is going to be synced once it is: {err}",
+                    host_info.uuid
+                );
+            }
+        }
+
+        self.ctx
+            .repo
+            .save_tracked_root(&RootsTrackedRow {
+                root_id: payload.root_id,
+                peer_uuid: host_info.uuid,
+                remote_root_id: payload.remote_root_id,
+            })
+            .await?;
+        self.ctx.sync_requested.notify_one();
+
+        Ok(Response::new(RootSyncResponse {}))
+    }
+
+    async fn root_unsync(
+        &self,
+        request: Request<RootUnsyncRequest>,
+    ) -> Result<Response<RootUnsyncResponse>, Status> {
+        let payload = request.into_inner();
+
+        let peer_uuid = match payload.host_spec {
+            Some(host_spec) => Some(
+                self.resolve_host_info_by_spec(&host_spec)
+                    .await
+                    .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?
+                    .uuid,
+            ),
+            None => None,
+        };
+
+        let removed = self
+            .ctx
+            .repo
+            .delete_tracked_roots(payload.root_id, peer_uuid.as_deref())
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if removed == 0 {
+            return Err(Status::not_found(format!(
+                "Root {} is not synced with the given host(s)",
+                payload.root_id
+            )));
+        }
+
+        Ok(Response::new(RootUnsyncResponse {
+            removed: u32::try_from(removed).unwrap_or(u32::MAX),
+        }))
+    }
+
+    async fn host_list(
+        &self,
+        _request: Request<HostListRequest>,
+    ) -> Result<Response<HostListResponse>, Status> {
+        log::info!("Received ListHostsRequest");
+
+        let mut servers_info = match self.ctx.repo.fetch_hosts().await {
+            Ok(data) => data,
+            Err(err) => {
+                log::error!("Error while fetching peer server info: {err}");
+                return Err(tonic::Status::internal(
+                    "Error while fetching peer server info: {err}",
+                ));
+            }
+        };
+
+        let local_uuid = self.ctx.repo.fetch_local_server_info().await?.uuid;
+        for host_info in servers_info.iter_mut() {
+            if host_info.uuid == local_uuid {
+                host_info.online = true;
+            } else {
+                self.ctx.peers.fill_status(host_info);
+            }
+        }
+
+        return Ok(Response::new(HostListResponse {
+            host_list: servers_info,
+        }));
+    }
+
+    async fn host_discover(
+        &self,
+        _request: Request<HostDiscoverRequest>,
+    ) -> Result<Response<HostDiscoverResponse>, Status> {
+        log::info!("Received DiscoverHostsRequest");
+
+        let mut discovered_rx = self.host_discovery_impl().await?;
+
+        let mut discovered_servers_info = Vec::new();
+        while let Some(host_info) = discovered_rx.recv().await {
+            discovered_servers_info.push(host_info);
+        }
+
+        return Ok(Response::new(HostDiscoverResponse {
+            host_list: discovered_servers_info,
+        }));
+    }
+
+    type HostDiscoverStreamStream =
+        Pin<Box<dyn Stream<Item = Result<HostDiscoverStreamResponse, Status>> + Send + 'static>>;
+
+    async fn host_discover_stream(
+        &self,
+        _request: Request<HostDiscoverRequest>,
+    ) -> Result<Response<Self::HostDiscoverStreamStream>, Status> {
+        log::info!("Received DiscoverHostsStreamRequest");
+
+        let discovered_rx = self.host_discovery_impl().await?;
+
+        let stream = ReceiverStream::new(discovered_rx)
+            .map(|host_info| HostDiscoverStreamResponse {
+                host_info: Some(host_info),
+            })
+            .map(Ok);
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn host_add(
+        &self,
+        request: Request<HostAddRequest>,
+    ) -> Result<Response<HostAddResponse>, Status> {
+        let payload = request.into_inner();
+
+        let port = if let Some(port) = payload.port {
+            match u16::try_from(port) {
+                Ok(port) => port,
+                Err(err) => {
+                    return Err(Status::invalid_argument(format!(
+                        "failed-to-parse-host-port: {err}"
+                    )));
+                }
+            }
+        } else {
+            DEFAULT_SERVER_PORT
+        };
+
+        let host_addr = match addr::parse_socket_addr(&payload.address, port) {
+            Ok(addr) => addr,
+            Err(err) => {
+                return Err(Status::invalid_argument(format!(
+                    "failed-to-parse-host-address: {err}"
+                )));
+            }
+        };
+
+        let (host_info, public_key) =
+            handshake::say_hello(&self.ctx, host_addr, Duration::from_secs(5))
+                .await
+                .map_err(|err| Status::unavailable(format!("received-error-response: {err}")))?;
+
+        let localhost_info = self.ctx.repo.fetch_local_server_info().await?;
+        if host_info.uuid == localhost_info.uuid {
+            return Err(Status::invalid_argument(
+                "Requested host is the current server instance",
+            ));
+        }
+
+        let seen_time = tools::time::get_current_timestamp();
+        if let Err(err) =
+            handshake::remember_host(&self.ctx, &host_info, &public_key, seen_time).await
+        {
+            log::warn!("Failed to save added host: {err}");
+        }
+
+        Ok(Response::new(HostAddResponse {
+            host_info: Some(host_info),
+        }))
+    }
+
+    async fn host_remove(
+        &self,
+        request: Request<HostRemoveRequest>,
+    ) -> Result<Response<HostRemoveResponse>, Status> {
+        let payload = request.into_inner();
+        let host_spec = payload
+            .host_spec
+            .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| {
+                Status::unknown(format!(
                     "Failed to fetch requested remote info, likely it doesn't exist - {err}"
                 ))
             })?;
@@ -423,11 +1063,292 @@ impl UserAgentService for UserAgentServiceImpl {
             ));
         }
 
-        let _ = self.ctx.repo.delete_host_with_uuid(&host_info.uuid).await;
+        self.ctx
+            .repo
+            .delete_host_with_uuid(&host_info.uuid)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        self.ctx.peers.forget(&host_info.uuid);
 
         Ok(Response::new(HostRemoveResponse {}))
     }
 
+    async fn host_update(
+        &self,
+        request: Request<HostUpdateRequest>,
+    ) -> Result<Response<HostUpdateResponse>, Status> {
+        let payload = request.into_inner();
+        let host_spec = payload
+            .host_spec
+            .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        if let Some(name) = &payload.name {
+            validate_host_name(name).map_err(|err| Status::invalid_argument(err.to_string()))?;
+        }
+        if payload.add_tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(Status::invalid_argument("Tags can not be empty"));
+        }
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+
+        let metadata = HostsMetadataFragmentUpdate {
+            name: payload.name,
+            notes: payload.notes,
+        };
+        let result = async {
+            self.ctx
+                .repo
+                .update_host_metadata(&host_info.uuid, &metadata)
+                .await?;
+            self.ctx
+                .repo
+                .save_host_tags(&host_info.uuid, &payload.add_tags)
+                .await?;
+            self.ctx
+                .repo
+                .delete_host_tags(&host_info.uuid, &payload.remove_tags)
+                .await
+        }
+        .await;
+
+        match result {
+            Ok(()) => Ok(Response::new(HostUpdateResponse {})),
+            Err(err) => Err(Status::internal(format!(
+                "Failed to update the host: {err}"
+            ))),
+        }
+    }
+
+    async fn host_alias(
+        &self,
+        request: Request<HostAliasRequest>,
+    ) -> Result<Response<HostAliasResponse>, Status> {
+        let payload = request.into_inner();
+        let host_spec = payload
+            .host_spec
+            .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+
+        if payload.remove {
+            let removed = self
+                .ctx
+                .repo
+                .delete_host_alias(&host_info.uuid, &payload.alias)
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+            if removed == 0 {
+                return Err(Status::not_found(format!(
+                    "Host {} has no alias `{}`",
+                    host_info.name, payload.alias
+                )));
+            }
+        } else {
+            validate_host_name(&payload.alias)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+            self.ctx
+                .repo
+                .save_host_alias(&host_info.uuid, &payload.alias)
+                .await?;
+        }
+
+        Ok(Response::new(HostAliasResponse {}))
+    }
+
+    async fn host_block(
+        &self,
+        request: Request<HostBlockRequest>,
+    ) -> Result<Response<HostBlockResponse>, Status> {
+        let payload = request.into_inner();
+        let rule: BlockRule = payload
+            .rule
+            .parse()
+            .map_err(|err: anyhow::Error| Status::invalid_argument(err.to_string()))?;
+
+        if payload.remove {
+            let removed = self
+                .ctx
+                .repo
+                .delete_blocklist_rule(&rule.to_string())
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+            if removed == 0 {
+                let reason = if self.ctx.cfg.blocklist.contains(&rule) {
+                    "comes from the server configuration"
+                } else {
+                    "does not exist"
+                };
+                return Err(Status::not_found(format!("Block rule {rule} {reason}")));
+            }
+            return Ok(Response::new(HostBlockResponse { removed_hosts: 0 }));
+        }
+
+        let local_info = self
+            .ctx
+            .repo
+            .fetch_local_server_info()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        if rule.matches(Some(&local_info.uuid), None) {
+            return Err(Status::invalid_argument(
+                "Can not block current server instance",
+            ));
+        }
+
+        self.ctx
+            .repo
+            .save_blocklist_rule(&rule.to_string())
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        // Blocked hosts are never stored, forget the ones known already.
+        let hosts = self
+            .ctx
+            .repo
+            .fetch_hosts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let mut removed_hosts = 0;
+        for host in hosts.iter().filter(|host| host.uuid != local_info.uuid) {
+            let ip = addr::parse_socket_addr(&host.address, 0)
+                .ok()
+                .map(|addr| addr.ip());
+            if !rule.matches(Some(&host.uuid), ip) {
+                continue;
+            }
+
+            self.ctx
+                .repo
+                .delete_host_with_uuid(&host.uuid)
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+            self.ctx.peers.forget(&host.uuid);
+            removed_hosts += 1;
+        }
+
+        Ok(Response::new(HostBlockResponse { removed_hosts }))
+    }
+
+    async fn host_blocklist(
+        &self,
+        _request: Request<HostBlocklistRequest>,
+    ) -> Result<Response<HostBlocklistResponse>, Status> {
+        let rules = self
+            .ctx
+            .repo
+            .fetch_blocklist()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(HostBlocklistResponse {
+            rules,
+            config_rules: self
+                .ctx
+                .cfg
+                .blocklist
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }))
+    }
+
+    async fn host_pair(
+        &self,
+        request: Request<HostPairRequest>,
+    ) -> Result<Response<HostPairResponse>, Status> {
+        let payload = request.into_inner();
+        let host_spec = payload
+            .host_spec
+            .ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("failed-to-resolve-host-spec: {err}")))?;
+
+        if !host_info.is_remote {
+            return Err(Status::invalid_argument(
+                "Can not pair with current server instance",
+            ));
+        }
+
+        let code = pairing::pair_with_host(&self.ctx, &host_info)
+            .await
+            .map_err(|err| Status::unavailable(format!("pairing-failed: {err}")))?;
+
+        Ok(Response::new(HostPairResponse {
+            host_info: Some(host_info.to_host_info()),
+            code,
+        }))
+    }
+
+    async fn host_pair_pending(
+        &self,
+        _request: Request<HostPairPendingRequest>,
+    ) -> Result<Response<HostPairPendingResponse>, Status> {
+        let pairings = self
+            .ctx
+            .pairings
+            .incoming_awaiting_confirmation()
+            .into_iter()
+            .filter_map(|pairing| match pairing.stage {
+                PairingStage::AwaitingConfirmation { code } => Some(PendingPairing {
+                    host_info: Some(pairing.host_info),
+                    code,
+                }),
+                PairingStage::AwaitingReveal { .. } => None,
+            })
+            .collect();
+
+        Ok(Response::new(HostPairPendingResponse { pairings }))
+    }
+
+    async fn host_pair_confirm(
+        &self,
+        request: Request<HostPairConfirmRequest>,
+    ) -> Result<Response<HostPairConfirmResponse>, Status> {
+        let payload = request.into_inner();
+
+        let pairing = self
+            .ctx
+            .pairings
+            .take_awaiting_confirmation(&payload.uuid)
+            .map_err(|err| Status::failed_precondition(err.to_string()))?;
+
+        if !payload.accept {
+            log::info!("Pairing with host {} rejected", payload.uuid);
+            return Ok(Response::new(HostPairConfirmResponse {}));
+        }
+
+        // Host which started the pairing might not be known yet.
+        if pairing.incoming {
+            let public_key = pairing
+                .public_key
+                .as_slice()
+                .try_into()
+                .expect("Key length is validated when the pairing starts");
+            let seen_time = tools::time::get_current_timestamp();
+            handshake::remember_host(&self.ctx, &pairing.host_info, &public_key, seen_time)
+                .await
+                .map_err(|err| Status::internal(format!("failed-to-save-host: {err}")))?;
+        }
+
+        self.ctx
+            .repo
+            .pair_host(&payload.uuid, &hex::encode(&pairing.public_key))
+            .await
+            .map_err(|err| Status::internal(format!("failed-to-save-public-key: {err}")))?;
+
+        log::info!("Paired with host {}", payload.uuid);
+        Ok(Response::new(HostPairConfirmResponse {}))
+    }
+
     async fn group_create(
         &self,
         request: Request<GroupCreateRequest>,
@@ -476,123 +1397,212 @@ impl UserAgentService for UserAgentServiceImpl {
         &self,
         _request: Request<GroupListRequest>,
     ) -> Result<Response<GroupListResponse>, Status> {
-        let group_list = match self.ctx.repo.fetch_local_groups().await {
+        let mut group_list = match self.ctx.repo.fetch_local_groups().await {
             Ok(groups) => groups,
             Err(err) => return Err(tonic::Status::internal(format!("Failed with error: {err}"))),
         };
+        let hosts = self
+            .ctx
+            .repo
+            .fetch_hosts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        // Stored as uuids, shown by names.
+        for group in &mut group_list {
+            for host_uuid in &mut group.host_priority {
+                if let Some(host) = hosts.iter().find(|host| host.uuid == *host_uuid) {
+                    host_uuid.clone_from(&host.name);
+                }
+            }
+        }
 
         Ok(tonic::Response::new(GroupListResponse { group_list }))
     }
-}
 
-impl UserAgentServiceImpl {
-    async fn check_hello(&self, ipv4_addr: &str) -> Option<HostInfo> {
-        let remote_ip_addr = Ipv4Addr::from_str(ipv4_addr)
-            .with_context(|| {
-                format!(
-                    "Failed to resolve host ipv4 addr from string: {}",
-                    ipv4_addr
-                )
-            })
-            .ok()?;
-
-        // Try to connect with the host
-        let remote_service_socket = SocketAddrV4::new(remote_ip_addr, defaults::SERVER_PORT);
-        let remote_service_uri = Uri::builder()
-            .scheme("http")
-            .authority(remote_service_socket.clone().to_string())
-            .path_and_query("/")
-            .build()
-            .with_context(|| {
-                format!(
-                    "Failed to build remote service uri from socket addr: {}",
-                    &remote_service_socket
-                )
-            })
-            .ok()?;
+    async fn group_share(
+        &self,
+        request: Request<GroupShareRequest>,
+    ) -> Result<Response<GroupShareResponse>, Status> {
+        let payload = request.into_inner();
+        let host_info = self.resolve_share_target(payload.host_spec).await?;
+        let access = access_from_proto(payload.access);
 
-        let Ok(mut endpoint) = tonic::transport::Endpoint::new(remote_service_uri) else {
-            log::warn!(target: "pslog", "Failed to create endpoint for addr: {}", &remote_service_socket);
-            return None;
-        };
+        self.ctx
+            .repo
+            .save_group_access(&payload.group_id, &host_info.uuid, access)
+            .await?;
 
-        endpoint = endpoint.connect_timeout(Duration::from_secs(10));
+        log::info!(
+            "Access of host {} to group {} set to {access:?}",
+            host_info.uuid,
+            payload.group_id
+        );
+        Ok(Response::new(GroupShareResponse {}))
+    }
 
-        let channel = match endpoint.connect().await {
-            Ok(ch) => ch,
-            Err(error) => {
-                log::warn!("Failed to connect with {remote_service_socket} with error: {error}");
-                return None;
-            }
-        };
+    async fn group_strategy(
+        &self,
+        request: Request<GroupStrategyRequest>,
+    ) -> Result<Response<GroupStrategyResponse>, Status> {
+        let payload = request.into_inner();
+        let strategy = ConflictStrategy::from(payload.strategy());
 
-        let mut client_conn = HostDiscoveryServiceClient::new(channel);
+        let mut host_priority = Vec::with_capacity(payload.host_priority.len());
+        for host_spec in &payload.host_priority {
+            let host_info = self
+                .resolve_host_info_by_spec(host_spec)
+                .await
+                .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
+            host_priority.push(host_info.uuid);
+        }
+        if strategy == ConflictStrategy::HostPriority && host_priority.is_empty() {
+            return Err(Status::invalid_argument(
+                "The host-priority strategy needs at least one host",
+            ));
+        }
 
-        let server_info = self.ctx.repo.fetch_local_server_info().await.ok()?;
+        self.ctx
+            .repo
+            .update_group_strategy(
+                &payload.group_id,
+                &strategy.to_string(),
+                &host_priority.join(","),
+            )
+            .await?;
 
-        let request = tonic::Request::new(HelloThereRequest {
-            host_info: Some(HostInfo {
-                uuid: server_info.uuid,
-                name: server_info.name,
-                hostname: server_info.hostname,
-                address: "".to_owned(),
-            }),
-        });
+        log::info!(
+            "Conflict strategy of group {} set to {strategy}",
+            payload.group_id
+        );
+        Ok(Response::new(GroupStrategyResponse {}))
+    }
 
-        let response = client_conn.hello_there(request).await.ok()?.into_inner();
+    async fn conflict_list(
+        &self,
+        _request: Request<ConflictListRequest>,
+    ) -> Result<Response<ConflictListResponse>, Status> {
+        let conflicts = self
+            .ctx
+            .repo
+            .fetch_file_conflicts()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
 
-        let Some(mut remote_server_info) = response.host_info else {
-            log::warn!(target: "pslog", "Invalid response from peer, server info must not be none");
-            return None;
-        };
+        Ok(Response::new(ConflictListResponse {
+            conflicts: self.describe_conflicts(conflicts).await?,
+        }))
+    }
 
-        assert!(
-            remote_server_info.address.is_empty(),
-            "Unexpected payload, expected empty address"
-        );
+    async fn conflict_show(
+        &self,
+        request: Request<ConflictShowRequest>,
+    ) -> Result<Response<ConflictShowResponse>, Status> {
+        let payload = request.into_inner();
+        let (local_file, conflict) = self
+            .find_conflict(payload.local_id, payload.host_spec)
+            .await?;
+
+        let diff = if payload.diff {
+            if !conflict::is_copy_fetched(&conflict).await {
+                return Err(Status::failed_precondition(
+                    "The remote version has not been fetched yet",
+                ));
+            }
+            Some(
+                conflict::diff(&local_file, &conflict)
+                    .await
+                    .map_err(|err| Status::failed_precondition(err.to_string()))?,
+            )
+        } else {
+            None
+        };
 
-        // Fill up the address, because we actually have this information here
-        remote_server_info.address = remote_service_socket.ip().to_string();
+        let conflict = self
+            .describe_conflicts(vec![conflict])
+            .await?
+            .pop()
+            .ok_or_else(|| Status::not_found("The conflicted file is not tracked anymore"))?;
 
-        Some(remote_server_info)
+        Ok(Response::new(ConflictShowResponse {
+            conflict: Some(conflict),
+            diff,
+        }))
     }
 
-    async fn host_discovery_impl(&self) -> Result<Vec<HostInfo>, Status> {
-        // TODO: this could be done once, on server start.
-        if !tools::file::check_binary_exists("nmap") {
-            return Err(tonic::Status::internal("Missing binary: nmap"));
-        }
+    async fn conflict_resolve(
+        &self,
+        request: Request<ConflictResolveRequest>,
+    ) -> Result<Response<ConflictResolveResponse>, Status> {
+        let payload = request.into_inner();
+        let resolution = Resolution::from(payload.resolution());
+        let (local_file, conflict) = self
+            .find_conflict(payload.local_id, payload.host_spec)
+            .await?;
+        let peer_name = self
+            .ctx
+            .repo
+            .fetch_host_by_uuid(&conflict.peer_uuid)
+            .await
+            .map_or_else(|_| conflict.peer_uuid.clone(), |host| host.name);
 
-        let Some(ipv4_addrs) = tools::net::addr_discovery::discover_hosts_in_local_network() else {
-            return Err(tonic::Status::internal(
-                "Failed to find hosts in local network",
-            ));
-        };
+        let kept_path =
+            conflict::resolve(&self.ctx, &local_file, &conflict, &peer_name, resolution)
+                .await
+                .map_err(|err| Status::failed_precondition(format!("Failed to resolve - {err}")))?;
+
+        Ok(Response::new(ConflictResolveResponse {
+            kept_path: kept_path.and_then(|path| path.to_str().map(str::to_owned)),
+        }))
+    }
+}
 
-        let mut serial_responses: Vec<HostInfo> = Vec::new();
+fn access_from_proto(access: Option<dsync_proto::services::user_agent::Access>) -> Access {
+    access
+        .map(|access| Access {
+            read: access.read,
+            write: access.write,
+        })
+        .unwrap_or_default()
+}
 
-        // This could be definitely improved, however it's fine for now.
-        for addr in ipv4_addrs.iter() {
-            match self.check_hello(&addr.to_string()).await {
-                Some(server_info) => serial_responses.push(server_info),
-                None => {
-                    log::trace!(target: "pslog", "Have not found deamon at {addr}");
-                }
-            }
-        }
+impl UserAgentServiceImpl {
+    /// Find candidate peers & handshake with them concurrently. Peers are sent through
+    /// the returned channel as they respond.
+    async fn host_discovery_impl(&self) -> Result<mpsc::Receiver<HostInfo>, Status> {
+        let candidate_addrs = self.ctx.discovery.discover().await.map_err(|err| {
+            tonic::Status::internal(format!("Failed to find hosts in local network: {err}"))
+        })?;
+        let candidate_addrs = addr_discovery::retain_on_interfaces(
+            candidate_addrs,
+            &self.ctx.cfg.discovery.interfaces,
+        )
+        .map_err(|err| {
+            tonic::Status::internal(format!("Failed to list network interfaces: {err}"))
+        })?;
 
-        let discovery_time = tools::time::get_current_timestamp();
+        Ok(handshake::say_hello_all(
+            self.ctx.clone(),
+            candidate_addrs,
+            self.ctx.cfg.discovery.hello_concurrency,
+        ))
+    }
 
-        // Cache discovered hosts locally
-        let peer_base_info: Vec<HostsRow> = serial_responses
-            .iter()
-            // TODO: Could use only references in this struct, avoiding all the copies
-            .map(|info| HostsRow::from_host_info(&info, true, Some(discovery_time)))
-            .collect();
+    /// Remote host that access is granted to. The local host always has full access.
+    async fn resolve_share_target(&self, host_spec: Option<HostSpec>) -> Result<HostsRow, Status> {
+        let host_spec = host_spec.ok_or_else(|| Status::invalid_argument("missing-host-spec"))?;
+
+        let host_info = self
+            .resolve_host_info_by_spec(&host_spec)
+            .await
+            .map_err(|err| Status::not_found(format!("Failed to find the host - {err}")))?;
 
-        let _ = self.ctx.repo.insert_hosts(&peer_base_info).await;
+        if !host_info.is_remote {
+            return Err(Status::invalid_argument(
+                "Can not share with current server instance",
+            ));
+        }
 
-        Ok(serial_responses)
+        Ok(host_info)
     }
 
     async fn resolve_host_info_by_spec(&self, host_spec: &HostSpec) -> anyhow::Result<HostsRow> {
@@ -602,6 +1612,10 @@ impl UserAgentServiceImpl {
                 Ok(local_host_info)
             }
             host_spec::Kind::Name(name) => {
+                // Aliases are chosen by the user to be unique, unlike names.
+                if let Some(host_info) = self.ctx.repo.fetch_host_by_alias(name).await? {
+                    return Ok(host_info);
+                }
                 let host_info = self.ctx.repo.fetch_host_by_name(name).await?;
                 Ok(host_info)
             }
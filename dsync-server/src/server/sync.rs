@@ -0,0 +1,958 @@
+//! Keeping tracked files in sync with files tracked by peers (`dsync-cli file sync`).
+//!
+//! Files which differ are compared by their version vectors (see `version_vector`): the file
+//! which has seen all modifications of the other one replaces it. Files modified concurrently -
+//! including files of different histories synced for the first time - are conflicted: both are
+//! left alone, the remote version is fetched aside (see [`Config::conflicts_dir`]) & the
+//! conflict is recorded until the files are equal, or one of them has seen all modifications
+//! of the other one, again - or resolved by the user or the strategy of its groups
+//! (see `conflict`).
+//!
+//! Deleted files (see `index`) are compared by the versions of their tombstones: a deletion
+//! which has seen all modifications of the other file deletes it - pushed to the peer, or
+//! learned from it - while a file modified since (or concurrently) is brought back instead,
+//! modifications win over deletions.
+//!
+//! Synced roots (`dsync-cli root sync`) are matched by paths relative to the roots first: files
+//! present on both sides are paired (& synced as above from then on, even when moved), files
+//! present on one side only are copied over to the other one. Files gone on either side are
+//! left alone, paths ignored by the local root (see `ignore_rules`) are not fetched.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use dsync_proto::model::common::{RootFileDescription, Tombstone};
+use dsync_proto::services::file_transfer::{
+    FileDeleteRequest, FileDescribeRequest, RootDescribeRequest, TransferSubmitRequest,
+    file_transfer_service_client::FileTransferServiceClient,
+};
+use tokio::{
+    sync::broadcast::error::{RecvError, TryRecvError},
+    time::MissedTickBehavior,
+};
+use tonic::transport::Channel;
+
+use crate::server::{
+    config::Config,
+    conflict::{self, ConflictSide, Resolution},
+    context::ServerContext,
+    data::source::sqlite::database::models::{
+        FileConflictRow, FileTombstoneRow, FilesLocalRow, FilesTrackedRow, HostsRow,
+        RootsTrackedRow, SyncRootRow,
+    },
+    ignore_rules::RootIgnore,
+    index::{self, LocalFileState},
+    peers::PeerEvent,
+    service::{
+        file_transfer,
+        tools::{self, net::peer},
+    },
+    version_vector::{Causality, VersionVector},
+};
+
+/// How long connecting to the peer might take during a sync.
+const SYNC_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What has to be done to bring a pair of files in sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SyncAction {
+    InSync,
+    /// The remote file has seen all modifications of the local one & more.
+    Pull,
+    /// The local file has seen all modifications of the remote one & more.
+    Push,
+    /// Both files have modifications the other one has not seen.
+    Conflict,
+    /// The remote file has been deleted after all modifications of the local one.
+    DeleteLocal,
+    /// The local file has been deleted after all modifications of the remote one.
+    DeleteRemote,
+}
+
+pub(crate) fn decide(
+    local_hash: &str,
+    remote_hash: &str,
+    local_version: &VersionVector,
+    remote_version: &VersionVector,
+) -> SyncAction {
+    if local_hash == remote_hash {
+        return SyncAction::InSync;
+    }
+
+    match local_version.compare(remote_version) {
+        Causality::Before => SyncAction::Pull,
+        Causality::After => SyncAction::Push,
+        // Equal versions of different contents have lost track of some modification,
+        // neither of them might be trusted.
+        Causality::Equal | Causality::Concurrent => SyncAction::Conflict,
+    }
+}
+
+/// Same as [`decide`] for pairs with a deleted file, none when both files are there.
+/// Versions of deleted files are versions of their tombstones.
+pub(crate) fn decide_deletion(
+    local_deleted: bool,
+    remote_deleted: bool,
+    local_version: &VersionVector,
+    remote_version: &VersionVector,
+) -> Option<SyncAction> {
+    let causality = local_version.compare(remote_version);
+    match (local_deleted, remote_deleted) {
+        (false, false) => None,
+        (true, true) => Some(SyncAction::InSync),
+        (true, false) => Some(match causality {
+            Causality::After | Causality::Equal => SyncAction::DeleteRemote,
+            Causality::Before | Causality::Concurrent => SyncAction::Pull,
+        }),
+        (false, true) => Some(match causality {
+            Causality::Before | Causality::Equal => SyncAction::DeleteLocal,
+            Causality::After | Causality::Concurrent => SyncAction::Push,
+        }),
+    }
+}
+
+/// Sync all pairings every `sync_interval`, whenever tracked files change
+/// (see [`FileEvent`](crate::server::watcher::FileEvent)), whenever a sync is requested
+/// (see [`ServerContext::sync_requested`]) & whenever a peer comes online.
+pub(crate) fn spawn_file_sync(ctx: Arc<ServerContext>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ctx.cfg.sync_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut peer_events = ctx.peers.subscribe();
+        let mut file_events = ctx.watcher.subscribe();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = ctx.sync_requested.notified() => {}
+                // Peers synced with the file should get the change right away.
+                event = file_events.recv() => {
+                    if let Err(RecvError::Closed) = event {
+                        unreachable!("The watcher lives as long as the context");
+                    }
+                    // Changes come in bursts (e.g. a rescan), a single sync covers all of them.
+                    while !matches!(file_events.try_recv(), Err(TryRecvError::Empty | TryRecvError::Closed)) {}
+                }
+                // Syncs which have failed while the peer was offline are not left for the next tick.
+                event = peer_events.recv() => match event {
+                    Ok(PeerEvent::Online { .. }) | Err(RecvError::Lagged(_)) => {}
+                    Ok(PeerEvent::Offline { .. }) => continue,
+                    Err(RecvError::Closed) => unreachable!("The peer registry lives as long as the context"),
+                }
+            }
+
+            if let Err(err) = sync_all(&ctx).await {
+                log::warn!(target: "pslog", "File sync failed: {err}");
+            }
+        }
+    });
+}
+
+async fn sync_all(ctx: &ServerContext) -> anyhow::Result<()> {
+    sync_roots(ctx).await?;
+
+    let tracked_files = ctx.repo.fetch_tracked_files().await?;
+    if tracked_files.is_empty() {
+        return Ok(());
+    }
+
+    let local_files = ctx.repo.fetch_local_files().await?;
+
+    for tracked in tracked_files {
+        let Some(local_file) = local_files.iter().find(|file| file.id == tracked.local_id) else {
+            continue;
+        };
+
+        match sync_file(ctx, local_file, &tracked).await {
+            // Conflicts are logged as they are detected.
+            Ok(SyncAction::InSync | SyncAction::Conflict) => {}
+            Ok(action) => {
+                log::info!(
+                    target: "pslog",
+                    "Syncing {} with file {} of host {}: {action:?}",
+                    local_file.file_path,
+                    tracked.remote_id,
+                    tracked.peer_uuid
+                );
+            }
+            Err(err) => {
+                log::debug!(
+                    target: "pslog",
+                    "Failed to sync {} with file {} of host {}: {err}",
+                    local_file.file_path,
+                    tracked.remote_id,
+                    tracked.peer_uuid
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the local file with the remote one & start the transfer, if needed.
+/// Transfers complete in the background, the pairing is marked as synced once
+/// a later run finds both files equal.
+async fn sync_file(
+    ctx: &ServerContext,
+    local_file: &FilesLocalRow,
+    tracked: &FilesTrackedRow,
+) -> anyhow::Result<SyncAction> {
+    let mut local_tombstone = None;
+    let (local_hash, local_version) = match index::refresh_local_file(ctx, local_file).await? {
+        LocalFileState::Unchanged => (local_file.hash_sha1.clone(), local_file.version_vector()),
+        LocalFileState::Modified { hash_sha1, version } => (hash_sha1, version),
+        LocalFileState::Removed | LocalFileState::Gone => {
+            // Files gone before tombstones were recorded are left alone.
+            let tombstone = ctx
+                .repo
+                .fetch_file_tombstones()
+                .await?
+                .into_iter()
+                .find(|tombstone| tombstone.file_id == local_file.id)
+                .ok_or_else(|| anyhow::anyhow!("Local file is gone"))?;
+            let version = tombstone.version_vector();
+            local_tombstone = Some(tombstone);
+            (local_file.hash_sha1.clone(), version)
+        }
+    };
+
+    let peer_host = ctx.repo.fetch_host_by_uuid(&tracked.peer_uuid).await?;
+    let channel = peer::connect_to_host(ctx, &peer_host, SYNC_CONNECT_TIMEOUT).await?;
+    let mut client = FileTransferServiceClient::new(channel);
+
+    let remote_file = client
+        .file_describe(FileDescribeRequest {
+            file_id: tracked.remote_id,
+        })
+        .await?
+        .into_inner()
+        .file
+        .ok_or_else(|| anyhow::anyhow!("Peer has not described the file"))?;
+    let remote_version = VersionVector::from(remote_file.version);
+
+    let action = decide_deletion(
+        local_tombstone.is_some(),
+        remote_file.tombstone.is_some(),
+        &local_version,
+        &remote_version,
+    )
+    .unwrap_or_else(|| {
+        decide(
+            &local_hash,
+            &remote_file.hash_sha1,
+            &local_version,
+            &remote_version,
+        )
+    });
+
+    let conflict = ctx
+        .repo
+        .fetch_file_conflicts()
+        .await?
+        .into_iter()
+        .find(|conflict| {
+            conflict.file_id == tracked.local_id && conflict.peer_uuid == tracked.peer_uuid
+        });
+    if let Some(conflict) = &conflict
+        && action != SyncAction::Conflict
+    {
+        ctx.repo
+            .delete_file_conflicts(tracked.local_id, Some(&tracked.peer_uuid))
+            .await?;
+        // Might have not arrived at all.
+        let _ = tokio::fs::remove_file(&conflict.copy_path).await;
+        log::info!(
+            target: "pslog",
+            "Conflict of {} with file {} of host {} has been resolved",
+            local_file.file_path,
+            tracked.remote_id,
+            tracked.peer_uuid
+        );
+    }
+
+    match action {
+        // Deleted on both sides.
+        SyncAction::InSync if local_tombstone.is_some() => {}
+        SyncAction::InSync => {
+            let merged_version = local_version.clone().merged(&remote_version);
+            if merged_version != local_version {
+                ctx.repo
+                    .update_local_file_version(local_file.id, &merged_version.to_string())
+                    .await?;
+            }
+            if tracked.last_synced_hash.as_deref() != Some(local_hash.as_str()) {
+                ctx.repo
+                    .update_tracked_file_hash(tracked.local_id, &tracked.peer_uuid, &local_hash)
+                    .await?;
+            }
+        }
+        SyncAction::Pull => {
+            let result = fetch_remote_file(
+                ctx,
+                &mut client,
+                &tracked.peer_uuid,
+                remote_file.file_path,
+                &local_file.file_path,
+            )
+            .await;
+            if let Err(err) = result {
+                log::warn!(
+                    target: "pslog",
+                    "Failed to fetch file {} of host {}: {err}",
+                    tracked.remote_id,
+                    tracked.peer_uuid
+                );
+            }
+        }
+        SyncAction::Push => {
+            // Brought back, the peer has to take it for newer than the deletion.
+            if remote_file.tombstone.is_some() {
+                conflict::keep_local_version(ctx, local_file.id, &local_version, &remote_version)
+                    .await?;
+            }
+            file_transfer::send_file(
+                ctx,
+                &peer_host,
+                local_file.file_path.clone(),
+                remote_file.file_path,
+            )
+            .await
+            .map_err(|status| anyhow::anyhow!("Peer refused the file: {status}"))?;
+        }
+        SyncAction::DeleteLocal => {
+            let tombstone = remote_file
+                .tombstone
+                .ok_or_else(|| anyhow::anyhow!("Peer has not described the deletion"))?;
+            index::apply_tombstone(
+                ctx,
+                local_file,
+                &FileTombstoneRow {
+                    file_id: local_file.id,
+                    host_uuid: tombstone.host_uuid,
+                    version: local_version.merged(&remote_version).to_string(),
+                    deleted_at: tombstone.deleted_at,
+                },
+            )
+            .await?;
+        }
+        SyncAction::DeleteRemote => {
+            let tombstone = local_tombstone
+                .ok_or_else(|| anyhow::anyhow!("Local file has not been deleted"))?;
+            client
+                .file_delete(FileDeleteRequest {
+                    file_id: tracked.remote_id,
+                    version: HashMap::from(&local_version),
+                    tombstone: Some(Tombstone {
+                        host_uuid: tombstone.host_uuid,
+                        deleted_at: tombstone.deleted_at,
+                    }),
+                })
+                .await
+                .map_err(|status| anyhow::anyhow!("Peer refused the deletion: {status}"))?;
+        }
+        SyncAction::Conflict => {
+            let local_mtime = tokio::fs::metadata(&local_file.file_path)
+                .await
+                .map_or(local_file.mtime, |metadata| {
+                    tools::file::modification_time(&metadata)
+                });
+            let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+            let resolution = conflict::group_resolution(
+                ctx,
+                local_file.id,
+                ConflictSide {
+                    host_uuid: &local_uuid,
+                    mtime: local_mtime,
+                },
+                ConflictSide {
+                    host_uuid: &tracked.peer_uuid,
+                    mtime: remote_file.mtime,
+                },
+            )
+            .await?;
+            if let Some(resolution) = resolution {
+                if let Some(conflict) = &conflict {
+                    ctx.repo
+                        .delete_file_conflicts(conflict.file_id, Some(&conflict.peer_uuid))
+                        .await?;
+                    let _ = tokio::fs::remove_file(&conflict.copy_path).await;
+                }
+                return resolve_by_group(
+                    ctx,
+                    &mut client,
+                    &peer_host,
+                    local_file,
+                    (&local_version, &remote_version),
+                    remote_file.file_path,
+                    resolution,
+                )
+                .await;
+            }
+
+            // Fetched again whenever the remote file changes, until resolved.
+            if conflict
+                .as_ref()
+                .is_some_and(|conflict| conflict.remote_hash == remote_file.hash_sha1)
+            {
+                return Ok(action);
+            }
+
+            let copy_path = conflict_copy_path(&ctx.cfg, local_file, &tracked.peer_uuid);
+            let copy_path = copy_path
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid path of the conflicted copy"))?;
+            ctx.repo
+                .save_file_conflict(&FileConflictRow {
+                    file_id: tracked.local_id,
+                    peer_uuid: tracked.peer_uuid.clone(),
+                    remote_hash: remote_file.hash_sha1.clone(),
+                    remote_version: remote_version.to_string(),
+                    copy_path: copy_path.to_owned(),
+                    detected_at: conflict
+                        .as_ref()
+                        .map_or_else(tools::time::get_current_timestamp, |conflict| {
+                            conflict.detected_at
+                        }),
+                })
+                .await?;
+            if conflict.is_none() {
+                log::warn!(
+                    target: "pslog",
+                    "File {} & file {} of host {} have been modified concurrently, keeping \
+                     both; the remote version is fetched to {copy_path}",
+                    local_file.file_path,
+                    tracked.remote_id,
+                    tracked.peer_uuid
+                );
+            }
+            let result = fetch_remote_file(
+                ctx,
+                &mut client,
+                &tracked.peer_uuid,
+                remote_file.file_path,
+                copy_path,
+            )
+            .await;
+            if let Err(err) = result {
+                log::warn!(
+                    target: "pslog",
+                    "Failed to fetch the conflicted copy of file {} of host {}: {err}",
+                    tracked.remote_id,
+                    tracked.peer_uuid
+                );
+            }
+        }
+    }
+
+    Ok(action)
+}
+
+/// Resolve the conflict by the strategy of the groups of the local file, without keeping both
+/// versions: the one which wins replaces the other one right away.
+async fn resolve_by_group(
+    ctx: &ServerContext,
+    client: &mut FileTransferServiceClient<Channel>,
+    peer_host: &HostsRow,
+    local_file: &FilesLocalRow,
+    (local_version, remote_version): (&VersionVector, &VersionVector),
+    remote_file_path: String,
+    resolution: Resolution,
+) -> anyhow::Result<SyncAction> {
+    let log_kept = |kept: &str| {
+        log::info!(
+            target: "pslog",
+            "File {} & its version of host {} have been modified concurrently, keeping the \
+             {kept} version by the strategy of its groups",
+            local_file.file_path,
+            peer_host.uuid,
+        );
+    };
+
+    match resolution {
+        Resolution::Local => {
+            log_kept("local");
+            conflict::keep_local_version(ctx, local_file.id, local_version, remote_version).await?;
+            file_transfer::send_file(
+                ctx,
+                peer_host,
+                local_file.file_path.clone(),
+                remote_file_path,
+            )
+            .await
+            .map_err(|status| anyhow::anyhow!("Peer refused the file: {status}"))?;
+            Ok(SyncAction::Push)
+        }
+        Resolution::Remote => {
+            log_kept("remote");
+            fetch_remote_file(
+                ctx,
+                client,
+                &peer_host.uuid,
+                remote_file_path,
+                &local_file.file_path,
+            )
+            .await?;
+            Ok(SyncAction::Pull)
+        }
+        // Strategies pick a single version, both are kept only on the user's request.
+        Resolution::KeepBoth => anyhow::bail!(
+            "Strategy of the groups of file {} asks to keep both versions",
+            local_file.file_path
+        ),
+    }
+}
+
+/// Ask the peer to push its file to the local path.
+async fn fetch_remote_file(
+    ctx: &ServerContext,
+    client: &mut FileTransferServiceClient<Channel>,
+    peer_uuid: &str,
+    file_path_src: String,
+    file_path_dst: &str,
+) -> anyhow::Result<()> {
+    // The peer pushes the file, which has to be recognized as requested by us.
+    ctx.expected_transfers.expect(peer_uuid, file_path_dst);
+    let local_uuid = ctx.repo.fetch_local_server_info().await?.uuid;
+    let result = client
+        .transfer_submit(TransferSubmitRequest {
+            host_org_uuid: local_uuid.clone(),
+            host_dst_uuid: local_uuid,
+            file_path_src,
+            file_path_dst: file_path_dst.to_owned(),
+        })
+        .await;
+    if let Err(status) = result {
+        let _ = ctx.expected_transfers.take(peer_uuid, file_path_dst);
+        anyhow::bail!("Peer refused to send the file: {status}");
+    }
+
+    Ok(())
+}
+
+/// Where the remote version of the conflicted file is kept until the conflict is resolved.
+fn conflict_copy_path(cfg: &Config, local_file: &FilesLocalRow, peer_uuid: &str) -> PathBuf {
+    let file_name = Path::new(&local_file.file_path)
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or_default();
+    cfg.conflicts_dir()
+        .join(format!("{}-{peer_uuid}-{file_name}", local_file.id))
+}
+
+async fn sync_roots(ctx: &ServerContext) -> anyhow::Result<()> {
+    let tracked_roots = ctx.repo.fetch_tracked_roots().await?;
+    if tracked_roots.is_empty() {
+        return Ok(());
+    }
+
+    let roots = ctx.repo.fetch_sync_roots().await?;
+
+    for tracked in tracked_roots {
+        let Some(root) = roots.iter().find(|root| root.id == tracked.root_id) else {
+            continue;
+        };
+
+        if let Err(err) = sync_root(ctx, root, &tracked).await {
+            log::debug!(
+                target: "pslog",
+                "Failed to sync root {} with root {} of host {}: {err}",
+                root.path,
+                tracked.remote_root_id,
+                tracked.peer_uuid
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Pair files present in both roots & copy over those present in one of them only.
+async fn sync_root(
+    ctx: &ServerContext,
+    root: &SyncRootRow,
+    tracked: &RootsTrackedRow,
+) -> anyhow::Result<()> {
+    let peer_host = ctx.repo.fetch_host_by_uuid(&tracked.peer_uuid).await?;
+    let channel = peer::connect_to_host(ctx, &peer_host, SYNC_CONNECT_TIMEOUT).await?;
+    let mut client = FileTransferServiceClient::new(channel);
+
+    let response = client
+        .root_describe(RootDescribeRequest {
+            root_id: tracked.remote_root_id,
+        })
+        .await?
+        .into_inner();
+    let remote_root = response
+        .root
+        .ok_or_else(|| anyhow::anyhow!("Peer has not described the root"))?;
+
+    let mut remote_files: HashMap<String, RootFileDescription> = HashMap::new();
+    for file in response.files {
+        if !is_relative_normal(&file.relative_path) {
+            log::warn!(
+                target: "pslog",
+                "Host {} listed {:?} in root {}, skipping",
+                tracked.peer_uuid,
+                file.relative_path,
+                tracked.remote_root_id
+            );
+            continue;
+        }
+        remote_files.insert(file.relative_path.clone(), file);
+    }
+
+    // Paired files are synced through their pairings, wherever they have been moved to.
+    let pairings: Vec<FilesTrackedRow> = ctx
+        .repo
+        .fetch_tracked_files()
+        .await?
+        .into_iter()
+        .filter(|file| file.peer_uuid == tracked.peer_uuid)
+        .collect();
+    remote_files.retain(|_, file| {
+        !pairings
+            .iter()
+            .any(|pairing| pairing.remote_id == file.file_id)
+    });
+
+    let local_files = ctx.repo.fetch_local_files().await?;
+    let local_files = local_files
+        .iter()
+        .filter(|file| file.root_id == Some(root.id))
+        .filter(|file| !pairings.iter().any(|pairing| pairing.local_id == file.id))
+        .filter_map(|file| {
+            let relative_path = root.relative_path(&file.file_path)?;
+            Some((relative_path.to_str()?.to_owned(), file))
+        });
+
+    for (relative_path, local_file) in local_files {
+        match remote_files.remove(&relative_path) {
+            Some(remote_file) => {
+                if local_file.gone || remote_file.gone {
+                    continue;
+                }
+                let last_synced_hash = (local_file.hash_sha1 == remote_file.hash_sha1)
+                    .then(|| local_file.hash_sha1.clone());
+                ctx.repo
+                    .save_tracked_file(&FilesTrackedRow {
+                        local_id: local_file.id,
+                        peer_uuid: tracked.peer_uuid.clone(),
+                        remote_id: remote_file.file_id,
+                        last_synced_hash,
+                    })
+                    .await?;
+                log::info!(
+                    target: "pslog",
+                    "Paired {} with file {} of host {}",
+                    local_file.file_path,
+                    remote_file.file_id,
+                    tracked.peer_uuid
+                );
+            }
+            None if !local_file.gone => {
+                let file_path_dst = Path::new(&remote_root.path).join(&relative_path);
+                let Some(file_path_dst) = file_path_dst.to_str() else {
+                    continue;
+                };
+                log::info!(
+                    target: "pslog",
+                    "Sending {} to root {} of host {}",
+                    local_file.file_path,
+                    tracked.remote_root_id,
+                    tracked.peer_uuid
+                );
+                // The peer might ignore the path, the other files are sent anyway.
+                let result = file_transfer::send_file(
+                    ctx,
+                    &peer_host,
+                    local_file.file_path.clone(),
+                    file_path_dst.to_owned(),
+                )
+                .await;
+                if let Err(status) = result {
+                    log::debug!(
+                        target: "pslog",
+                        "Host {} refused {}: {status}",
+                        tracked.peer_uuid,
+                        local_file.file_path
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    // Whatever is left is not tracked locally.
+    let mut ignore = RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns);
+    for (relative_path, remote_file) in remote_files {
+        let file_path_dst = Path::new(&root.path).join(&relative_path);
+        // Either being received right now, or about to be indexed.
+        if remote_file.gone || file_path_dst.exists() {
+            continue;
+        }
+        if ignore.is_ignored(&file_path_dst, false) {
+            log::trace!(target: "pslog", "Not fetching ignored {relative_path}");
+            continue;
+        }
+        let Some(file_path_dst) = file_path_dst.to_str() else {
+            continue;
+        };
+        let file_path_src = Path::new(&remote_root.path).join(&relative_path);
+        let Some(file_path_src) = file_path_src.to_str() else {
+            continue;
+        };
+
+        log::info!(
+            target: "pslog",
+            "Fetching {relative_path} from root {} of host {}",
+            tracked.remote_root_id,
+            tracked.peer_uuid
+        );
+        // A file the peer refuses does not hold back the others.
+        let result = fetch_remote_file(
+            ctx,
+            &mut client,
+            &tracked.peer_uuid,
+            file_path_src.to_owned(),
+            file_path_dst,
+        )
+        .await;
+        if let Err(err) = result {
+            log::warn!(
+                target: "pslog",
+                "Failed to fetch {relative_path} from root {} of host {}: {err}",
+                tracked.remote_root_id,
+                tracked.peer_uuid
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Relative path which stays within the root, as listed by peers.
+fn is_relative_normal(relative_path: &str) -> bool {
+    let path = Path::new(relative_path);
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::server::{
+        data::source::sqlite::database::models::Access, test_utils, watcher::FileEvent,
+    };
+
+    #[test]
+    fn test_decide() {
+        let base = VersionVector::initial("host-a");
+        let modified_a = base.clone().bumped("host-a");
+        let modified_b = base.clone().bumped("host-b");
+
+        assert_eq!(
+            decide("a", "a", &modified_a, &modified_b),
+            SyncAction::InSync
+        );
+        assert_eq!(decide("a", "b", &base, &modified_b), SyncAction::Pull);
+        assert_eq!(decide("a", "b", &modified_a, &base), SyncAction::Push);
+        assert_eq!(
+            decide("a", "b", &modified_a, &modified_b),
+            SyncAction::Conflict
+        );
+        assert_eq!(decide("a", "b", &base, &base), SyncAction::Conflict);
+        // Files of unrelated histories.
+        assert_eq!(
+            decide(
+                "a",
+                "b",
+                &VersionVector::initial("host-a"),
+                &VersionVector::initial("host-b")
+            ),
+            SyncAction::Conflict
+        );
+    }
+
+    #[test]
+    fn test_decide_deletion() {
+        let base = VersionVector::initial("host-a");
+        let deleted_a = base.clone().bumped("host-a");
+        let modified_b = base.clone().bumped("host-b");
+
+        assert_eq!(decide_deletion(false, false, &base, &base), None);
+        assert_eq!(
+            decide_deletion(true, true, &deleted_a, &deleted_a),
+            Some(SyncAction::InSync)
+        );
+        assert_eq!(
+            decide_deletion(true, false, &deleted_a, &base),
+            Some(SyncAction::DeleteRemote)
+        );
+        assert_eq!(
+            decide_deletion(false, true, &base, &deleted_a),
+            Some(SyncAction::DeleteLocal)
+        );
+        // Modifications win over deletions they have not been seen by.
+        assert_eq!(
+            decide_deletion(true, false, &deleted_a, &modified_b),
+            Some(SyncAction::Pull)
+        );
+        assert_eq!(
+            decide_deletion(false, true, &modified_b, &deleted_a),
+            Some(SyncAction::Push)
+        );
+    }
+
+    #[test]
+    fn test_is_relative_normal() {
+        assert!(is_relative_normal("a.txt"));
+        assert!(is_relative_normal("dir/a.txt"));
+        assert!(!is_relative_normal(""));
+        assert!(!is_relative_normal("/etc/passwd"));
+        assert!(!is_relative_normal("../a.txt"));
+        assert!(!is_relative_normal("dir/../../a.txt"));
+        assert!(!is_relative_normal("./a.txt"));
+    }
+
+    /// Local file paired with a newer remote file of a peer, which is offline as the sync task
+    /// starts. The first sync has failed, the next tick is an hour away.
+    struct PendingPull {
+        ctx: Arc<ServerContext>,
+        peer_ctx: Arc<ServerContext>,
+        peer_port: u16,
+        local_path: PathBuf,
+        dir: PathBuf,
+    }
+
+    impl PendingPull {
+        async fn new() -> Self {
+            let dir = test_utils::temp_dir();
+            let mut cfg = test_utils::test_config();
+            cfg.sync_interval = Duration::from_secs(60 * 60);
+            let ctx = test_utils::test_context(cfg).await;
+            let peer_ctx = test_utils::host_context("peer", test_utils::test_config()).await;
+
+            let local_port = test_utils::serve_peer_services(ctx.clone()).await;
+            // Stands in for the peer until it comes online, drops connections without a handshake.
+            let offline_peer = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+                .await
+                .unwrap();
+            let peer_port = offline_peer.local_addr().unwrap().port();
+            test_utils::add_paired_host(
+                &ctx,
+                "peer",
+                peer_port,
+                &peer_ctx.identity.public_key_hex(),
+            )
+            .await;
+            test_utils::add_paired_host(
+                &peer_ctx,
+                test_utils::LOCAL_UUID,
+                local_port,
+                &ctx.identity.public_key_hex(),
+            )
+            .await;
+
+            let local_path = dir.join("local.txt");
+            let local_file =
+                test_utils::track_file(&ctx, &local_path, "", &VersionVector::default()).await;
+            let remote_file = test_utils::track_file(
+                &peer_ctx,
+                &dir.join("remote.txt"),
+                "remote",
+                &VersionVector::initial("peer"),
+            )
+            .await;
+            peer_ctx
+                .repo
+                .save_file_access(
+                    &remote_file.file_path,
+                    test_utils::LOCAL_UUID,
+                    Access {
+                        read: true,
+                        write: false,
+                    },
+                )
+                .await
+                .unwrap();
+            ctx.repo
+                .save_tracked_file(&FilesTrackedRow {
+                    local_id: local_file.id,
+                    peer_uuid: "peer".to_owned(),
+                    remote_id: remote_file.id,
+                    last_synced_hash: None,
+                })
+                .await
+                .unwrap();
+
+            spawn_file_sync(ctx.clone());
+            let (first_attempt, _) =
+                tokio::time::timeout(Duration::from_secs(5), offline_peer.accept())
+                    .await
+                    .expect("The first sync should run as the task starts")
+                    .unwrap();
+            drop((first_attempt, offline_peer));
+            assert_eq!(std::fs::read_to_string(&local_path).unwrap(), "");
+
+            Self {
+                ctx,
+                peer_ctx,
+                peer_port,
+                local_path,
+                dir,
+            }
+        }
+
+        async fn start_peer(&self) {
+            let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, self.peer_port))
+                .await
+                .unwrap();
+            test_utils::serve_peer_services_on(self.peer_ctx.clone(), listener);
+        }
+
+        async fn wait_for_pull(self) {
+            test_utils::wait_until(|| {
+                std::fs::read_to_string(&self.local_path).unwrap() == "remote"
+            })
+            .await;
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_runs_when_peer_comes_online() {
+        let pull = PendingPull::new().await;
+
+        pull.start_peer().await;
+        pull.ctx.peers.record_success(
+            "peer",
+            Duration::from_millis(1),
+            tools::time::get_current_timestamp(),
+        );
+
+        pull.wait_for_pull().await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_runs_on_file_events() {
+        let pull = PendingPull::new().await;
+
+        pull.start_peer().await;
+        pull.ctx.watcher.publish(FileEvent::Modified {
+            file_id: 1,
+            file_path: pull.local_path.to_str().unwrap().to_owned(),
+            hash_sha1: String::new(),
+        });
+
+        pull.wait_for_pull().await;
+    }
+}
@@ -0,0 +1,183 @@
+//! Helpers shared by tests of the server: a context backed by an in-memory database.
+
+use std::{
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use diesel::{Connection, SqliteConnection};
+use dsync_proto::{
+    model::server::HostInfo,
+    services::{
+        file_transfer::file_transfer_service_server::FileTransferServiceServer,
+        host_discovery::host_discovery_service_server::HostDiscoveryServiceServer,
+    },
+};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use crate::server::{
+    config::{Config, DiscoveryConfig, defaults},
+    context::ServerContext,
+    data::{
+        repo::{DataRepository, MainDataRepository},
+        source::{
+            SqliteDataSource,
+            sqlite::database::models::{FilesLocalFragmentInsert, FilesLocalRow, HostsRow},
+        },
+    },
+    identity::Identity,
+    service::{
+        file_transfer::FileTransferServiceImpl,
+        host_discovery::HostDiscoveryServiceImpl,
+        tools::{
+            self,
+            net::{addr_discovery, tls},
+        },
+    },
+    version_vector::VersionVector,
+};
+
+pub(crate) const LOCAL_UUID: &str = "00000000-0000-0000-0000-000000000000";
+
+pub(crate) fn test_config() -> Config {
+    Config {
+        port: defaults::SERVER_PORT,
+        control_port: defaults::CONTROL_PORT,
+        control_socket: None,
+        database_url: PathBuf::from(":memory:"),
+        discovery: DiscoveryConfig::default(),
+        heartbeat_interval: defaults::HEARTBEAT_INTERVAL,
+        blocklist: Vec::new(),
+        sync_interval: defaults::SYNC_INTERVAL,
+        rescan_interval: defaults::RESCAN_INTERVAL,
+        tombstone_retention: defaults::TOMBSTONE_RETENTION,
+        ignore_patterns: Vec::new(),
+    }
+}
+
+/// Context of a freshly initialized server, paired with itself like on start.
+pub(crate) async fn test_context(cfg: Config) -> Arc<ServerContext> {
+    host_context(LOCAL_UUID, cfg).await
+}
+
+/// Same as [`test_context`], for a server with the uuid (e.g. a peer in the same test).
+pub(crate) async fn host_context(local_uuid: &str, cfg: Config) -> Arc<ServerContext> {
+    let conn = SqliteConnection::establish(":memory:").unwrap();
+    let source = SqliteDataSource::new(conn, || host_row(local_uuid, false))
+        .await
+        .unwrap();
+    let repo: Arc<dyn DataRepository> = Arc::new(MainDataRepository::new(Box::new(source)));
+
+    let identity = Identity::generate().unwrap();
+    repo.pair_host(local_uuid, &identity.public_key_hex())
+        .await
+        .unwrap();
+    let discovery = addr_discovery::make_discovery_backend(&cfg);
+
+    Arc::new(ServerContext::new(cfg, repo, discovery, identity))
+}
+
+/// Store a paired remote host with the key. Returns the stored row.
+pub(crate) async fn add_paired_host(
+    ctx: &ServerContext,
+    uuid: &str,
+    port: u16,
+    public_key: &str,
+) -> HostsRow {
+    let mut host = host_row(uuid, true);
+    host.port = port.into();
+    ctx.repo.insert_hosts(&[host]).await.unwrap();
+    ctx.repo.pair_host(uuid, public_key).await.unwrap();
+    ctx.repo.fetch_host_by_uuid(uuid).await.unwrap()
+}
+
+pub(crate) fn host_row(uuid: &str, is_remote: bool) -> HostsRow {
+    HostsRow::from_host_info(
+        &HostInfo {
+            uuid: uuid.to_owned(),
+            name: uuid.to_owned(),
+            hostname: uuid.to_owned(),
+            address: defaults::ADDR_LOOPBACK_V4.to_owned(),
+            port: defaults::SERVER_PORT.into(),
+            ..Default::default()
+        },
+        is_remote,
+        None,
+    )
+}
+
+/// Serve the services for peers of the context over TLS on a free loopback port. Returns the port.
+pub(crate) async fn serve_peer_services(ctx: Arc<ServerContext>) -> u16 {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    serve_peer_services_on(ctx, listener)
+}
+
+/// Same as [`serve_peer_services`], on the listener.
+pub(crate) fn serve_peer_services_on(ctx: Arc<ServerContext>, listener: TcpListener) -> u16 {
+    let port = listener.local_addr().unwrap().port();
+    let tls_config = dsync_shared::conn::tls::server_config(ctx.identity.tls()).unwrap();
+
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(HostDiscoveryServiceServer::new(
+                HostDiscoveryServiceImpl::new(ctx.clone()),
+            ))
+            .add_service(FileTransferServiceServer::new(
+                FileTransferServiceImpl::new(ctx),
+            ))
+            .serve_with_incoming(tls::incoming(listener, tls_config)),
+    );
+    port
+}
+
+/// Fresh directory for files of the test.
+pub(crate) fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsync-test-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write the file & track it with the version. Returns the stored row.
+pub(crate) async fn track_file(
+    ctx: &ServerContext,
+    file_path: &Path,
+    contents: &str,
+    version: &VersionVector,
+) -> FilesLocalRow {
+    std::fs::write(file_path, contents).unwrap();
+    let file_path = file_path.to_str().unwrap().to_owned();
+    let hash_sha1 = tools::file::compute_sha1_hash_from_file(&file_path, None).unwrap();
+    let metadata = std::fs::metadata(&file_path).unwrap();
+    ctx.repo
+        .save_local_files(&[FilesLocalFragmentInsert::from_metadata(
+            file_path.clone(),
+            hash_sha1,
+            &metadata,
+            None,
+            version,
+        )])
+        .await
+        .unwrap();
+
+    ctx.repo
+        .fetch_local_files()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|file| file.file_path == file_path)
+        .unwrap()
+}
+
+/// Poll the condition until it holds, failing the test after a while.
+pub(crate) async fn wait_until(mut condition: impl FnMut() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Condition not met in time");
+}
@@ -0,0 +1,61 @@
+//! Transfers into this host requested by the local user.
+//!
+//! Remote hosts need write access to push files here. When the local user pulls a file from
+//! a remote host, the source pushes it all the same - the pull is recorded before it is submitted,
+//! so that the push it triggers is recognized & accepted w/o any grant.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// How long the source host might take to start the requested transfer.
+const EXPECTED_TRANSFER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// In-memory registry of expected incoming transfers, keyed by source host uuid & destination path.
+pub(crate) struct ExpectedTransfers {
+    expected: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl ExpectedTransfers {
+    pub(crate) fn new() -> Self {
+        Self {
+            expected: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a transfer of `file_path_dst` from host `src_uuid`, requested by the local user.
+    pub(crate) fn expect(&self, src_uuid: &str, file_path_dst: &str) {
+        let mut expected = self.expected.lock().expect("Lock poisoned");
+        expected.retain(|_, since| since.elapsed() < EXPECTED_TRANSFER_TIMEOUT);
+        expected.insert(
+            (src_uuid.to_owned(), file_path_dst.to_owned()),
+            Instant::now(),
+        );
+    }
+
+    /// Whether the transfer has been expected. Each expectation is consumed by the first match.
+    pub(crate) fn take(&self, src_uuid: &str, file_path_dst: &str) -> bool {
+        let mut expected = self.expected.lock().expect("Lock poisoned");
+        expected
+            .remove(&(src_uuid.to_owned(), file_path_dst.to_owned()))
+            .is_some_and(|since| since.elapsed() < EXPECTED_TRANSFER_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ExpectedTransfers;
+
+    #[test]
+    fn test_expected_transfer_is_taken_once() {
+        let transfers = ExpectedTransfers::new();
+        transfers.expect("host-a", "/tmp/file");
+
+        assert!(!transfers.take("host-b", "/tmp/file"));
+        assert!(!transfers.take("host-a", "/tmp/other"));
+        assert!(transfers.take("host-a", "/tmp/file"));
+        assert!(!transfers.take("host-a", "/tmp/file"));
+    }
+}
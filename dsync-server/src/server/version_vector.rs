@@ -0,0 +1,165 @@
+//! Version vectors of tracked files.
+//!
+//! Every host counts the modifications of a file it has made itself; the vector holds the
+//! counters of all hosts the file has passed through. A file whose vector is ahead of the other
+//! one for every host has seen all of its modifications & might replace it. When each of them
+//! has seen a modification the other has not, the files have been modified concurrently.
+
+use std::{cmp::Ordering, collections::BTreeMap, collections::HashMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// How two versions of a file relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Causality {
+    Equal,
+    /// The other version has seen all modifications of this one & more.
+    Before,
+    /// This version has seen all modifications of the other one & more.
+    After,
+    /// Both versions have modifications the other one has not seen.
+    Concurrent,
+}
+
+/// Modification counters of a file, keyed by host uuid.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VersionVector(BTreeMap<String, u64>);
+
+impl VersionVector {
+    /// Version of a file first seen by the host.
+    pub(crate) fn initial(host_uuid: &str) -> Self {
+        let mut version = Self::default();
+        version.bump(host_uuid);
+        version
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn get(&self, host_uuid: &str) -> u64 {
+        self.0.get(host_uuid).copied().unwrap_or(0)
+    }
+
+    /// Count a modification made by the host.
+    pub(crate) fn bump(&mut self, host_uuid: &str) {
+        *self.0.entry(host_uuid.to_owned()).or_insert(0) += 1;
+    }
+
+    pub(crate) fn bumped(mut self, host_uuid: &str) -> Self {
+        self.bump(host_uuid);
+        self
+    }
+
+    /// Version which has seen the modifications of both.
+    pub(crate) fn merged(mut self, other: &VersionVector) -> Self {
+        for (host_uuid, &counter) in &other.0 {
+            let entry = self.0.entry(host_uuid.clone()).or_insert(0);
+            *entry = (*entry).max(counter);
+        }
+        self
+    }
+
+    pub(crate) fn compare(&self, other: &VersionVector) -> Causality {
+        let mut ordering = Ordering::Equal;
+        let host_uuids = self.0.keys().chain(other.0.keys());
+
+        for host_uuid in host_uuids {
+            match (ordering, self.get(host_uuid).cmp(&other.get(host_uuid))) {
+                (_, Ordering::Equal) => {}
+                (Ordering::Equal, counter_ordering) => ordering = counter_ordering,
+                (ordering, counter_ordering) if ordering != counter_ordering => {
+                    return Causality::Concurrent;
+                }
+                _ => {}
+            }
+        }
+
+        match ordering {
+            Ordering::Equal => Causality::Equal,
+            Ordering::Less => Causality::Before,
+            Ordering::Greater => Causality::After,
+        }
+    }
+}
+
+impl From<HashMap<String, u64>> for VersionVector {
+    fn from(counters: HashMap<String, u64>) -> Self {
+        // Zero counters carry no information, leaving them in would break equality.
+        Self(
+            counters
+                .into_iter()
+                .filter(|(_, counter)| *counter > 0)
+                .collect(),
+        )
+    }
+}
+
+impl From<&VersionVector> for HashMap<String, u64> {
+    fn from(version: &VersionVector) -> Self {
+        version.0.clone().into_iter().collect()
+    }
+}
+
+/// Stored as a JSON object.
+impl fmt::Display for VersionVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(&self.0).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl FromStr for VersionVector {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_version_vector_compare() {
+        let a1 = VersionVector::initial("a");
+        let a2 = a1.clone().bumped("a");
+        let a1b1 = a1.clone().bumped("b");
+
+        assert_eq!(a1.compare(&a1.clone()), Causality::Equal);
+        assert_eq!(a1.compare(&a2), Causality::Before);
+        assert_eq!(a2.compare(&a1), Causality::After);
+        assert_eq!(a2.compare(&a1b1), Causality::Concurrent);
+        assert_eq!(a1b1.compare(&a2), Causality::Concurrent);
+        assert_eq!(VersionVector::default().compare(&a1), Causality::Before);
+
+        let merged = a2.clone().merged(&a1b1);
+        assert_eq!(merged.get("a"), 2);
+        assert_eq!(merged.get("b"), 1);
+        assert_eq!(merged.compare(&a2), Causality::After);
+        assert_eq!(merged.compare(&a1b1), Causality::After);
+    }
+
+    #[test]
+    fn test_version_vector_roundtrip() {
+        let version = VersionVector::initial("a").bumped("b").bumped("a");
+        assert_eq!(version.to_string(), r#"{"a":2,"b":1}"#);
+        assert_eq!(
+            version.to_string().parse::<VersionVector>().unwrap(),
+            version
+        );
+        assert_eq!(
+            "{}".parse::<VersionVector>().unwrap(),
+            VersionVector::default()
+        );
+
+        let counters = HashMap::from([("a".to_owned(), 2), ("c".to_owned(), 0)]);
+        let version = VersionVector::from(counters);
+        assert_eq!(
+            HashMap::from(&version),
+            HashMap::from([("a".to_owned(), 2)])
+        );
+    }
+}
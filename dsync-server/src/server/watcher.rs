@@ -0,0 +1,480 @@
+//! Keeping hashes of tracked files up to date, as the files change on disk.
+//!
+//! Parent directories of tracked files are watched (inotify, through `notify`) rather than the
+//! files themselves, so that files replaced by editors (written to a temporary file, which is
+//! renamed over the original) are noticed as well. Events are coalesced per path & handled once
+//! the path has been quiet for a moment: changed files are rehashed (see [`index`]), moved files
+//! keep their id, groups, grants & sync pairings under the new path.
+//!
+//! Every directory of sync roots is watched, except for ignored ones (see `ignore_rules`).
+//! Files showing up in the roots are indexed right away.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher,
+    event::{AccessKind, AccessMode, ModifyKind, RenameMode},
+};
+use tokio::{
+    sync::{Notify, broadcast, mpsc},
+    time::Instant,
+};
+
+use crate::server::{
+    context::ServerContext,
+    data::source::sqlite::database::models::FilesLocalRow,
+    ignore_rules::{IGNORE_FILE_NAME, RootIgnore},
+    index::{self, LocalFileState},
+};
+
+/// How long a path has to be quiet before its events are handled.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+/// Files written continuously are handled at least this often.
+const MAX_DEBOUNCE_DELAY: Duration = Duration::from_secs(5);
+/// How many events might be buffered for a slow subscriber before it starts losing them.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Changes of tracked files, as observed on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FileEvent {
+    /// The file has shown up in a sync root & is tracked from now on.
+    Added { file_id: i32, file_path: String },
+    /// Contents of the file have changed, the new hash is already stored.
+    Modified {
+        file_id: i32,
+        file_path: String,
+        hash_sha1: String,
+    },
+    /// The file has been moved, the new path is already stored.
+    Renamed {
+        file_id: i32,
+        from: String,
+        to: String,
+    },
+    /// The file is gone. It stays tracked, in case it shows up again.
+    Removed { file_id: i32, file_path: String },
+}
+
+/// Handle of the watcher task, shared through the server context.
+pub(crate) struct FileWatcher {
+    events: broadcast::Sender<FileEvent>,
+    refresh_requested: Notify,
+}
+
+impl FileWatcher {
+    pub(crate) fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            events,
+            refresh_requested: Notify::new(),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<FileEvent> {
+        self.events.subscribe()
+    }
+
+    /// Tracked files have been added or removed, the watched directories have to follow.
+    pub(crate) fn refresh(&self) {
+        self.refresh_requested.notify_one();
+    }
+
+    pub(crate) fn publish(&self, event: FileEvent) {
+        log::debug!(target: "pslog", "File event: {event:?}");
+        // No subscribers is fine.
+        let _ = self.events.send(event);
+    }
+}
+
+/// What has happened to a path since its events were last handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PendingChange {
+    /// Created, modified or removed - whichever it is, the path has to be looked at.
+    Changed,
+    /// Moved to the given path.
+    RenamedTo(PathBuf),
+}
+
+#[derive(Debug, Default)]
+struct PendingChanges {
+    changes: HashMap<PathBuf, PendingChange>,
+    /// Events have been lost, all tracked files & sync roots have to be looked at.
+    rescan: bool,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
+}
+
+impl PendingChanges {
+    fn deadline(&self) -> Option<Instant> {
+        Some(std::cmp::min(
+            self.last_event? + DEBOUNCE_DELAY,
+            self.first_event? + MAX_DEBOUNCE_DELAY,
+        ))
+    }
+
+    fn record(&mut self, event: Event) {
+        if event.need_rescan() {
+            self.rescan = true;
+        }
+
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                if let [from, to] = event.paths.as_slice() {
+                    self.record_rename(from.clone(), to.clone());
+                }
+            }
+            EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(_)
+            | EventKind::Access(AccessKind::Close(AccessMode::Write))
+            | EventKind::Any
+            | EventKind::Other => {
+                for path in event.paths {
+                    self.changes.insert(path, PendingChange::Changed);
+                }
+            }
+            EventKind::Access(_) => return,
+        }
+
+        let now = Instant::now();
+        self.first_event.get_or_insert(now);
+        self.last_event = Some(now);
+    }
+
+    fn record_rename(&mut self, from: PathBuf, to: PathBuf) {
+        // A path moved again before its first move has been handled - the original path
+        // is what the database knows.
+        let origin = self
+            .changes
+            .iter()
+            .find(|(_, change)| **change == PendingChange::RenamedTo(from.clone()))
+            .map(|(origin, _)| origin.clone());
+
+        match origin {
+            Some(origin) => {
+                self.changes.remove(&from);
+                self.changes
+                    .insert(origin, PendingChange::RenamedTo(to.clone()));
+            }
+            None => {
+                self.changes
+                    .insert(from, PendingChange::RenamedTo(to.clone()));
+            }
+        }
+        // Whatever has been at the destination is replaced.
+        self.changes.insert(to, PendingChange::Changed);
+    }
+}
+
+/// Watch tracked files & keep their hashes & paths up to date, see the module docs.
+/// Runs until the server stops, unless the platform watcher can not be created.
+pub(crate) fn spawn_file_watcher(ctx: Arc<ServerContext>) {
+    let (events_tx, events_rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        // The receiver is gone only when the server stops.
+        let _ = events_tx.send(result);
+    });
+
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            log::warn!(target: "pslog", "Failed to create file watcher, changes of tracked files will not be noticed: {err}");
+            return;
+        }
+    };
+
+    tokio::spawn(run_file_watcher(ctx, watcher, events_rx));
+}
+
+async fn run_file_watcher(
+    ctx: Arc<ServerContext>,
+    mut watcher: impl Watcher + Send,
+    mut events_rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
+) {
+    let mut watched_dirs = HashSet::new();
+    let mut pending = PendingChanges::default();
+    // Watch whatever is tracked at start.
+    ctx.watcher.refresh();
+
+    loop {
+        let deadline = pending.deadline();
+
+        tokio::select! {
+            _ = ctx.watcher.refresh_requested.notified() => {
+                update_watched_dirs(&ctx, &mut watcher, &mut watched_dirs).await;
+            }
+            Some(result) = events_rx.recv() => match result {
+                Ok(event) => pending.record(event),
+                Err(err) => log::warn!(target: "pslog", "File watcher error: {err}"),
+            },
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                let changes = std::mem::take(&mut pending);
+                if let Err(err) = handle_changes(&ctx, changes).await {
+                    log::warn!(target: "pslog", "Failed to handle changes of tracked files: {err}");
+                }
+            }
+        }
+    }
+}
+
+/// Watch directories of sync roots, parent directories of other tracked files & nothing else.
+async fn update_watched_dirs(
+    ctx: &ServerContext,
+    watcher: &mut impl Watcher,
+    watched_dirs: &mut HashSet<PathBuf>,
+) {
+    let (local_files, roots) = match (
+        ctx.repo.fetch_local_files().await,
+        ctx.repo.fetch_sync_roots().await,
+    ) {
+        (Ok(local_files), Ok(roots)) => (local_files, roots),
+        (Err(err), _) | (_, Err(err)) => {
+            log::warn!(target: "pslog", "Failed to fetch tracked files to watch: {err}");
+            return;
+        }
+    };
+
+    // Watching roots recursively would watch ignored directories as well, which might be many
+    // (`node_modules/`) & run into the limit of watches.
+    let mut wanted_dirs = HashSet::new();
+    for root in &roots {
+        match index::walk_root(ctx, root, PathBuf::from(&root.path)).await {
+            Ok(tree) => wanted_dirs.extend(tree.dirs),
+            Err(err) => log::debug!(target: "pslog", "Failed to walk {}: {err}", root.path),
+        }
+    }
+    wanted_dirs.extend(
+        local_files
+            .iter()
+            .filter(|file| file.root_id.is_none())
+            .filter_map(|file| Path::new(&file.file_path).parent())
+            .map(Path::to_path_buf),
+    );
+
+    for dir in watched_dirs.difference(&wanted_dirs) {
+        // Fails when the directory is gone, the watch is gone with it anyway.
+        let _ = watcher.unwatch(dir);
+    }
+    watched_dirs.retain(|dir| wanted_dirs.contains(dir));
+
+    for dir in wanted_dirs {
+        if watched_dirs.contains(&dir) {
+            continue;
+        }
+        match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                watched_dirs.insert(dir);
+            }
+            // Retried on the next refresh.
+            Err(err) => log::debug!(target: "pslog", "Failed to watch {dir:?}: {err}"),
+        }
+    }
+}
+
+async fn handle_changes(ctx: &ServerContext, pending: PendingChanges) -> anyhow::Result<()> {
+    let roots = ctx.repo.fetch_sync_roots().await?;
+    let mut local_files: HashMap<PathBuf, FilesLocalRow> = ctx
+        .repo
+        .fetch_local_files()
+        .await?
+        .into_iter()
+        .map(|file| (PathBuf::from(&file.file_path), file))
+        .collect();
+
+    let mut ignores: HashMap<i32, RootIgnore> = roots
+        .iter()
+        .map(|root| {
+            let ignore = RootIgnore::new(&root.path, &ctx.cfg.ignore_patterns);
+            (root.id, ignore)
+        })
+        .collect();
+
+    let mut changed_paths = HashSet::new();
+    let mut renamed = false;
+
+    for (path, change) in pending.changes {
+        let PendingChange::RenamedTo(to) = change else {
+            changed_paths.insert(path);
+            continue;
+        };
+
+        // The path might be a directory, everything tracked under it has moved with it.
+        let moved_paths: Vec<PathBuf> = local_files
+            .keys()
+            .filter(|file_path| file_path.starts_with(&path))
+            .cloned()
+            .collect();
+
+        for from in moved_paths {
+            let new_path = match from.strip_prefix(&path) {
+                Ok(relative_path) if !relative_path.as_os_str().is_empty() => {
+                    to.join(relative_path)
+                }
+                _ => to.clone(),
+            };
+            let file = local_files
+                .remove(&from)
+                .expect("Collected from the map above");
+
+            // Moved over another tracked file, which has been replaced - that is a change
+            // of the other file & the moved one is gone. Same for files moved out of their
+            // sync root or to an ignored path, the root does not have them anymore.
+            let left_root = file.root_id.is_some_and(|root_id| {
+                let in_root = roots
+                    .iter()
+                    .any(|root| root.id == root_id && root.relative_path(&new_path).is_some());
+                let ignored = ignores
+                    .get_mut(&root_id)
+                    .is_some_and(|ignore| ignore.is_ignored(&new_path, false));
+                !in_root || ignored
+            });
+            let (Some(to_str), false, false) = (
+                new_path.to_str(),
+                local_files.contains_key(&new_path),
+                left_root,
+            ) else {
+                local_files.insert(from.clone(), file);
+                changed_paths.insert(from);
+                continue;
+            };
+
+            ctx.repo.update_local_file_path(file.id, to_str).await?;
+            log::info!(target: "pslog", "Tracked file {} has been moved to {to_str}", file.file_path);
+            ctx.watcher.publish(FileEvent::Renamed {
+                file_id: file.id,
+                from: file.file_path.clone(),
+                to: to_str.to_owned(),
+            });
+            renamed = true;
+
+            local_files.insert(
+                new_path.clone(),
+                FilesLocalRow {
+                    file_path: to_str.to_owned(),
+                    ..file
+                },
+            );
+            changed_paths.insert(new_path);
+        }
+    }
+
+    let mut modified = false;
+    if pending.rescan {
+        changed_paths.extend(local_files.keys().cloned());
+        for root in &roots {
+            match index::index_root(ctx, root).await {
+                Ok(added) => modified |= added > 0,
+                Err(err) => {
+                    log::warn!(target: "pslog", "Failed to index sync root {}: {err}", root.path)
+                }
+            }
+        }
+    }
+
+    let mut refresh = renamed;
+    let mut reindexed_roots = HashSet::new();
+    for path in changed_paths {
+        let root = index::find_root(&roots, &path);
+        // Changed patterns might both add & remove files of the root.
+        if let Some(root) = root
+            && path
+                .file_name()
+                .is_some_and(|name| name == IGNORE_FILE_NAME)
+        {
+            reindexed_roots.insert(root.id);
+        }
+
+        let Some(file) = local_files.get(&path) else {
+            if let Some(root) = root {
+                // New directories have to be watched.
+                refresh |= path.is_dir();
+                match index::index_root_path(ctx, root, path).await {
+                    Ok(added) => modified |= added > 0,
+                    Err(err) => log::warn!(target: "pslog", "Failed to index new files: {err}"),
+                }
+            }
+            continue;
+        };
+        match index::refresh_local_file(ctx, file).await {
+            Ok(state) => {
+                modified |= matches!(
+                    state,
+                    LocalFileState::Modified { .. } | LocalFileState::Removed
+                )
+            }
+            Err(err) => {
+                log::warn!(target: "pslog", "Failed to handle change of {}: {err}", file.file_path)
+            }
+        }
+    }
+
+    for root in roots
+        .iter()
+        .filter(|root| reindexed_roots.contains(&root.id))
+    {
+        match index::index_root(ctx, root).await {
+            Ok(added) => modified |= added > 0,
+            Err(err) => {
+                log::warn!(target: "pslog", "Failed to index sync root {}: {err}", root.path)
+            }
+        }
+        refresh = true;
+    }
+
+    if refresh || modified {
+        ctx.watcher.refresh();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use notify::event::CreateKind;
+
+    use super::*;
+
+    fn rename(from: &str, to: &str) -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(from.into())
+            .add_path(to.into())
+    }
+
+    #[test]
+    fn test_pending_changes_coalesce_renames() {
+        let mut pending = PendingChanges::default();
+        assert!(pending.deadline().is_none());
+
+        // Editor saving through a temporary file.
+        pending.record(Event::new(EventKind::Create(CreateKind::File)).add_path("/d/a.tmp".into()));
+        pending.record(rename("/d/a.tmp", "/d/a"));
+        assert_eq!(
+            pending.changes.get(Path::new("/d/a")),
+            Some(&PendingChange::Changed)
+        );
+
+        // Moved twice before being handled.
+        pending.record(rename("/d/b", "/d/c"));
+        pending.record(rename("/d/c", "/d/e"));
+        assert_eq!(
+            pending.changes.get(Path::new("/d/b")),
+            Some(&PendingChange::RenamedTo("/d/e".into()))
+        );
+        assert_eq!(pending.changes.get(Path::new("/d/c")), None);
+        assert_eq!(
+            pending.changes.get(Path::new("/d/e")),
+            Some(&PendingChange::Changed)
+        );
+
+        // Reads are not changes.
+        let mut pending = PendingChanges::default();
+        pending.record(Event::new(EventKind::Access(AccessKind::Read)).add_path("/d/a".into()));
+        assert!(pending.changes.is_empty());
+        assert!(pending.deadline().is_none());
+    }
+}
@@ -1,5 +1,9 @@
+pub mod tls;
+
 use std::{
-    net::{Ipv4Addr, SocketAddrV4},
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
     time::Duration,
 };
 
@@ -7,16 +11,54 @@ use dsync_proto::services::{
     server_control::server_control_service_client::ServerControlServiceClient,
     user_agent::user_agent_service_client::UserAgentServiceClient,
 };
-use tonic::transport::Channel;
+use hyper_util::rt::TokioIo;
+use rustls::pki_types::ServerName;
+use tokio::net::{TcpStream, UnixStream};
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Channel, Uri};
+
+use crate::{DEFAULT_CONTROL_PORT, default::CONTROL_SOCKET_NAME};
+use tls::{PeerVerification, PublicKey, TlsIdentity};
+
+/// Where the control services of the local daemon are reachable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlAddr {
+    /// Unix socket, tried first when set.
+    pub socket: Option<PathBuf>,
+    /// Loopback TCP port, used when there is no socket or connecting to it fails.
+    pub port: u16,
+}
 
-use crate::DEFAULT_SERVER_PORT;
+impl Default for ControlAddr {
+    fn default() -> Self {
+        Self {
+            socket: default_control_socket(),
+            port: DEFAULT_CONTROL_PORT,
+        }
+    }
+}
+
+/// Default path of the control socket, none when `XDG_RUNTIME_DIR` is not set.
+pub fn default_control_socket() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("dsync")
+        .get_runtime_file(CONTROL_SOCKET_NAME)
+        .ok()
+}
 
+/// Uri of the control services of the local daemon.
 pub fn local_server_uri(port: Option<u16>) -> tonic::transport::Uri {
-    let authority = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port.unwrap_or(DEFAULT_SERVER_PORT));
+    let authority = SocketAddr::new(
+        Ipv4Addr::LOCALHOST.into(),
+        port.unwrap_or(DEFAULT_CONTROL_PORT),
+    );
     create_server_uri(authority)
 }
 
-pub fn create_server_uri(socket: SocketAddrV4) -> tonic::transport::Uri {
+/// Works for both address families, IPv6 addresses are bracketed (with the zone id, if any).
+///
+/// The scheme is `http`, because TLS (for peer channels) is set up by `ChannelFactory` itself,
+/// below HTTP/2.
+pub fn create_server_uri(socket: SocketAddr) -> tonic::transport::Uri {
     tonic::transport::Uri::builder()
         .scheme("http")
         .authority(socket.to_string())
@@ -27,52 +69,118 @@ pub fn create_server_uri(socket: SocketAddrV4) -> tonic::transport::Uri {
         })
 }
 
-pub async fn user_agent_service_conn_factory(
-    uri: tonic::transport::Uri,
-) -> Result<UserAgentServiceClient<Channel>, tonic::transport::Error> {
-    UserAgentServiceClient::connect(uri).await
-}
-
 pub struct ChannelFactory {}
 
 impl ChannelFactory {
-    pub async fn channel(
-        uri: tonic::transport::Uri,
-    ) -> Result<tonic::transport::Channel, tonic::Status> {
-        ChannelFactory::create_channel(uri, None).await
+    /// Plaintext channel to the control services of the local daemon.
+    /// They listen on loopback only, so the traffic does not leave the host.
+    pub async fn channel(uri: Uri) -> Result<Channel, tonic::Status> {
+        let endpoint = ChannelFactory::create_endpoint(uri, None);
+        endpoint.connect().await.map_err(|err| {
+            tonic::Status::unavailable(format!(
+                "Failed to connect to an endpoint: {endpoint:?} with err: {err}"
+            ))
+        })
     }
 
-    pub async fn channel_with_timeout(
-        uri: tonic::transport::Uri,
-        timeout: Duration,
-    ) -> Result<tonic::transport::Channel, tonic::Status> {
-        ChannelFactory::create_channel(uri, Some(timeout)).await
+    /// Plaintext channel over a Unix socket. Access is guarded by the permissions of the socket file.
+    pub async fn unix_channel(socket: &Path) -> Result<Channel, tonic::Status> {
+        let socket = socket.to_owned();
+        // The uri is required by tonic, but never dialed.
+        let endpoint = ChannelFactory::create_endpoint(local_server_uri(None), None);
+
+        endpoint
+            .connect_with_connector(tower::service_fn(move |_: Uri| {
+                let socket = socket.clone();
+                async move {
+                    let stream = UnixStream::connect(socket).await?;
+                    Ok::<_, std::io::Error>(TokioIo::new(stream))
+                }
+            }))
+            .await
+            .map_err(|err| {
+                tonic::Status::unavailable(format!(
+                    "Failed to connect to the control socket with err: {err}"
+                ))
+            })
     }
 
-    async fn create_channel(
-        uri: tonic::transport::Uri,
-        timeout: Option<Duration>,
-    ) -> Result<tonic::transport::Channel, tonic::Status> {
-        let mut endpoint = match tonic::transport::Endpoint::new(uri.clone()) {
-            Ok(endpoint) => endpoint,
-            Err(err) => {
-                return Err(tonic::Status::unavailable(format!(
-                    "Failed to create endpoint from uri {uri} with error: {err}"
-                )));
-            }
+    /// Channel to the control services of the local daemon, over the socket if possible.
+    pub async fn control_channel(addr: &ControlAddr) -> Result<Channel, tonic::Status> {
+        let socket_error = match &addr.socket {
+            Some(socket) => match ChannelFactory::unix_channel(socket).await {
+                Ok(channel) => return Ok(channel),
+                Err(status) => Some(format!("{} ({})", socket.display(), status.message())),
+            },
+            None => None,
         };
 
-        endpoint = if let Some(timeout) = timeout {
-            endpoint.connect_timeout(timeout)
-        } else {
-            endpoint
+        ChannelFactory::channel(local_server_uri(Some(addr.port)))
+            .await
+            .map_err(|status| match socket_error {
+                Some(socket_error) => tonic::Status::unavailable(format!(
+                    "Daemon is reachable neither at {socket_error}, nor at port {} ({})",
+                    addr.port,
+                    status.message()
+                )),
+                None => status,
+            })
+    }
+
+    /// Channel to another daemon, authenticated with `identity`. Returns the channel along with
+    /// the key the daemon presented.
+    pub async fn peer_channel(
+        addr: SocketAddr,
+        identity: &TlsIdentity,
+        verification: PeerVerification,
+        timeout: Duration,
+    ) -> Result<(Channel, PublicKey), tonic::Status> {
+        let endpoint = ChannelFactory::create_endpoint(create_server_uri(addr), Some(timeout));
+
+        let observed_key = Arc::new(OnceLock::new());
+        let tls_config =
+            tls::client_config(identity, verification, observed_key.clone()).map_err(|err| {
+                tonic::Status::internal(format!("Failed to create TLS config with err: {err}"))
+            })?;
+        let connector = TlsConnector::from(Arc::new(tls_config));
+
+        let channel = endpoint
+            .connect_with_connector(tower::service_fn(move |uri: Uri| {
+                let connector = connector.clone();
+                async move {
+                    let authority = uri.authority().map(ToString::to_string).ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Missing authority")
+                    })?;
+                    let stream = TcpStream::connect(authority).await?;
+                    stream.set_nodelay(true)?;
+                    let server_name =
+                        ServerName::try_from(tls::SERVER_NAME).expect("Valid server name");
+                    let stream = connector.connect(server_name, stream).await?;
+                    Ok::<_, std::io::Error>(TokioIo::new(stream))
+                }
+            }))
+            .await
+            .map_err(|err| {
+                tonic::Status::unavailable(format!(
+                    "Failed to connect to an endpoint: {endpoint:?} with err: {err}"
+                ))
+            })?;
+
+        let Some(public_key) = observed_key.get().copied() else {
+            return Err(tonic::Status::internal(
+                "TLS handshake finished w/o verifying the key of the peer",
+            ));
         };
 
-        endpoint.connect().await.map_err(|err| {
-            tonic::Status::unavailable(format!(
-                "Failed to connect to an endpoint: {endpoint:?} with err: {err}"
-            ))
-        })
+        Ok((channel, public_key))
+    }
+
+    fn create_endpoint(uri: Uri, timeout: Option<Duration>) -> tonic::transport::Endpoint {
+        let endpoint = tonic::transport::Endpoint::from(uri);
+        match timeout {
+            Some(timeout) => endpoint.connect_timeout(timeout),
+            None => endpoint,
+        }
     }
 }
 
@@ -80,18 +188,16 @@ pub struct ServiceConnFactory {}
 
 impl ServiceConnFactory {
     pub async fn local_user_agent_service(
-        port: Option<u16>,
+        addr: &ControlAddr,
     ) -> Result<UserAgentServiceClient<Channel>, tonic::Status> {
-        let port = port.unwrap_or(DEFAULT_SERVER_PORT);
-        let channel = ChannelFactory::channel(local_server_uri(Some(port))).await?;
+        let channel = ChannelFactory::control_channel(addr).await?;
         Ok(UserAgentServiceClient::new(channel))
     }
 
     pub async fn local_server_control_service(
-        port: Option<u16>,
+        addr: &ControlAddr,
     ) -> Result<ServerControlServiceClient<Channel>, tonic::Status> {
-        let port = port.unwrap_or(DEFAULT_SERVER_PORT);
-        let channel = ChannelFactory::channel(local_server_uri(Some(port))).await?;
+        let channel = ChannelFactory::control_channel(addr).await?;
         Ok(ServerControlServiceClient::new(channel))
     }
 }
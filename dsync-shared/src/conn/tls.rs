@@ -0,0 +1,207 @@
+//! TLS for connections between daemons.
+//!
+//! Certificates are self-signed ed25519 ones, there is no certificate authority. Instead, peers
+//! are recognized by the public key of their certificate, which is pinned on the first contact
+//! (or when pairing) & has to match on every following connection.
+
+use std::sync::{Arc, OnceLock};
+
+use rustls::{
+    CertificateError, DigitallySignedStruct, DistinguishedName, Error, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{CryptoProvider, WebPkiSupportedAlgorithms},
+    pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime},
+    server::danger::{ClientCertVerified, ClientCertVerifier},
+};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Certificates are not issued for any particular name, but rustls requires one.
+pub const SERVER_NAME: &str = "dsync";
+
+const ALPN_H2: &[u8] = b"h2";
+
+/// Length of an ed25519 public key.
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+
+pub type PublicKey = [u8; PUBLIC_KEY_LENGTH];
+
+/// Certificate & private key presented by this side of the connection.
+#[derive(Debug, Clone)]
+pub struct TlsIdentity {
+    /// DER encoded certificate.
+    pub certificate: Vec<u8>,
+    /// DER encoded PKCS#8 private key.
+    pub private_key: Vec<u8>,
+}
+
+/// How the key presented by a daemon we connect to is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerVerification {
+    /// The key must be the given one.
+    Pinned(PublicKey),
+    /// Any key is accepted. Meant for the first contact, the caller is expected to pin the key.
+    TrustOnFirstUse,
+}
+
+/// Ed25519 public key of the certificate, none for certificates with other keys.
+pub fn certificate_public_key(certificate: &[u8]) -> Option<PublicKey> {
+    let (_, certificate) = X509Certificate::from_der(certificate).ok()?;
+    let public_key = certificate.public_key();
+    if public_key.algorithm.algorithm != x509_parser::oid_registry::OID_SIG_ED25519 {
+        return None;
+    }
+    public_key.subject_public_key.data.as_ref().try_into().ok()
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// Client side config. Key of the daemon is stored in `observed_key` once the handshake succeeds.
+pub fn client_config(
+    identity: &TlsIdentity,
+    verification: PeerVerification,
+    observed_key: Arc<OnceLock<PublicKey>>,
+) -> Result<rustls::ClientConfig, Error> {
+    let provider = provider();
+    let verifier = Arc::new(PeerKeyVerifier {
+        verification,
+        observed_key,
+        algorithms: provider.signature_verification_algorithms,
+    });
+
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_client_auth_cert(
+            vec![CertificateDer::from(identity.certificate.clone())],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(identity.private_key.clone())),
+        )?;
+    config.alpn_protocols = vec![ALPN_H2.to_vec()];
+    Ok(config)
+}
+
+/// Server side config. Clients have to present a certificate with an ed25519 key;
+/// it is up to the services to check whether the key belongs to a known host.
+pub fn server_config(identity: &TlsIdentity) -> Result<rustls::ServerConfig, Error> {
+    let provider = provider();
+    let verifier = Arc::new(AnyClientKeyVerifier {
+        algorithms: provider.signature_verification_algorithms,
+    });
+
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(
+            vec![CertificateDer::from(identity.certificate.clone())],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(identity.private_key.clone())),
+        )?;
+    config.alpn_protocols = vec![ALPN_H2.to_vec()];
+    Ok(config)
+}
+
+#[derive(Debug)]
+struct PeerKeyVerifier {
+    verification: PeerVerification,
+    observed_key: Arc<OnceLock<PublicKey>>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PeerKeyVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, Error> {
+        let Some(public_key) = certificate_public_key(end_entity) else {
+            return Err(Error::InvalidCertificate(CertificateError::BadEncoding));
+        };
+
+        if let PeerVerification::Pinned(pinned_key) = self.verification
+            && pinned_key != public_key
+        {
+            return Err(Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ));
+        }
+
+        let _ = self.observed_key.set(public_key);
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![SignatureScheme::ED25519]
+    }
+}
+
+#[derive(Debug)]
+struct AnyClientKeyVerifier {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ClientCertVerifier for AnyClientKeyVerifier {
+    fn client_auth_mandatory(&self) -> bool {
+        true
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> Result<ClientCertVerified, Error> {
+        // Possession of the key is proven by the handshake signature, see below.
+        match certificate_public_key(end_entity) {
+            Some(_) => Ok(ClientCertVerified::assertion()),
+            None => Err(Error::InvalidCertificate(CertificateError::BadEncoding)),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![SignatureScheme::ED25519]
+    }
+}
@@ -1 +1,5 @@
 pub const DEFAULT_SERVER_PORT: u16 = 50051;
+/// Port of the control services (user agent & server control), bound to loopback only.
+pub const DEFAULT_CONTROL_PORT: u16 = 50050;
+/// Name of the control services socket, in the dsync directory under `XDG_RUNTIME_DIR`.
+pub const CONTROL_SOCKET_NAME: &str = "control.sock";
@@ -2,5 +2,7 @@ pub mod conn;
 pub mod core;
 pub mod default;
 pub mod model;
+pub mod protocol;
 
-pub use default::DEFAULT_SERVER_PORT;
+pub use default::{DEFAULT_CONTROL_PORT, DEFAULT_SERVER_PORT};
+pub use protocol::PROTOCOL_VERSION;
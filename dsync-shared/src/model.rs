@@ -159,7 +159,13 @@ pub fn parse_file_source_path_spec(spec: &str) -> Result<PathSpec, FileSourcePar
     }
 }
 
+/// Anything but `localhost` & local ids is a display name or an alias of the host,
+/// these are told apart by the server.
 pub fn parse_file_source_host_spec(spec: &str) -> Result<HostSpec, FileSourceParseError> {
+    if spec.is_empty() {
+        return Err(FileSourceParseError::InvalidSpec);
+    }
+
     if spec == "localhost" {
         return Ok(HostSpec {
             kind: Some(host_spec::Kind::LocalHost(0)),
@@ -177,3 +183,70 @@ pub fn parse_file_source_host_spec(spec: &str) -> Result<HostSpec, FileSourcePar
         kind: Some(host_spec::Kind::Name(spec.to_owned())),
     })
 }
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum HostNameError {
+    #[error("Host name can not be empty")]
+    Empty,
+
+    #[error("`{0}` is reserved for the local host")]
+    Reserved(String),
+
+    #[error("`{0}` would be taken for a local id")]
+    Numeric(String),
+
+    #[error("Host name `{0}` can not contain `@`")]
+    InvalidChar(String),
+}
+
+/// Display names & aliases set by the user have to parse back into the name kind
+/// of `HostSpec`, see `parse_file_source_host_spec`.
+pub fn validate_host_name(name: &str) -> Result<(), HostNameError> {
+    if name.trim().is_empty() {
+        return Err(HostNameError::Empty);
+    }
+    if name == "localhost" {
+        return Err(HostNameError::Reserved(name.to_owned()));
+    }
+    if name.parse::<i32>().is_ok() {
+        return Err(HostNameError::Numeric(name.to_owned()));
+    }
+    if name.contains('@') {
+        return Err(HostNameError::InvalidChar(name.to_owned()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use dsync_proto::services::user_agent::host_spec;
+
+    use super::{HostNameError, parse_file_source_host_spec, validate_host_name};
+
+    #[test]
+    fn test_valid_host_names_parse_as_names() {
+        for name in ["laptop", "vm-b", "localhost.localdomain"] {
+            assert_eq!(validate_host_name(name), Ok(()));
+            let spec = parse_file_source_host_spec(name).unwrap();
+            assert_eq!(spec.kind, Some(host_spec::Kind::Name(name.to_owned())));
+        }
+    }
+
+    #[test]
+    fn test_invalid_host_names() {
+        assert_eq!(validate_host_name(" "), Err(HostNameError::Empty));
+        assert!(matches!(
+            validate_host_name("localhost"),
+            Err(HostNameError::Reserved(_))
+        ));
+        assert!(matches!(
+            validate_host_name("12"),
+            Err(HostNameError::Numeric(_))
+        ));
+        assert!(matches!(
+            validate_host_name("a@b"),
+            Err(HostNameError::InvalidChar(_))
+        ));
+    }
+}
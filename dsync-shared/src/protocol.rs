@@ -0,0 +1,20 @@
+/// Version of the peer-to-peer protocol spoken by this build. Bump it on any
+/// backwards incompatible change of the peer facing services.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest protocol version this build still talks to. Peers which do not report
+/// any version at all (predating the negotiation) are treated as version 0.
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// Optional features a daemon might support. Names are `<kind>:<variant>` for features
+/// with alternatives (e.g. `hash:sha1`, `compression:zstd`) & plain names otherwise
+/// (e.g. `delta-transfer`). Unknown capabilities of peers must be ignored.
+pub mod capability {
+    /// Files are identified by SHA-1 of their content.
+    pub const HASH_SHA1: &str = "hash:sha1";
+
+    pub const HASH_PREFIX: &str = "hash:";
+}
+
+/// Capabilities of this build.
+pub const CAPABILITIES: &[&str] = &[capability::HASH_SHA1];